@@ -0,0 +1,52 @@
+//! Instruction and cache event counts of the round loop and the key schedule, deterministic
+//! across runs unlike wall clock benches.
+//!
+//! Run with `cargo bench --features iai --bench iai`, needs valgrind and a matching
+//! `iai-callgrind-runner` (`cargo install iai-callgrind-runner --version 0.14`).
+
+use std::hint::black_box;
+
+use cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+use iai_callgrind::{library_benchmark, library_benchmark_group, main};
+use rc5::RC5_32_12_16;
+
+const KEY: [u8; 16] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
+];
+
+#[library_benchmark]
+fn key_schedule() -> RC5_32_12_16 {
+    black_box(RC5_32_12_16::new(black_box(&KEY.into())))
+}
+
+#[library_benchmark]
+fn key_schedule_batch() -> [RC5_32_12_16; 8] {
+    black_box(RC5_32_12_16::new_batch(black_box(&[KEY.into(); 8])))
+}
+
+#[library_benchmark]
+#[bench::one(1)]
+#[bench::many(64)]
+fn encrypt(blocks: usize) -> Vec<[u8; 8]> {
+    let rc5 = RC5_32_12_16::new(&KEY.into());
+    let mut data = vec![[0x5A; 8].into(); blocks];
+
+    rc5.encrypt_blocks(black_box(&mut data));
+    data.into_iter().map(Into::into).collect()
+}
+
+#[library_benchmark]
+#[bench::one(1)]
+#[bench::many(64)]
+fn decrypt(blocks: usize) -> Vec<[u8; 8]> {
+    let rc5 = RC5_32_12_16::new(&KEY.into());
+    let mut data = vec![[0x5A; 8].into(); blocks];
+
+    rc5.decrypt_blocks(black_box(&mut data));
+    data.into_iter().map(Into::into).collect()
+}
+
+library_benchmark_group!(name = key_setup; benchmarks = key_schedule, key_schedule_batch);
+library_benchmark_group!(name = rounds; benchmarks = encrypt, decrypt);
+
+main!(library_benchmark_groups = key_setup, rounds);
@@ -0,0 +1,44 @@
+//! Compare the CTR and OFB keystreams and the CTR pipeline byte for byte with the generic `ctr`
+//! and `ofb` crates layered over the single block API.
+//!
+//! Input: 16 byte key, 8 byte IV, 1 byte chunk size, data.
+
+#![no_main]
+
+use cipher::{KeyInit, KeyIvInit, StreamCipher};
+use libfuzzer_sys::fuzz_target;
+use rc5::{CtrPipeline, Keystream, KeystreamMode, RC5_32_12_16};
+
+fuzz_target!(|input: &[u8]| {
+    if input.len() < 25 {
+        return;
+    }
+    let (key, rest) = input.split_at(16);
+    let (iv, rest) = rest.split_at(8);
+    let (chunk_size, data) = (rest[0] as usize, &rest[1..]);
+
+    let rc5 = RC5_32_12_16::new(key.into());
+    let iv = iv.into();
+
+    let xor = |mode| {
+        let mut keystream = vec![0; data.len()];
+        Keystream::new(&rc5, mode, iv).fill(&mut keystream);
+        keystream.iter().zip(data).map(|(k, d)| k ^ d).collect::<Vec<_>>()
+    };
+
+    let mut expected = data.to_vec();
+    ctr::Ctr64BE::<RC5_32_12_16>::new(key.into(), iv).apply_keystream(&mut expected);
+    assert_eq!(xor(KeystreamMode::Ctr), expected);
+
+    let mut piped = Vec::new();
+    CtrPipeline::new(&rc5, iv)
+        .threads(2)
+        .chunk_size(chunk_size)
+        .run(data, &mut piped)
+        .unwrap();
+    assert_eq!(piped, expected);
+
+    let mut expected = data.to_vec();
+    ofb::Ofb::<RC5_32_12_16>::new(key.into(), iv).apply_keystream(&mut expected);
+    assert_eq!(xor(KeystreamMode::Ofb), expected);
+});
@@ -0,0 +1,76 @@
+//! `#[derive(EncryptedFields)]` for the `rc5` crate, use it through the `derive` feature of
+//! `rc5`.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields};
+
+/// Implement `rc5::EncryptedFields`, sealing every field marked `#[encrypted]`.
+///
+/// The fields must implement `rc5::SealField`, `String` and `Vec<u8>` do. The field name is bound
+/// to the ciphertext, renaming a field makes its stored values fail to open.
+#[proc_macro_derive(EncryptedFields, attributes(encrypted))]
+pub fn derive_encrypted_fields(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return Error::new_spanned(&input, "EncryptedFields needs named fields")
+                    .to_compile_error()
+                    .into()
+            }
+        },
+        _ => {
+            return Error::new_spanned(&input, "EncryptedFields can only be derived for structs")
+                .to_compile_error()
+                .into()
+        }
+    };
+    let (idents, names): (Vec<_>, Vec<_>) = fields
+        .iter()
+        .filter(|field| {
+            field
+                .attrs
+                .iter()
+                .any(|attr| attr.path().is_ident("encrypted"))
+        })
+        .filter_map(|field| field.ident.as_ref())
+        .map(|ident| (ident, ident.to_string()))
+        .unzip();
+
+    let name = &input.ident;
+    let (impl_generics, type_generics, where_clause) = input.generics.split_for_impl();
+    let bounds = quote! {
+        C: ::rc5::__private::BlockEncrypt + ::rc5::__private::KeyInit,
+        ::rc5::__private::Block<C>: ::rc5::__private::Dbl,
+    };
+
+    quote! {
+        impl #impl_generics ::rc5::EncryptedFields for #name #type_generics #where_clause {
+            fn seal_fields<C>(
+                &mut self,
+                keys: &::rc5::KeyRing<::rc5::DeterministicCipher<C>>,
+            ) -> ::core::result::Result<(), ::rc5::FieldError>
+            where
+                #bounds
+            {
+                #(::rc5::SealField::seal_field(&mut self.#idents, #names, keys)?;)*
+                ::core::result::Result::Ok(())
+            }
+
+            fn open_fields<C>(
+                &mut self,
+                keys: &::rc5::KeyRing<::rc5::DeterministicCipher<C>>,
+            ) -> ::core::result::Result<(), ::rc5::FieldError>
+            where
+                #bounds
+            {
+                #(::rc5::SealField::open_field(&mut self.#idents, #names, keys)?;)*
+                ::core::result::Result::Ok(())
+            }
+        }
+    }
+    .into()
+}
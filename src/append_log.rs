@@ -0,0 +1,247 @@
+use std::{
+    fs::File,
+    io::{self, Read, Write},
+};
+
+use cipher::{typenum::Unsigned, Block, BlockEncrypt, Key, KeyInit};
+use dbl::Dbl;
+use subtle::ConstantTimeEq;
+
+use crate::{mac::Cmac, Keystream, KeystreamMode, Plaintext};
+
+/// Bytes of the IV holding the block counter within a record.
+const COUNTER_BYTES: usize = 4;
+
+/// Encrypted, authenticated append-only log of records, read back with [LogReader].
+///
+/// Every record is framed as `length || ciphertext || tag`. The ciphertext is CTR encrypted with
+/// an IV made of the record sequence number and a block counter, so no two records share
+/// keystream, and the tag is a CMAC over the sequence number, length and ciphertext
+/// (encrypt-then-MAC). Records can't be modified, reordered, dropped from the middle or copied
+/// between positions without [LogReader] noticing. Dropping records from the end can't be detected
+/// from the log alone, store the record count elsewhere if that matters.
+///
+/// Each record is written with a single `write_all` call. A crash in the middle of an append
+/// leaves a torn last record which the reader reports as [io::ErrorKind::UnexpectedEof], after
+/// all the complete records. To recover, truncate the log to [LogReader::verified_len] and
+/// [resume](LogWriter::resume) at [LogReader::sequence].
+///
+/// With a 64 bit block cipher a log holds at most `2^32` records, each less than 4 GiB.
+pub struct LogWriter<C, W> {
+    keys: LogKeys<C>,
+    writer: W,
+    sequence: u64,
+}
+
+impl<C, W> LogWriter<C, W>
+where
+    C: BlockEncrypt + KeyInit,
+    Block<C>: Dbl,
+    W: Write,
+{
+    /// Start a new log, with independent keys for the MAC and the encryption.
+    pub fn new(mac_key: &Key<C>, encryption_key: &Key<C>, writer: W) -> Self {
+        Self::resume(mac_key, encryption_key, writer, 0)
+    }
+
+    /// Continue a log already holding `sequence` records, see [LogReader::sequence].
+    ///
+    /// The writer must be positioned right after the last of those records. After a torn append
+    /// the log has to be truncated to [LogReader::verified_len] first, records appended after the
+    /// torn bytes can't be read back. The torn record's sequence number is used again, so its
+    /// keystream is reused for the next record: the torn bytes must not have been copied anywhere
+    /// the new record's ciphertext can be compared with.
+    pub fn resume(mac_key: &Key<C>, encryption_key: &Key<C>, writer: W, sequence: u64) -> Self {
+        Self {
+            keys: LogKeys::new(mac_key, encryption_key),
+            writer,
+            sequence,
+        }
+    }
+
+    /// Encrypt and append a record, returning its sequence number.
+    pub fn append(&mut self, record: &[u8]) -> io::Result<u64> {
+        let length = u32::try_from(record.len())
+            .map_err(|_| io::Error::new(io::ErrorKind::InvalidInput, "record too long"))?
+            .to_be_bytes();
+        let iv = self.keys.iv(self.sequence)?;
+
+        let mut frame = Vec::with_capacity(4 + record.len() + C::BlockSize::USIZE);
+        frame.extend_from_slice(&length);
+        frame.extend_from_slice(record);
+        Keystream::new(&self.keys.cipher, KeystreamMode::Ctr, &iv).apply(&mut frame[4..]);
+        let tag = self.keys.tag(self.sequence, &frame);
+        frame.extend_from_slice(&tag);
+
+        self.writer.write_all(&frame)?;
+        self.sequence += 1;
+
+        Ok(self.sequence - 1)
+    }
+
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.writer
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<C> LogWriter<C, File>
+where
+    C: BlockEncrypt + KeyInit,
+    Block<C>: Dbl,
+{
+    /// Flush and wait until every appended record is on disk.
+    pub fn sync(&mut self) -> io::Result<()> {
+        self.flush()?;
+        self.writer.sync_data()
+    }
+}
+
+/// Iterator replaying and verifying the records of a [LogWriter] log.
+///
+/// Yields the decrypted records in order. A record failing verification yields an
+/// [io::ErrorKind::InvalidData] error and a torn last record an [io::ErrorKind::UnexpectedEof]
+/// error, after which the iteration ends.
+pub struct LogReader<C, R> {
+    keys: LogKeys<C>,
+    reader: R,
+    sequence: u64,
+    verified_len: u64,
+    failed: bool,
+}
+
+impl<C, R> LogReader<C, R>
+where
+    C: BlockEncrypt + KeyInit,
+    Block<C>: Dbl,
+    R: Read,
+{
+    pub fn new(mac_key: &Key<C>, encryption_key: &Key<C>, reader: R) -> Self {
+        Self {
+            keys: LogKeys::new(mac_key, encryption_key),
+            reader,
+            sequence: 0,
+            verified_len: 0,
+            failed: false,
+        }
+    }
+
+    /// Number of records verified so far, to [LogWriter::resume] after a full replay.
+    pub fn sequence(&self) -> u64 {
+        self.sequence
+    }
+
+    /// Bytes of the log up to the end of the last verified record, the length to truncate a log
+    /// with a torn last record to before resuming it.
+    pub fn verified_len(&self) -> u64 {
+        self.verified_len
+    }
+
+    fn read_record(&mut self) -> io::Result<Option<Plaintext>> {
+        let mut length = [0; 4];
+        loop {
+            match self.reader.read(&mut length[..1]) {
+                Ok(0) => return Ok(None),
+                Ok(_) => break,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+        self.reader.read_exact(&mut length[1..])?;
+
+        // read instead of allocating the claimed length up front, it isn't verified yet
+        let mut frame = Plaintext::from(length.to_vec());
+        let claimed = u64::from(u32::from_be_bytes(length));
+        if self.reader.by_ref().take(claimed).read_to_end(&mut frame)? as u64 != claimed {
+            return Err(io::ErrorKind::UnexpectedEof.into());
+        }
+        let mut tag = Block::<C>::default();
+        self.reader.read_exact(&mut tag)?;
+
+        if !bool::from(self.keys.tag(self.sequence, &frame).ct_eq(&tag)) {
+            #[cfg(feature = "metrics")]
+            crate::telemetry::tag_failure("log");
+            #[cfg(feature = "tracing")]
+            tracing::warn!(scheme = "log", "authentication failed");
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "log record failed verification",
+            ));
+        }
+
+        let iv = self.keys.iv(self.sequence)?;
+        Keystream::new(&self.keys.cipher, KeystreamMode::Ctr, &iv).apply(&mut frame[4..]);
+        frame.drain(..4);
+        self.sequence += 1;
+        self.verified_len += 4 + claimed + C::BlockSize::U64;
+
+        Ok(Some(frame))
+    }
+}
+
+impl<C, R> Iterator for LogReader<C, R>
+where
+    C: BlockEncrypt + KeyInit,
+    Block<C>: Dbl,
+    R: Read,
+{
+    type Item = io::Result<Plaintext>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        let record = self.read_record().transpose();
+        self.failed = matches!(record, Some(Err(_)));
+        record
+    }
+}
+
+struct LogKeys<C> {
+    mac: C,
+    cipher: C,
+}
+
+impl<C> LogKeys<C>
+where
+    C: BlockEncrypt + KeyInit,
+    Block<C>: Dbl,
+{
+    fn new(mac_key: &Key<C>, encryption_key: &Key<C>) -> Self {
+        Self {
+            mac: C::new(mac_key),
+            cipher: C::new(encryption_key),
+        }
+    }
+
+    /// The sequence number followed by a zero block counter.
+    fn iv(&self, sequence: u64) -> io::Result<Block<C>> {
+        let nonce_bytes = C::BlockSize::USIZE - COUNTER_BYTES;
+        let sequence = sequence.to_be_bytes();
+        let (unused, sequence) = sequence.split_at(8 - nonce_bytes.min(8));
+        if unused.iter().any(|&byte| byte != 0) {
+            return Err(io::Error::other(
+                "log is full, start a new one with new keys",
+            ));
+        }
+
+        let mut iv = Block::<C>::default();
+        iv[nonce_bytes - sequence.len()..nonce_bytes].copy_from_slice(sequence);
+        Ok(iv)
+    }
+
+    fn tag(&self, sequence: u64, frame: &[u8]) -> Block<C> {
+        let mut mac = Cmac::new(&self.mac);
+        mac.update(&sequence.to_be_bytes());
+        mac.update(frame);
+        mac.finalize()
+    }
+}
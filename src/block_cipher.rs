@@ -1,62 +1,587 @@
-use cipher::consts::{U12, U16, U8};
+use std::{
+    marker::PhantomData,
+    ops::{Add, Div, Mul, Sub},
+};
 
-use crate::core::{ExpandedKeyTable, RC5};
-use cipher::{impl_simple_block_encdec, AlgorithmName, KeyInit};
-use cipher::{inout::InOut, Block, BlockCipher, KeySizeUser};
+use cipher::consts::{U1, U12, U16, U2, U24, U8, U9};
 
-pub struct RC5_32_12_16 {
-    key_table: ExpandedKeyTable<u32, U12>,
+use crate::{
+    core::{key_tables_ct_eq, BlockSize, ExpandedKeyTable, ExpandedKeyTableSize, Word, RC5},
+    ParseKeyError, Rc5Key,
+};
+use cipher::{
+    generic_array::ArrayLength,
+    inout::InOut,
+    typenum::{Diff, Quot, Sum, Unsigned},
+    AlgorithmName, Block, BlockBackend, BlockCipher, BlockClosure, BlockDecrypt, BlockEncrypt,
+    BlockSizeUser, KeyInit, KeySizeUser, ParBlocksSizeUser,
+};
+
+/// RC5 with word type `W`, `R` rounds and a key of `B` bytes.
+///
+/// Any parameterization of the paper can be named with typenum constants, `Rc5<u32, U20, U16>`
+/// for RC5-32/20/16 or `Rc5<u32, U16, U10>` for RC5-32/16/10, with `u8`, `u16`, `u32` or `u64`
+/// words, up to 255 rounds and a key of up to 255 bytes. Every one implements [KeyInit],
+/// [BlockCipher], [BlockEncrypt], [BlockDecrypt] and [AlgorithmName], the aliases below name the
+/// common ones. Generic code can bound on [Rc5Variant] instead of the typenum arithmetic.
+pub struct Rc5<W, R, B>
+where
+    W: Word,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+    key_table: ExpandedKeyTable<W, R>,
+    _key_size: PhantomData<B>,
 }
 
-impl RC5<u32, U12, U16> for RC5_32_12_16 {}
+#[allow(non_camel_case_types)]
+pub type RC5_32_12_16 = Rc5<u32, U12, U16>;
 
-impl RC5_32_12_16 {
-    fn encrypt_block(&self, block: InOut<'_, '_, Block<Self>>) {
-        Self::encrypt(block, &self.key_table);
-    }
+/// The 72 bit key variant of the RSA secret-key challenge, searched by distributed.net.
+#[allow(non_camel_case_types)]
+pub type RC5_32_12_9 = Rc5<u32, U12, U9>;
 
-    fn decrypt_block(&self, block: InOut<'_, '_, Block<Self>>) {
-        Self::decrypt(block, &self.key_table);
-    }
+/// RC5 with 16 bit words and a 32 bit block, the variant of choice for 16 bit MCUs.
+#[allow(non_camel_case_types)]
+pub type RC5_16_16_8 = Rc5<u16, U16, U8>;
+
+/// RC5 with 64 bit words and a 128 bit block, with the 24 rounds and 24 byte key of the test
+/// vectors of [draft-krovetz-rc6-rc5-vectors](https://datatracker.ietf.org/doc/html/draft-krovetz-rc6-rc5-vectors-00).
+#[allow(non_camel_case_types)]
+pub type RC5_64_24_24 = Rc5<u64, U24, U24>;
+
+/// [Rc5] with the rounds and the key size as plain integers, `Rc5Const<u32, 20, 16>` is
+/// `Rc5<u32, U20, U16>`.
+///
+/// Concrete parameterizations need no typenum, and parameters outside the ranges of the paper fail
+/// to build with a message naming the limit. Generic code still has to satisfy the bounds of the
+/// underlying [Rc5], bound on [Rc5Variant] for that.
+pub type Rc5Const<W, const R: usize, const B: usize> = Rc5<W, typenum::U<R>, typenum::U<B>>;
+
+/// A valid RC5 parameterization.
+///
+/// Implemented for every [Rc5] whose parameters satisfy the bounds of the core implementation.
+/// The bounds are attached to the associated types, so generic code only needs `V: Rc5Variant`
+/// instead of restating them. [Rc5Variant::KeySize] is the same type as [KeySizeUser::KeySize],
+/// refer to it as `<V as Rc5Variant>::KeySize` to get its bounds.
+pub trait Rc5Variant: BlockCipher + BlockEncrypt + BlockDecrypt + KeyInit {
+    type Word: Word<Bytes: Mul<U2, Output: ArrayLength<u8>>>;
+    type Rounds: Unsigned + Add<U1, Output: Mul<U2, Output: ArrayLength<Self::Word>>>;
+    type KeySize: ArrayLength<u8>
+        + Add<
+            <Self::Word as Word>::Bytes,
+            Output: Sub<
+                U1,
+                Output: Div<<Self::Word as Word>::Bytes, Output: ArrayLength<Self::Word>>,
+            >,
+        >;
 }
 
-impl BlockCipher for RC5_32_12_16 {}
+impl<W, R, B> Rc5Variant for Rc5<W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Unsigned,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+    B: ArrayLength<u8>,
+    B: Add<W::Bytes>,
+    Sum<B, W::Bytes>: Sub<U1>,
+    Diff<Sum<B, W::Bytes>, U1>: Div<W::Bytes>,
+    Quot<Diff<Sum<B, W::Bytes>, U1>, W::Bytes>: ArrayLength<W>,
+{
+    type Word = W;
+    type Rounds = R;
+    type KeySize = B;
+}
+
+impl<W, R, B> RC5<W, R, B> for Rc5<W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Unsigned,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+    B: ArrayLength<u8>,
+    B: Add<W::Bytes>,
+    Sum<B, W::Bytes>: Sub<U1>,
+    Diff<Sum<B, W::Bytes>, U1>: Div<W::Bytes>,
+    Quot<Diff<Sum<B, W::Bytes>, U1>, W::Bytes>: ArrayLength<W>,
+{
+}
+
+impl<W, R, B> BlockCipher for Rc5<W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+}
 
-impl KeySizeUser for RC5_32_12_16 {
-    type KeySize = U16;
+impl<W, R, B> KeySizeUser for Rc5<W, R, B>
+where
+    W: Word,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+    B: ArrayLength<u8>,
+{
+    type KeySize = B;
 }
 
-impl KeyInit for RC5_32_12_16 {
+impl<W, R, B> KeyInit for Rc5<W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Unsigned,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+    B: ArrayLength<u8>,
+    B: Add<W::Bytes>,
+    Sum<B, W::Bytes>: Sub<U1>,
+    Diff<Sum<B, W::Bytes>, U1>: Div<W::Bytes>,
+    Quot<Diff<Sum<B, W::Bytes>, U1>, W::Bytes>: ArrayLength<W>,
+{
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "debug", name = "rc5::key_setup", skip_all)
+    )]
     fn new(key: &cipher::Key<Self>) -> Self {
+        #[cfg(feature = "metrics")]
+        crate::telemetry::key_setups(1);
         Self {
-            key_table: Self::substitute_key(key),
+            key_table: {
+                let () = Self::PARAMETERS_IN_RANGE;
+                Self::substitute_key(key)
+            },
+            _key_size: PhantomData,
         }
     }
 }
 
-impl AlgorithmName for RC5_32_12_16 {
+impl<W, R, B> Rc5<W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Unsigned,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+    B: ArrayLength<u8>,
+    B: Add<W::Bytes>,
+    Sum<B, W::Bytes>: Sub<U1>,
+    Diff<Sum<B, W::Bytes>, U1>: Div<W::Bytes>,
+    Quot<Diff<Sum<B, W::Bytes>, U1>, W::Bytes>: ArrayLength<W>,
+{
+    /// Fails the build for parameters outside the ranges of the paper, checked on key setup.
+    const PARAMETERS_IN_RANGE: () = assert!(
+        R::USIZE <= 255 && B::USIZE <= 255,
+        "RC5 allows at most 255 rounds and a 255 byte key"
+    );
+
+    pub fn from_key(key: &Rc5Key<B>) -> Self {
+        Self::new(key.as_bytes())
+    }
+
+    /// Initialize the cipher from a key that is no longer needed, overwriting it with zeros.
+    ///
+    /// Equivalent to [KeyInit::new] followed by wiping the key, but for key sizes that are a
+    /// multiple of the word size the key schedule works in the key buffer itself, so key setup
+    /// doesn't hold a second copy of the key. This lowers peak memory on small devices.
+    pub fn new_in_place(key: &mut cipher::Key<Self>) -> Self {
+        let () = Self::PARAMETERS_IN_RANGE;
+        #[cfg(feature = "metrics")]
+        crate::telemetry::key_setups(1);
+        Self {
+            key_table: Self::substitute_key_in_place(key),
+            _key_size: PhantomData,
+        }
+    }
+
+    /// Initialize `N` ciphers at once.
+    ///
+    /// Equivalent to calling [KeyInit::new] for every key, but the key schedules run in lockstep
+    /// so the mixing loop is vectorized across keys. Useful when key setup dominates, like trying
+    /// many keys or serving many tenants.
+    pub fn new_batch<const N: usize>(keys: &[cipher::Key<Self>; N]) -> [Self; N] {
+        let () = Self::PARAMETERS_IN_RANGE;
+        #[cfg(feature = "metrics")]
+        crate::telemetry::key_setups(N as u64);
+        Self::substitute_keys(keys.each_ref()).map(|key_table| Self {
+            key_table,
+            _key_size: PhantomData,
+        })
+    }
+
+    /// Initialize the cipher from `2 * B` hex digits.
+    pub fn from_hex(key: &str) -> Result<Self, ParseKeyError> {
+        Ok(Self::from_key(&key.parse()?))
+    }
+
+    /// Encrypt a block held in a plain array, `N` is the block size (`[u8; 8]` for 32 bit words).
+    pub fn encrypt_block_array<const N: usize>(&self, block: &mut [u8; N])
+    where
+        for<'a> &'a mut Block<Self>: From<&'a mut [u8; N]>,
+    {
+        self.encrypt_block(block.into());
+    }
+
+    /// Decrypt a block held in a plain array, `N` is the block size (`[u8; 8]` for 32 bit words).
+    pub fn decrypt_block_array<const N: usize>(&self, block: &mut [u8; N])
+    where
+        for<'a> &'a mut Block<Self>: From<&'a mut [u8; N]>,
+    {
+        self.decrypt_block(block.into());
+    }
+
+    /// Backend encrypting with this key, to drive block processing directly.
+    pub fn encrypt_backend(&self) -> Rc5EncryptBackend<'_, W, R, B> {
+        Rc5EncryptBackend::new(self)
+    }
+
+    /// Backend decrypting with this key, to drive block processing directly.
+    pub fn decrypt_backend(&self) -> Rc5DecryptBackend<'_, W, R, B> {
+        Rc5DecryptBackend::new(self)
+    }
+}
+
+impl<W, R, B> AlgorithmName for Rc5<W, R, B>
+where
+    W: Word,
+    R: Unsigned,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+    B: Unsigned,
+{
     fn write_alg_name(f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.write_str("RC5-32/12/16")
+        write!(f, "RC5-{}/{}/{}", W::Bytes::USIZE * 8, R::USIZE, B::USIZE)
     }
 }
 
-// TODO: impl by hand. Code is obfuscated. Macro undocumented.
-impl_simple_block_encdec!(
-    RC5_32_12_16, U8, cipher, block,
-    encrypt: {
-        cipher.encrypt_block(block);
+impl<W, R, B> BlockSizeUser for Rc5<W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+    type BlockSize = BlockSize<W>;
+}
+
+impl<W, R, B> BlockEncrypt for Rc5<W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Unsigned,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+    B: ArrayLength<u8>,
+    B: Add<W::Bytes>,
+    Sum<B, W::Bytes>: Sub<U1>,
+    Diff<Sum<B, W::Bytes>, U1>: Div<W::Bytes>,
+    Quot<Diff<Sum<B, W::Bytes>, U1>, W::Bytes>: ArrayLength<W>,
+{
+    fn encrypt_with_backend(&self, f: impl BlockClosure<BlockSize = Self::BlockSize>) {
+        f.call(&mut Rc5EncryptBackend::new(self))
     }
-    decrypt: {
-        cipher.decrypt_block(block);
+}
+
+impl<W, R, B> BlockDecrypt for Rc5<W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Unsigned,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+    B: ArrayLength<u8>,
+    B: Add<W::Bytes>,
+    Sum<B, W::Bytes>: Sub<U1>,
+    Diff<Sum<B, W::Bytes>, U1>: Div<W::Bytes>,
+    Quot<Diff<Sum<B, W::Bytes>, U1>, W::Bytes>: ArrayLength<W>,
+{
+    fn decrypt_with_backend(&self, f: impl BlockClosure<BlockSize = Self::BlockSize>) {
+        f.call(&mut Rc5DecryptBackend::new(self))
     }
-);
+}
+
+// impl_simple_block_encdec! can't express the typenum bounds, so the backends are written by hand
+
+/// Encryption backend of [Rc5], as passed to the [BlockClosure] of [BlockEncrypt].
+///
+/// Drive it directly through [BlockBackend] to process single blocks or `ParBlocksSize` blocks at
+/// once (8 with the `simd` feature) without going through a closure.
+pub struct Rc5EncryptBackend<'a, W, R, B>(
+    &'a Rc5<W, R, B>,
+    /// Blocks processed, reported on drop.
+    #[cfg(feature = "metrics")]
+    u64,
+)
+where
+    W: Word,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>;
+
+/// Decryption backend of [Rc5], as passed to the [BlockClosure] of [BlockDecrypt].
+///
+/// Drive it directly through [BlockBackend], see [Rc5EncryptBackend].
+pub struct Rc5DecryptBackend<'a, W, R, B>(
+    &'a Rc5<W, R, B>,
+    /// Blocks processed, reported on drop.
+    #[cfg(feature = "metrics")]
+    u64,
+)
+where
+    W: Word,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>;
+
+impl<'a, W, R, B> Rc5EncryptBackend<'a, W, R, B>
+where
+    W: Word,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+    fn new(cipher: &'a Rc5<W, R, B>) -> Self {
+        Self(
+            cipher,
+            #[cfg(feature = "metrics")]
+            0,
+        )
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl<W, R, B> Drop for Rc5EncryptBackend<'_, W, R, B>
+where
+    W: Word,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+    fn drop(&mut self) {
+        crate::telemetry::blocks("encrypt", self.1, W::Bytes::USIZE * 2);
+    }
+}
+
+impl<'a, W, R, B> Rc5DecryptBackend<'a, W, R, B>
+where
+    W: Word,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+    fn new(cipher: &'a Rc5<W, R, B>) -> Self {
+        Self(
+            cipher,
+            #[cfg(feature = "metrics")]
+            0,
+        )
+    }
+}
+
+#[cfg(feature = "metrics")]
+impl<W, R, B> Drop for Rc5DecryptBackend<'_, W, R, B>
+where
+    W: Word,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+    fn drop(&mut self) {
+        crate::telemetry::blocks("decrypt", self.1, W::Bytes::USIZE * 2);
+    }
+}
+
+impl<W, R, B> BlockSizeUser for Rc5EncryptBackend<'_, W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+    type BlockSize = BlockSize<W>;
+}
+
+impl<W, R, B> ParBlocksSizeUser for Rc5EncryptBackend<'_, W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+    #[cfg(not(feature = "simd"))]
+    type ParBlocksSize = U1;
+    #[cfg(feature = "simd")]
+    type ParBlocksSize = U8;
+}
+
+impl<W, R, B> BlockBackend for Rc5EncryptBackend<'_, W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Unsigned,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+    B: ArrayLength<u8>,
+    B: Add<W::Bytes>,
+    Sum<B, W::Bytes>: Sub<U1>,
+    Diff<Sum<B, W::Bytes>, U1>: Div<W::Bytes>,
+    Quot<Diff<Sum<B, W::Bytes>, U1>, W::Bytes>: ArrayLength<W>,
+{
+    #[inline(always)]
+    fn proc_block(&mut self, block: InOut<'_, '_, Block<Self>>) {
+        #[cfg(feature = "metrics")]
+        {
+            self.1 += 1;
+        }
+        Rc5::<W, R, B>::encrypt(block, &self.0.key_table);
+    }
+
+    #[cfg(feature = "simd")]
+    #[inline(always)]
+    fn proc_par_blocks(&mut self, mut blocks: InOut<'_, '_, cipher::ParBlocks<Self>>) {
+        #[cfg(feature = "metrics")]
+        {
+            self.1 += Self::ParBlocksSize::U64;
+        }
+        let mut lanes = blocks.clone_in();
+        Rc5::<W, R, B>::encrypt_lanes(&mut lanes, &self.0.key_table);
+        *blocks.get_out() = lanes;
+    }
+}
+
+impl<W, R, B> BlockSizeUser for Rc5DecryptBackend<'_, W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+    type BlockSize = BlockSize<W>;
+}
+
+impl<W, R, B> ParBlocksSizeUser for Rc5DecryptBackend<'_, W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+    #[cfg(not(feature = "simd"))]
+    type ParBlocksSize = U1;
+    #[cfg(feature = "simd")]
+    type ParBlocksSize = U8;
+}
+
+impl<W, R, B> BlockBackend for Rc5DecryptBackend<'_, W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Unsigned,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+    B: ArrayLength<u8>,
+    B: Add<W::Bytes>,
+    Sum<B, W::Bytes>: Sub<U1>,
+    Diff<Sum<B, W::Bytes>, U1>: Div<W::Bytes>,
+    Quot<Diff<Sum<B, W::Bytes>, U1>, W::Bytes>: ArrayLength<W>,
+{
+    #[inline(always)]
+    fn proc_block(&mut self, block: InOut<'_, '_, Block<Self>>) {
+        #[cfg(feature = "metrics")]
+        {
+            self.1 += 1;
+        }
+        Rc5::<W, R, B>::decrypt(block, &self.0.key_table);
+    }
+
+    #[cfg(feature = "simd")]
+    #[inline(always)]
+    fn proc_par_blocks(&mut self, mut blocks: InOut<'_, '_, cipher::ParBlocks<Self>>) {
+        #[cfg(feature = "metrics")]
+        {
+            self.1 += Self::ParBlocksSize::U64;
+        }
+        let mut lanes = blocks.clone_in();
+        Rc5::<W, R, B>::decrypt_lanes(&mut lanes, &self.0.key_table);
+        *blocks.get_out() = lanes;
+    }
+}
+
+/// Ciphers are equal when their expanded key tables are, compared in constant time.
+impl<W, R, B> PartialEq for Rc5<W, R, B>
+where
+    W: Word,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+    fn eq(&self, other: &Self) -> bool {
+        key_tables_ct_eq(&self.key_table, &other.key_table)
+    }
+}
+
+impl<W, R, B> Eq for Rc5<W, R, B>
+where
+    W: Word,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+}
 
 #[cfg(feature = "zeroize")]
-impl cipher::zeroize::ZeroizeOnDrop for RC5_32_12_16 {}
+impl<W, R, B> cipher::zeroize::ZeroizeOnDrop for Rc5<W, R, B>
+where
+    W: Word,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
+}
 
 #[cfg(feature = "zeroize")]
-impl Drop for RC5_32_12_16 {
+impl<W, R, B> Drop for Rc5<W, R, B>
+where
+    W: Word,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+{
     fn drop(&mut self) {
-        cipher::zeroize::Zeroize::zeroize(&mut self.key_table);
+        cipher::zeroize::Zeroize::zeroize(self.key_table.as_mut_slice());
     }
 }
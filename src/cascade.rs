@@ -0,0 +1,157 @@
+use std::{fmt, ops::Add};
+
+use cipher::{
+    consts::U1,
+    generic_array::{ArrayLength, GenericArray},
+    inout::InOut,
+    typenum::{Sum, Unsigned},
+    AlgorithmName, Block, BlockBackend, BlockCipher, BlockClosure, BlockDecrypt, BlockEncrypt,
+    BlockSizeUser, Key, KeyInit, KeySizeUser, ParBlocksSizeUser,
+};
+
+/// Cascade of two block ciphers with the same block size.
+///
+/// `C = E2(K2, E1(K1, P))` with the key laid out as `K1 || K2`. The cascade is at least as strong
+/// as the first cipher, so RC5 can be combined with an unrelated cipher to hedge against a break
+/// of either one.
+pub struct Cascade<C1, C2> {
+    first: C1,
+    second: C2,
+}
+
+impl<C1, C2> Cascade<C1, C2> {
+    /// Cascade two already keyed ciphers.
+    pub fn from_ciphers(first: C1, second: C2) -> Self {
+        Self { first, second }
+    }
+}
+
+impl<C1, C2> KeySizeUser for Cascade<C1, C2>
+where
+    C1: KeySizeUser,
+    C2: KeySizeUser,
+    C1::KeySize: Add<C2::KeySize>,
+    Sum<C1::KeySize, C2::KeySize>: ArrayLength<u8>,
+{
+    type KeySize = Sum<C1::KeySize, C2::KeySize>;
+}
+
+impl<C1, C2> KeyInit for Cascade<C1, C2>
+where
+    C1: KeyInit,
+    C2: KeyInit,
+    C1::KeySize: Add<C2::KeySize>,
+    Sum<C1::KeySize, C2::KeySize>: ArrayLength<u8>,
+{
+    fn new(key: &Key<Self>) -> Self {
+        let (first, second) = key.split_at(C1::KeySize::USIZE);
+
+        Self {
+            first: C1::new(GenericArray::from_slice(first)),
+            second: C2::new(GenericArray::from_slice(second)),
+        }
+    }
+}
+
+impl<C1, C2> BlockSizeUser for Cascade<C1, C2>
+where
+    C1: BlockSizeUser,
+    C2: BlockSizeUser<BlockSize = C1::BlockSize>,
+{
+    type BlockSize = C1::BlockSize;
+}
+
+impl<C1, C2> BlockCipher for Cascade<C1, C2>
+where
+    C1: BlockCipher,
+    C2: BlockCipher<BlockSize = C1::BlockSize>,
+{
+}
+
+impl<C1, C2> BlockEncrypt for Cascade<C1, C2>
+where
+    C1: BlockEncrypt,
+    C2: BlockEncrypt<BlockSize = C1::BlockSize>,
+{
+    fn encrypt_with_backend(&self, f: impl BlockClosure<BlockSize = Self::BlockSize>) {
+        f.call(&mut EncryptBackend(self))
+    }
+}
+
+impl<C1, C2> BlockDecrypt for Cascade<C1, C2>
+where
+    C1: BlockDecrypt,
+    C2: BlockDecrypt<BlockSize = C1::BlockSize>,
+{
+    fn decrypt_with_backend(&self, f: impl BlockClosure<BlockSize = Self::BlockSize>) {
+        f.call(&mut DecryptBackend(self))
+    }
+}
+
+impl<C1: AlgorithmName, C2: AlgorithmName> AlgorithmName for Cascade<C1, C2> {
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Cascade<")?;
+        C1::write_alg_name(f)?;
+        f.write_str(", ")?;
+        C2::write_alg_name(f)?;
+        f.write_str(">")
+    }
+}
+
+struct EncryptBackend<'a, C1, C2>(&'a Cascade<C1, C2>);
+
+struct DecryptBackend<'a, C1, C2>(&'a Cascade<C1, C2>);
+
+impl<C1: BlockSizeUser, C2> BlockSizeUser for EncryptBackend<'_, C1, C2> {
+    type BlockSize = C1::BlockSize;
+}
+
+impl<C1: BlockSizeUser, C2> ParBlocksSizeUser for EncryptBackend<'_, C1, C2> {
+    type ParBlocksSize = U1;
+}
+
+impl<C1, C2> BlockBackend for EncryptBackend<'_, C1, C2>
+where
+    C1: BlockEncrypt,
+    C2: BlockEncrypt<BlockSize = C1::BlockSize>,
+{
+    #[inline(always)]
+    fn proc_block(&mut self, mut block: InOut<'_, '_, Block<Self>>) {
+        let mut inner = block.clone_in();
+        self.0.first.encrypt_block(&mut inner);
+        self.0.second.encrypt_block(&mut inner);
+
+        *block.get_out() = inner;
+    }
+}
+
+impl<C1: BlockSizeUser, C2> BlockSizeUser for DecryptBackend<'_, C1, C2> {
+    type BlockSize = C1::BlockSize;
+}
+
+impl<C1: BlockSizeUser, C2> ParBlocksSizeUser for DecryptBackend<'_, C1, C2> {
+    type ParBlocksSize = U1;
+}
+
+impl<C1, C2> BlockBackend for DecryptBackend<'_, C1, C2>
+where
+    C1: BlockDecrypt,
+    C2: BlockDecrypt<BlockSize = C1::BlockSize>,
+{
+    #[inline(always)]
+    fn proc_block(&mut self, mut block: InOut<'_, '_, Block<Self>>) {
+        let mut inner = block.clone_in();
+        self.0.second.decrypt_block(&mut inner);
+        self.0.first.decrypt_block(&mut inner);
+
+        *block.get_out() = inner;
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<C1, C2> cipher::zeroize::ZeroizeOnDrop for Cascade<C1, C2>
+where
+    C1: cipher::zeroize::ZeroizeOnDrop,
+    C2: cipher::zeroize::ZeroizeOnDrop,
+{
+}
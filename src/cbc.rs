@@ -0,0 +1,202 @@
+use cipher::{typenum::Unsigned, Block, BlockDecrypt, BlockEncrypt, BlockSizeUser};
+use dbl::Dbl;
+
+use crate::{
+    mac::{xor, Cmac},
+    padding::padme_padded,
+    unpad_padme, InvalidCiphertext, Plaintext,
+};
+
+/// Label of the IV derivation.
+const LABEL: &[u8] = b"CBC-IV";
+
+/// Where the IV of a [CbcPad] message is kept.
+pub enum IvPlacement<'a, C: BlockSizeUser> {
+    /// A random IV written before the ciphertext, where decryption reads it from.
+    Prepended,
+    /// An IV kept apart from the ciphertext by the caller, in a header or a database column, and
+    /// passed to both sides. It must be unpredictable.
+    Separate(&'a Block<C>),
+    /// An IV derived with the key from a context unique to the message, such as a record ID, so
+    /// it is neither random nor stored. No context may be used for two messages under one key.
+    Derived(&'a [u8]),
+}
+
+impl<C: BlockSizeUser> Clone for IvPlacement<'_, C> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<C: BlockSizeUser> Copy for IvPlacement<'_, C> {}
+
+/// One-shot CBC with PKCS#7 padding, with the IV placement conventions of [IvPlacement].
+///
+/// Derived IVs are the CMAC of `"CBC-IV" || context` under the encryption key, the generation of
+/// unpredictable IVs from a nonce in NIST SP 800-38A appendix C, with CMAC so the context can
+/// have any length.
+///
+/// The mode doesn't authenticate and [CbcPad::decrypt] is a padding oracle to anyone who can
+/// submit ciphertexts and tell the error apart. Authenticate the ciphertexts, or use `EtM` or
+/// [TokenCipher](crate::TokenCipher) instead.
+pub struct CbcPad<C> {
+    cipher: C,
+    padme: bool,
+}
+
+impl<C> CbcPad<C>
+where
+    C: BlockEncrypt + BlockDecrypt,
+    Block<C>: Dbl,
+{
+    pub fn new(cipher: C) -> Self {
+        Self {
+            cipher,
+            padme: false,
+        }
+    }
+
+    /// Pad plaintexts to their Padmé length before encrypting, see [pad_padme](crate::pad_padme),
+    /// so ciphertext lengths leak less about plaintext lengths. Both sides must agree on it.
+    pub fn padme(mut self) -> Self {
+        self.padme = true;
+        self
+    }
+
+    /// Encrypt `plaintext` with a random IV drawn from `rng` in front of the ciphertext, for
+    /// [IvPlacement::Prepended].
+    #[cfg(feature = "rand_core")]
+    pub fn encrypt(
+        &self,
+        plaintext: &[u8],
+        rng: &mut (impl cipher::rand_core::CryptoRng + cipher::rand_core::RngCore),
+    ) -> Vec<u8> {
+        self.encrypt_with_iv(&crate::keystream::random_iv::<C>(rng), plaintext)
+    }
+
+    /// [CbcPad::encrypt] with the given IV in front of the ciphertext, like
+    /// [IvPlacement::Prepended]. The IV must be unpredictable.
+    pub fn encrypt_with_iv(&self, iv: &Block<C>, plaintext: &[u8]) -> Vec<u8> {
+        self.encrypt_to(iv, plaintext, iv.to_vec())
+    }
+
+    /// Encrypt `plaintext` under an IV the caller keeps, the ciphertext only, for
+    /// [IvPlacement::Separate]. The IV must be unpredictable.
+    pub fn encrypt_separate(&self, iv: &Block<C>, plaintext: &[u8]) -> Vec<u8> {
+        self.encrypt_to(iv, plaintext, Vec::new())
+    }
+
+    /// Encrypt `plaintext` under the IV derived from `context`, the ciphertext only, for
+    /// [IvPlacement::Derived].
+    pub fn encrypt_derived(&self, context: &[u8], plaintext: &[u8]) -> Vec<u8> {
+        self.encrypt_to(&self.derive_iv(context), plaintext, Vec::new())
+    }
+
+    /// Decrypt a message from one of the encryptions, with its placement.
+    pub fn decrypt(
+        &self,
+        iv: IvPlacement<'_, C>,
+        message: &[u8],
+    ) -> Result<Plaintext, InvalidCiphertext> {
+        let mut plaintext = match iv {
+            IvPlacement::Prepended => {
+                if message.len() < C::BlockSize::USIZE {
+                    return Err(InvalidCiphertext);
+                }
+                let (iv, ciphertext) = message.split_at(C::BlockSize::USIZE);
+                decrypt_padded(&self.cipher, iv, ciphertext)
+            }
+            IvPlacement::Separate(iv) => decrypt_padded(&self.cipher, iv, message),
+            IvPlacement::Derived(context) => {
+                decrypt_padded(&self.cipher, &self.derive_iv(context), message)
+            }
+        }
+        .ok_or(InvalidCiphertext)?;
+
+        if self.padme {
+            unpad_padme(&mut plaintext).map_err(|_| InvalidCiphertext)?;
+        }
+        Ok(plaintext)
+    }
+
+    /// The IV of [IvPlacement::Derived] for `context`.
+    pub fn derive_iv(&self, context: &[u8]) -> Block<C> {
+        let mut mac = Cmac::new(&self.cipher);
+        mac.update(LABEL);
+        mac.update(context);
+        mac.finalize()
+    }
+
+    fn encrypt_to(&self, iv: &Block<C>, plaintext: &[u8], mut out: Vec<u8>) -> Vec<u8> {
+        if self.padme {
+            encrypt_padded(&self.cipher, iv, &padme_padded(plaintext), &mut out);
+        } else {
+            encrypt_padded(&self.cipher, iv, plaintext, &mut out);
+        }
+        out
+    }
+}
+
+/// Append `plaintext` encrypted in CBC mode with PKCS#7 padding to `out`.
+pub(crate) fn encrypt_padded<C: BlockEncrypt>(
+    cipher: &C,
+    iv: &Block<C>,
+    plaintext: &[u8],
+    out: &mut Vec<u8>,
+) {
+    let block_size = C::BlockSize::USIZE;
+    let padding = block_size - plaintext.len() % block_size;
+
+    // pad a copy of the last block only, without a heap copy of the whole plaintext
+    let (full, rest) = plaintext.split_at(plaintext.len() - (block_size - padding));
+    let mut last = Block::<C>::default();
+    last[..rest.len()].copy_from_slice(rest);
+    last[rest.len()..].fill(padding as u8);
+
+    let mut previous = iv.clone();
+    for block in full.chunks(block_size).chain([&last[..]]) {
+        xor(&mut previous, block);
+        cipher.encrypt_block(&mut previous);
+        out.extend_from_slice(&previous);
+    }
+}
+
+/// Decrypt a CBC ciphertext and strip the PKCS#7 padding, `None` if either is malformed.
+///
+/// Without a MAC over the ciphertext the two cases are a padding oracle, callers authenticate
+/// first or document it.
+pub(crate) fn decrypt_padded<C: BlockDecrypt>(
+    cipher: &C,
+    iv: &[u8],
+    ciphertext: &[u8],
+) -> Option<Plaintext> {
+    let block_size = C::BlockSize::USIZE;
+    if ciphertext.is_empty() || !ciphertext.len().is_multiple_of(block_size) {
+        return None;
+    }
+
+    let mut previous = iv;
+    let mut plaintext = Plaintext::default();
+    plaintext.reserve_exact(ciphertext.len());
+    for block in ciphertext.chunks(block_size) {
+        let mut decrypted = Block::<C>::clone_from_slice(block);
+        cipher.decrypt_block(&mut decrypted);
+        xor(&mut decrypted, previous);
+        plaintext.extend_from_slice(&decrypted);
+        previous = block;
+    }
+
+    let padding = usize::from(*plaintext.last().expect("at least one block"));
+    if padding == 0
+        || padding > block_size
+        || plaintext[plaintext.len() - padding..]
+            .iter()
+            .any(|&byte| usize::from(byte) != padding)
+    {
+        return None;
+    }
+    let length = plaintext.len() - padding;
+    plaintext.truncate(length);
+
+    Some(plaintext)
+}
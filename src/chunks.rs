@@ -0,0 +1,342 @@
+use std::{
+    io::{self, IoSliceMut, Read, Seek, SeekFrom, Write},
+    ops::DerefMut,
+};
+
+use cipher::{Block, BlockEncrypt};
+
+#[cfg(feature = "nonce-tracking")]
+use crate::nonce_tracking::PREFIX;
+use crate::Keystream;
+
+/// Default [StreamEncryptor] buffer size in bytes.
+const BUFFER_SIZE: usize = 64 << 10;
+
+/// Keystream that continues mid-block across calls, unlike [Keystream::apply] which discards the
+/// rest of the last block.
+///
+/// The nonce use of the keystream is checked by the first [Continuous::apply] instead.
+struct Continuous<'a, C: BlockEncrypt> {
+    keystream: Keystream<'a, C>,
+    block: Block<C>,
+    position: usize,
+}
+
+impl<'a, C: BlockEncrypt> Continuous<'a, C> {
+    fn new(keystream: Keystream<'a, C>) -> Self {
+        Self {
+            keystream,
+            block: Block::<C>::default(),
+            position: C::block_size(),
+        }
+    }
+
+    /// Discard the next `bytes` bytes of keystream.
+    fn skip(&mut self, mut bytes: usize) {
+        // data starting within a message isn't the message a nonce use is compared by
+        #[cfg(feature = "nonce-tracking")]
+        if bytes > 0 {
+            self.keystream.take_nonce_use();
+        }
+
+        while bytes > 0 {
+            if self.position == self.block.len() {
+                self.block = self.keystream.next_block();
+                self.position = 0;
+            }
+            let skipped = bytes.min(self.block.len() - self.position);
+            self.position += skipped;
+            bytes -= skipped;
+        }
+    }
+
+    fn apply(&mut self, data: &mut [u8]) {
+        #[cfg(feature = "nonce-tracking")]
+        if !data.is_empty() {
+            if let Some(nonce_use) = self.keystream.take_nonce_use() {
+                let len = data.len().min(PREFIX);
+                let mut input = [0; PREFIX];
+                input[..len].copy_from_slice(&data[..len]);
+                self.xor(data);
+                nonce_use.check(&input[..len], &data[..len]);
+                return;
+            }
+        }
+
+        self.xor(data);
+    }
+
+    fn xor(&mut self, data: &mut [u8]) {
+        for byte in data {
+            if self.position == self.block.len() {
+                self.block = self.keystream.next_block();
+                self.position = 0;
+            }
+            *byte ^= self.block[self.position];
+            self.position += 1;
+        }
+    }
+
+    /// Apply to the first `bytes` bytes of `bufs`, in order.
+    fn apply_vectored(&mut self, bufs: &mut [IoSliceMut<'_>], mut bytes: usize) {
+        for buf in bufs {
+            let len = buf.len().min(bytes);
+            self.apply(&mut buf[..len]);
+            bytes -= len;
+        }
+    }
+}
+
+/// XOR the CTR keystream into `data`, which starts `offset` bytes into the message.
+pub(crate) fn apply_ctr_at<C: BlockEncrypt>(
+    cipher: &C,
+    iv: &Block<C>,
+    offset: u64,
+    data: &mut [u8],
+) {
+    let block_size = C::block_size() as u64;
+
+    let mut keystream = Continuous::new(Keystream::ctr_at(cipher, iv, offset / block_size));
+    keystream.skip((offset % block_size) as usize);
+    keystream.apply(data);
+}
+
+/// Iterator encrypting (or decrypting) each chunk of the inner iterator with a continuous
+/// keystream, created by [encrypt_chunks].
+pub struct EncryptChunks<'a, C: BlockEncrypt, I> {
+    keystream: Continuous<'a, C>,
+    chunks: I,
+}
+
+/// Lazily encrypt a sequence of chunks of any size as one message.
+///
+/// Chunks are only pulled from `chunks` when the next encrypted chunk is requested, so the input
+/// never has to be in memory at once. The result equals applying `keystream` to the whole
+/// concatenated input.
+pub fn encrypt_chunks<'a, C, I>(
+    keystream: Keystream<'a, C>,
+    chunks: I,
+) -> EncryptChunks<'a, C, I::IntoIter>
+where
+    C: BlockEncrypt,
+    I: IntoIterator,
+    I::Item: AsRef<[u8]>,
+{
+    EncryptChunks {
+        keystream: Continuous::new(keystream),
+        chunks: chunks.into_iter(),
+    }
+}
+
+impl<C, I> Iterator for EncryptChunks<'_, C, I>
+where
+    C: BlockEncrypt,
+    I: Iterator,
+    I::Item: AsRef<[u8]>,
+{
+    type Item = Vec<u8>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut chunk = self.chunks.next()?.as_ref().to_vec();
+        self.keystream.apply(&mut chunk);
+
+        Some(chunk)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.chunks.size_hint()
+    }
+}
+
+/// Keystream applied in place to scattered buffers, as one message.
+///
+/// Buffers handed to vectored I/O, `readv` and `writev`, are encrypted (or decrypted) where they
+/// are, with the keystream continuing mid-block from one buffer to the next and from one call to
+/// the next, so network stacks don't have to coalesce segments into one buffer first. Buffers of
+/// `IoSlice` are immutable, encrypt them in place before building the slices.
+pub struct VectoredKeystream<'a, C: BlockEncrypt> {
+    keystream: Continuous<'a, C>,
+}
+
+impl<'a, C: BlockEncrypt> VectoredKeystream<'a, C> {
+    pub fn new(keystream: Keystream<'a, C>) -> Self {
+        Self {
+            keystream: Continuous::new(keystream),
+        }
+    }
+
+    /// Apply the keystream to `bufs` in order.
+    pub fn apply_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) {
+        self.apply_buffers(bufs.iter_mut().map(|buf| &mut **buf));
+    }
+
+    /// Apply the keystream to every buffer of `bufs` in order, `&mut [u8]`, `Vec<u8>` or any other
+    /// mutable byte buffer.
+    pub fn apply_buffers<I>(&mut self, bufs: I)
+    where
+        I: IntoIterator,
+        I::Item: DerefMut<Target = [u8]>,
+    {
+        for mut buf in bufs {
+            self.keystream.apply(&mut buf);
+        }
+    }
+}
+
+/// [Read] adapter encrypting (or decrypting) everything read from the inner reader.
+pub struct EncryptReader<'a, C: BlockEncrypt, R> {
+    keystream: Continuous<'a, C>,
+    reader: R,
+}
+
+impl<'a, C: BlockEncrypt, R: Read> EncryptReader<'a, C, R> {
+    pub fn new(keystream: Keystream<'a, C>, reader: R) -> Self {
+        Self {
+            keystream: Continuous::new(keystream),
+            reader,
+        }
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<C: BlockEncrypt, R: Read> Read for EncryptReader<'_, C, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        self.keystream.apply(&mut buf[..read]);
+
+        Ok(read)
+    }
+
+    fn read_vectored(&mut self, bufs: &mut [IoSliceMut<'_>]) -> io::Result<usize> {
+        let read = self.reader.read_vectored(bufs)?;
+        self.keystream.apply_vectored(bufs, read);
+
+        Ok(read)
+    }
+}
+
+/// Random-access [Read] and [Seek] over CTR encrypted data.
+///
+/// The ciphertext starts at the position of the inner reader when the adapter is created. Each
+/// read decrypts only the blocks covering the bytes read, starting the keystream at the read
+/// position with [Keystream::ctr_at], so parts of large encrypted files can be read without
+/// decrypting what comes before them.
+pub struct CtrReader<'a, C: BlockEncrypt, R> {
+    cipher: &'a C,
+    iv: Block<C>,
+    reader: R,
+    start: u64,
+    position: u64,
+}
+
+impl<'a, C: BlockEncrypt, R: Read + Seek> CtrReader<'a, C, R> {
+    pub fn new(cipher: &'a C, iv: &Block<C>, mut reader: R) -> io::Result<Self> {
+        let start = reader.stream_position()?;
+
+        Ok(Self {
+            cipher,
+            iv: iv.clone(),
+            reader,
+            start,
+            position: 0,
+        })
+    }
+
+    /// Read plaintext at `offset` bytes into the ciphertext, moving the position there.
+    pub fn read_at(&mut self, buf: &mut [u8], offset: u64) -> io::Result<usize> {
+        self.seek(SeekFrom::Start(offset))?;
+        self.read(buf)
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+impl<C: BlockEncrypt, R: Read> Read for CtrReader<'_, C, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+
+        apply_ctr_at(self.cipher, &self.iv, self.position, &mut buf[..read]);
+        self.position += read as u64;
+
+        Ok(read)
+    }
+}
+
+impl<C: BlockEncrypt, R: Seek> Seek for CtrReader<'_, C, R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(offset) => SeekFrom::Start(self.start + offset),
+            relative => relative,
+        };
+        let position = self.reader.seek(pos)?;
+
+        if position < self.start {
+            self.reader
+                .seek(SeekFrom::Start(self.start + self.position))?;
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "seek before the start of the ciphertext",
+            ));
+        }
+
+        self.position = position - self.start;
+        Ok(self.position)
+    }
+}
+
+/// Single-threaded streaming encryption (or decryption) with a fixed size buffer.
+///
+/// Input is read into one buffer of [StreamEncryptor::buffer_size] bytes, encrypted in place and
+/// written out before the next read. Peak memory is that buffer plus one keystream block, however
+/// long the input, so unbounded streams can be processed with a known footprint. Smaller buffers
+/// mean more read and write calls, not more keystream work.
+pub struct StreamEncryptor<'a, C: BlockEncrypt> {
+    keystream: Continuous<'a, C>,
+    buffer_size: usize,
+}
+
+impl<'a, C: BlockEncrypt> StreamEncryptor<'a, C> {
+    pub fn new(keystream: Keystream<'a, C>) -> Self {
+        Self {
+            keystream: Continuous::new(keystream),
+            buffer_size: BUFFER_SIZE,
+        }
+    }
+
+    /// Buffer size in bytes, rounded up to whole blocks with a minimum of one block. Defaults to
+    /// 64 KiB.
+    pub fn buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size.max(1).div_ceil(C::block_size()) * C::block_size();
+        self
+    }
+
+    /// Encrypt everything read from `reader` into `writer`, returning the number of bytes.
+    ///
+    /// The keystream continues where the previous call stopped, so a stream can be fed from
+    /// several readers in turn.
+    pub fn run<R: Read, W: Write>(&mut self, mut reader: R, mut writer: W) -> io::Result<u64> {
+        let mut buf = vec![0; self.buffer_size];
+        let mut total = 0;
+
+        loop {
+            let read = match reader.read(&mut buf) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+
+            self.keystream.apply(&mut buf[..read]);
+            writer.write_all(&buf[..read])?;
+            total += read as u64;
+        }
+
+        writer.flush()?;
+        Ok(total)
+    }
+}
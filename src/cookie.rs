@@ -0,0 +1,87 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use cipher::{Block, BlockDecrypt, BlockEncrypt, KeyInit};
+use dbl::Dbl;
+
+use crate::{KeyRing, Plaintext, TokenCipher, TokenError};
+
+/// Encrypted session cookies with key rotation.
+///
+/// A cookie value is `<key ID>.<token>`, the hex ID of the [KeyRing] key and a [TokenCipher]
+/// token. Both only use characters allowed in cookie values without quoting, so they survive
+/// any framework and `SameSite` setting unchanged. New cookies use the current key of the ring,
+/// older cookies keep working for as long as their key stays in the ring, whatever its validity
+/// period, and the token tag is compared in constant time.
+///
+/// The cookie name isn't authenticated, use different keys for cookies that must not be
+/// swapped for one another.
+pub struct SessionCookies<C> {
+    keys: KeyRing<TokenCipher<C>>,
+    ttl: Option<Duration>,
+}
+
+impl<C> SessionCookies<C>
+where
+    C: BlockEncrypt + BlockDecrypt + KeyInit,
+    Block<C>: Dbl,
+{
+    pub fn new(keys: KeyRing<TokenCipher<C>>) -> Self {
+        Self { keys, ttl: None }
+    }
+
+    /// Reject cookies older than `ttl`, by default cookies don't expire.
+    pub fn ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = Some(ttl);
+        self
+    }
+
+    /// The key ring, to rotate keys.
+    pub fn keys_mut(&mut self) -> &mut KeyRing<TokenCipher<C>> {
+        &mut self.keys
+    }
+
+    /// Cookie value holding `session`, `None` if no key is valid now.
+    #[cfg(feature = "rand_core")]
+    pub fn encode(
+        &self,
+        session: &[u8],
+        rng: &mut (impl cipher::rand_core::CryptoRng + cipher::rand_core::RngCore),
+    ) -> Option<String> {
+        let iv = crate::keystream::random_iv::<C>(rng);
+        self.encode_at(session, SystemTime::now(), &iv)
+    }
+
+    /// [SessionCookies::encode] at `time` with the given IV, which must be random and never
+    /// reused.
+    pub fn encode_at(&self, session: &[u8], time: SystemTime, iv: &Block<C>) -> Option<String> {
+        let (id, tokens) = self.keys.current_at(time)?;
+
+        Some(format!(
+            "{id:x}.{}",
+            tokens.encrypt_at(session, unix_seconds(time), iv)
+        ))
+    }
+
+    /// Verify and decrypt a cookie value.
+    pub fn decode(&self, cookie: &str) -> Result<Plaintext, TokenError> {
+        self.decode_at(cookie, SystemTime::now())
+    }
+
+    /// [SessionCookies::decode] at `time`.
+    pub fn decode_at(&self, cookie: &str, time: SystemTime) -> Result<Plaintext, TokenError> {
+        let (id, token) = cookie.split_once('.').ok_or(TokenError::Invalid)?;
+        // `from_str_radix` takes a leading `+`, the ID must be hex digits only
+        let tokens = Some(id)
+            .filter(|id| id.bytes().all(|char| char.is_ascii_hexdigit()))
+            .and_then(|id| u32::from_str_radix(id, 16).ok())
+            .and_then(|id| self.keys.get(id))
+            .ok_or(TokenError::Invalid)?;
+
+        tokens.decrypt_at(token, self.ttl, unix_seconds(time))
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
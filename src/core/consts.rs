@@ -5,11 +5,11 @@
 
 use std::ops::{Add, BitXor};
 
-use cipher::typenum::{Diff, Prod, Quot, Sum, U1, U2, U4};
+use cipher::typenum::{Diff, Prod, Quot, Sum, U1, U2, U4, U8};
 use generic_array::{ArrayLength, GenericArray};
 
 // TODO: Sealed
-pub trait Word: Default + Copy + From<u8> + Add<Output = Self> {
+pub trait Word: Default + Copy + From<u8> + Add<Output = Self> + Zeroizable + Vectorizable {
     type Bytes: ArrayLength<u8>;
 
     const ZERO: Self;
@@ -28,9 +28,150 @@ pub trait Word: Default + Copy + From<u8> + Add<Output = Self> {
     fn from_le_bytes(bytes: &GenericArray<u8, Self::Bytes>) -> Self;
     fn to_le_bytes(self) -> GenericArray<u8, Self::Bytes>;
 
+    /// The words `(A, B)` of a block with word sized loads, `None` if `block` isn't two words
+    /// aligned for `Self`.
+    fn pair_from_aligned(_block: &[u8]) -> Option<(Self, Self)> {
+        None
+    }
+
+    /// Store `(A, B)` with word sized stores, `false` if `block` isn't two words aligned for
+    /// `Self`.
+    fn pair_into_aligned(_a: Self, _b: Self, _block: &mut [u8]) -> bool {
+        false
+    }
+
     fn bitxor(self, other: Self) -> Self;
 }
 
+/// Words can be wiped from memory when the `zeroize` feature is enabled.
+#[cfg(feature = "zeroize")]
+pub trait Zeroizable: cipher::zeroize::DefaultIsZeroes {}
+#[cfg(feature = "zeroize")]
+impl<T: cipher::zeroize::DefaultIsZeroes> Zeroizable for T {}
+
+/// Words can be wiped from memory when the `zeroize` feature is enabled.
+#[cfg(not(feature = "zeroize"))]
+pub trait Zeroizable {}
+#[cfg(not(feature = "zeroize"))]
+impl<T> Zeroizable for T {}
+
+/// Number of blocks the portable SIMD backend processes at once.
+#[cfg(feature = "simd")]
+pub const LANES: usize = 8;
+
+/// Words can be processed [LANES] at a time when the `simd` feature is enabled.
+#[cfg(feature = "simd")]
+pub trait Vectorizable: Sized {
+    /// Vector of [LANES] words, arithmetic wraps and shift amounts are taken modulo the word size.
+    type Vector: Copy
+        + std::ops::Add<Output = Self::Vector>
+        + std::ops::Sub<Output = Self::Vector>
+        + BitXor<Output = Self::Vector>
+        + std::ops::BitOr<Output = Self::Vector>
+        + std::ops::Shl<Output = Self::Vector>
+        + std::ops::Shr<Output = Self::Vector>;
+
+    fn splat(word: Self) -> Self::Vector;
+    fn from_array(words: [Self; LANES]) -> Self::Vector;
+    fn to_array(vector: Self::Vector) -> [Self; LANES];
+
+    fn rotate_lanes_left(vector: Self::Vector, n: Self::Vector) -> Self::Vector {
+        vector << n | vector >> (Self::bits() - n)
+    }
+
+    fn rotate_lanes_right(vector: Self::Vector, n: Self::Vector) -> Self::Vector {
+        vector >> n | vector << (Self::bits() - n)
+    }
+
+    /// Word size in bits in every lane.
+    fn bits() -> Self::Vector;
+}
+
+/// Words can be processed [LANES] at a time when the `simd` feature is enabled.
+#[cfg(not(feature = "simd"))]
+pub trait Vectorizable {}
+#[cfg(not(feature = "simd"))]
+impl<T> Vectorizable for T {}
+
+/// Implement [Vectorizable] for a primitive word with [std::simd].
+#[cfg(feature = "simd")]
+macro_rules! impl_vectorizable {
+    ($word:ty) => {
+        impl Vectorizable for $word {
+            type Vector = std::simd::Simd<$word, LANES>;
+
+            fn splat(word: Self) -> Self::Vector {
+                Self::Vector::splat(word)
+            }
+
+            fn from_array(words: [Self; LANES]) -> Self::Vector {
+                Self::Vector::from_array(words)
+            }
+
+            fn to_array(vector: Self::Vector) -> [Self; LANES] {
+                vector.to_array()
+            }
+
+            fn bits() -> Self::Vector {
+                Self::Vector::splat(<$word>::BITS as $word)
+            }
+        }
+    };
+}
+
+#[cfg(feature = "simd")]
+impl_vectorizable!(u32);
+#[cfg(feature = "simd")]
+impl_vectorizable!(u8);
+#[cfg(feature = "simd")]
+impl_vectorizable!(u64);
+#[cfg(feature = "simd")]
+impl_vectorizable!(u16);
+
+/// Data dependent rotation using only rotations by constants.
+///
+/// The amount is applied one bit at a time, each step rotating by a fixed power of two and
+/// selecting the result with a mask instead of a branch. Cores without a barrel shifter take a
+/// cycle per bit shifted, so there the native rotation leaks the amount through its timing while
+/// this takes the same time for every amount, at the cost of `log2(w)` rotations per call.
+#[cfg(feature = "ct_rotate")]
+macro_rules! ct_rotate_left {
+    ($word:ty, $x:expr, $n:expr) => {{
+        let (mut x, n): ($word, $word) = ($x, $n);
+        let mut bit = 0;
+        while 1 << bit < <$word>::BITS {
+            let mask = <$word>::wrapping_sub(0, n >> bit & 1);
+            x = x.rotate_left(1 << bit) & mask | x & !mask;
+            bit += 1;
+        }
+        x
+    }};
+}
+
+/// [Word::pair_from_aligned] and [Word::pair_into_aligned] for a primitive word.
+macro_rules! aligned_pair {
+    ($word:ty) => {
+        fn pair_from_aligned(block: &[u8]) -> Option<(Self, Self)> {
+            // SAFETY: every bit pattern is a valid integer
+            match unsafe { block.align_to::<$word>() } {
+                ([], [a, b], []) => Some((<$word>::from_le(*a), <$word>::from_le(*b))),
+                _ => None,
+            }
+        }
+
+        fn pair_into_aligned(a: Self, b: Self, block: &mut [u8]) -> bool {
+            // SAFETY: every bit pattern is a valid integer
+            match unsafe { block.align_to_mut::<$word>() } {
+                ([], [left, right], []) => {
+                    (*left, *right) = (a.to_le(), b.to_le());
+                    true
+                }
+                _ => false,
+            }
+        }
+    };
+}
+
 impl Word for u32 {
     type Bytes = U4;
 
@@ -49,14 +190,26 @@ impl Word for u32 {
         u32::wrapping_sub(self, rhs)
     }
 
+    #[cfg(not(feature = "ct_rotate"))]
     fn rotate_left(self, n: Self) -> Self {
         u32::rotate_left(self, n)
     }
 
+    #[cfg(not(feature = "ct_rotate"))]
     fn rotate_right(self, n: Self) -> Self {
         u32::rotate_right(self, n)
     }
 
+    #[cfg(feature = "ct_rotate")]
+    fn rotate_left(self, n: Self) -> Self {
+        ct_rotate_left!(u32, self, n)
+    }
+
+    #[cfg(feature = "ct_rotate")]
+    fn rotate_right(self, n: Self) -> Self {
+        ct_rotate_left!(u32, self, n.wrapping_neg())
+    }
+
     fn from_le_bytes(bytes: &GenericArray<u8, Self::Bytes>) -> Self {
         u32::from_le_bytes(bytes.to_owned().into())
     }
@@ -65,11 +218,175 @@ impl Word for u32 {
         u32::to_le_bytes(self).into()
     }
 
+    aligned_pair!(u32);
+
     fn bitxor(self, other: Self) -> Self {
         <u32 as BitXor>::bitxor(self, other)
     }
 }
 
+impl Word for u16 {
+    type Bytes = U2;
+
+    const ZERO: Self = 0;
+    const THREE: Self = 3;
+    const EIGHT: Self = 8;
+
+    const P: Self = 0xb7e1;
+    const Q: Self = 0x9e37;
+
+    fn wrapping_add(self, rhs: Self) -> Self {
+        u16::wrapping_add(self, rhs)
+    }
+
+    fn wrapping_sub(self, rhs: Self) -> Self {
+        u16::wrapping_sub(self, rhs)
+    }
+
+    #[cfg(not(feature = "ct_rotate"))]
+    fn rotate_left(self, n: Self) -> Self {
+        u16::rotate_left(self, n.into())
+    }
+
+    #[cfg(not(feature = "ct_rotate"))]
+    fn rotate_right(self, n: Self) -> Self {
+        u16::rotate_right(self, n.into())
+    }
+
+    #[cfg(feature = "ct_rotate")]
+    fn rotate_left(self, n: Self) -> Self {
+        ct_rotate_left!(u16, self, n)
+    }
+
+    #[cfg(feature = "ct_rotate")]
+    fn rotate_right(self, n: Self) -> Self {
+        ct_rotate_left!(u16, self, n.wrapping_neg())
+    }
+
+    fn from_le_bytes(bytes: &GenericArray<u8, Self::Bytes>) -> Self {
+        u16::from_le_bytes(bytes.to_owned().into())
+    }
+
+    fn to_le_bytes(self) -> GenericArray<u8, Self::Bytes> {
+        u16::to_le_bytes(self).into()
+    }
+
+    aligned_pair!(u16);
+
+    fn bitxor(self, other: Self) -> Self {
+        <u16 as BitXor>::bitxor(self, other)
+    }
+}
+
+impl Word for u64 {
+    type Bytes = U8;
+
+    const ZERO: Self = 0;
+    const THREE: Self = 3;
+    const EIGHT: Self = 8;
+
+    const P: Self = 0xb7e151628aed2a6b;
+    const Q: Self = 0x9e3779b97f4a7c15;
+
+    fn wrapping_add(self, rhs: Self) -> Self {
+        u64::wrapping_add(self, rhs)
+    }
+
+    fn wrapping_sub(self, rhs: Self) -> Self {
+        u64::wrapping_sub(self, rhs)
+    }
+
+    // rotations only use the low bits of the amount, truncating it keeps them
+    #[cfg(not(feature = "ct_rotate"))]
+    fn rotate_left(self, n: Self) -> Self {
+        u64::rotate_left(self, n as u32)
+    }
+
+    #[cfg(not(feature = "ct_rotate"))]
+    fn rotate_right(self, n: Self) -> Self {
+        u64::rotate_right(self, n as u32)
+    }
+
+    #[cfg(feature = "ct_rotate")]
+    fn rotate_left(self, n: Self) -> Self {
+        ct_rotate_left!(u64, self, n)
+    }
+
+    #[cfg(feature = "ct_rotate")]
+    fn rotate_right(self, n: Self) -> Self {
+        ct_rotate_left!(u64, self, n.wrapping_neg())
+    }
+
+    fn from_le_bytes(bytes: &GenericArray<u8, Self::Bytes>) -> Self {
+        u64::from_le_bytes(bytes.to_owned().into())
+    }
+
+    fn to_le_bytes(self) -> GenericArray<u8, Self::Bytes> {
+        u64::to_le_bytes(self).into()
+    }
+
+    aligned_pair!(u64);
+
+    fn bitxor(self, other: Self) -> Self {
+        <u64 as BitXor>::bitxor(self, other)
+    }
+}
+
+/// 8 bit words are far too small for real use, they exist to make exhaustive experiments on
+/// toy parameterizations possible.
+impl Word for u8 {
+    type Bytes = U1;
+
+    const ZERO: Self = 0;
+    const THREE: Self = 3;
+    const EIGHT: Self = 8;
+
+    const P: Self = 0xb7;
+    const Q: Self = 0x9f;
+
+    fn wrapping_add(self, rhs: Self) -> Self {
+        u8::wrapping_add(self, rhs)
+    }
+
+    fn wrapping_sub(self, rhs: Self) -> Self {
+        u8::wrapping_sub(self, rhs)
+    }
+
+    #[cfg(not(feature = "ct_rotate"))]
+    fn rotate_left(self, n: Self) -> Self {
+        u8::rotate_left(self, n.into())
+    }
+
+    #[cfg(not(feature = "ct_rotate"))]
+    fn rotate_right(self, n: Self) -> Self {
+        u8::rotate_right(self, n.into())
+    }
+
+    #[cfg(feature = "ct_rotate")]
+    fn rotate_left(self, n: Self) -> Self {
+        ct_rotate_left!(u8, self, n)
+    }
+
+    #[cfg(feature = "ct_rotate")]
+    fn rotate_right(self, n: Self) -> Self {
+        ct_rotate_left!(u8, self, n.wrapping_neg())
+    }
+
+    fn from_le_bytes(bytes: &GenericArray<u8, Self::Bytes>) -> Self {
+        u8::from_le_bytes(bytes.to_owned().into())
+    }
+
+    fn to_le_bytes(self) -> GenericArray<u8, Self::Bytes> {
+        u8::to_le_bytes(self).into()
+    }
+
+    aligned_pair!(u8);
+
+    fn bitxor(self, other: Self) -> Self {
+        <u8 as BitXor>::bitxor(self, other)
+    }
+}
+
 pub type BlockSize<W> = Prod<<W as Word>::Bytes, U2>;
 pub type ExpandedKeyTableSize<R> = Prod<Sum<R, U1>, U2>;
 pub type KeyAsWordsSize<W, B> = Quot<Diff<Sum<B, <W as Word>::Bytes>, U1>, <W as Word>::Bytes>;
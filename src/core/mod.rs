@@ -24,13 +24,20 @@
 //! 1. Two's complement addition and subtraction of words: [u32::wrapping_add], [u32::wrapping_sub].
 //! 2. Bitwise exclusive or of words: [std::ops::BitXor::bitxor].
 //! 3. Left/Right rotation of words: [u32::rotate_left], [u32::rotate_right].
+//!
+//! ## Timing
+//!
+//! The round function has no branches and no table lookups depending on the key or the data,
+//! the tests of the `codegen` feature check the optimized x86_64 assembly for it. Rotations by
+//! data dependent amounts are constant time on cores with a barrel shifter, for other cores see
+//! the `ct_rotate` feature. Secret values, like key tables and MAC tags, are only compared with
+//! [subtle], whose optimization barriers keep the comparison from exiting early.
 
 pub mod consts;
 pub use consts::*;
 
 use std::{
     cmp::max,
-    convert::TryInto,
     ops::{Add, Div, Mul, Sub},
 };
 
@@ -56,31 +63,65 @@ where
     Quot<Diff<Sum<B, W::Bytes>, U1>, W::Bytes>: ArrayLength<W>,
 {
     fn encrypt(mut block: InOut<'_, '_, Block<W>>, key: &ExpandedKeyTable<W, R>) {
-        let (mut a, mut b) = Self::words_from_block(block.get_in());
+        let (a, b) = encrypt_words(Self::words_from_block(block.get_in()), key);
+
+        Self::block_from_words(a, b, block.get_out())
+    }
+
+    fn decrypt(mut block: InOut<'_, '_, Block<W>>, key: &ExpandedKeyTable<W, R>) {
+        let (a, b) = decrypt_words(Self::words_from_block(block.get_in()), key);
+
+        Self::block_from_words(a, b, block.get_out())
+    }
+
+    /// [RC5::encrypt] of [LANES] blocks at once, one block per vector lane.
+    #[cfg(feature = "simd")]
+    fn encrypt_lanes(blocks: &mut [Block<W>], key: &ExpandedKeyTable<W, R>) {
+        let (mut a, mut b) = Self::vectors_from_blocks(blocks);
 
-        a = a.wrapping_add(key[0]);
-        b = b.wrapping_add(key[1]);
+        a = a + W::splat(key[0]);
+        b = b + W::splat(key[1]);
 
         for i in 1..=R::USIZE {
-            a = a.bitxor(b).rotate_left(b).wrapping_add(key[2 * i]);
-            b = b.bitxor(a).rotate_left(a).wrapping_add(key[2 * i + 1]);
+            a = W::rotate_lanes_left(a ^ b, b) + W::splat(key[2 * i]);
+            b = W::rotate_lanes_left(b ^ a, a) + W::splat(key[2 * i + 1]);
         }
 
-        Self::block_from_words(a, b, block.get_out())
+        Self::blocks_from_vectors(a, b, blocks)
     }
 
-    fn decrypt(mut block: InOut<'_, '_, Block<W>>, key: &ExpandedKeyTable<W, R>) {
-        let (mut a, mut b) = Self::words_from_block(block.get_in());
+    /// [RC5::decrypt] of [LANES] blocks at once, one block per vector lane.
+    #[cfg(feature = "simd")]
+    fn decrypt_lanes(blocks: &mut [Block<W>], key: &ExpandedKeyTable<W, R>) {
+        let (mut a, mut b) = Self::vectors_from_blocks(blocks);
 
         for i in (1..=R::USIZE).rev() {
-            b = b.wrapping_sub(key[2 * i + 1]).rotate_right(a).bitxor(a);
-            a = a.wrapping_sub(key[2 * i]).rotate_right(b).bitxor(b);
+            b = W::rotate_lanes_right(b - W::splat(key[2 * i + 1]), a) ^ a;
+            a = W::rotate_lanes_right(a - W::splat(key[2 * i]), b) ^ b;
         }
 
-        b = b.wrapping_sub(key[1]);
-        a = a.wrapping_sub(key[0]);
+        b = b - W::splat(key[1]);
+        a = a - W::splat(key[0]);
 
-        Self::block_from_words(a, b, block.get_out())
+        Self::blocks_from_vectors(a, b, blocks)
+    }
+
+    #[cfg(feature = "simd")]
+    fn vectors_from_blocks(blocks: &[Block<W>]) -> (W::Vector, W::Vector) {
+        let words: [(W, W); LANES] = std::array::from_fn(|i| Self::words_from_block(&blocks[i]));
+
+        (
+            W::from_array(words.map(|(a, _)| a)),
+            W::from_array(words.map(|(_, b)| b)),
+        )
+    }
+
+    #[cfg(feature = "simd")]
+    fn blocks_from_vectors(a: W::Vector, b: W::Vector, blocks: &mut [Block<W>]) {
+        let (a, b) = (W::to_array(a), W::to_array(b));
+        for (i, block) in blocks.iter_mut().enumerate().take(LANES) {
+            Self::block_from_words(a[i], b[i], block);
+        }
     }
 
     fn substitute_key(key: &Key<B>) -> ExpandedKeyTable<W, R> {
@@ -90,43 +131,57 @@ where
         Self::mix_in(expanded_key_table, key_as_words)
     }
 
-    fn words_from_block(block: &Block<W>) -> (W, W) {
-        // Block size is 2 * word::BYTES so the unwrap is safe
-        let a = W::from_le_bytes(block[..W::Bytes::USIZE].try_into().unwrap());
-        let b = W::from_le_bytes(block[W::Bytes::USIZE..].try_into().unwrap());
+    /// [RC5::substitute_key] using the key bytes as `L`, overwriting the key with zeros.
+    ///
+    /// When `B` is a multiple of the word size the little endian key bytes already are the words
+    /// of `L`, so they are mixed in place and no separate copy of the key is held next to the
+    /// table. Other key sizes need padding and take the copying path.
+    fn substitute_key_in_place(key: &mut Key<B>) -> ExpandedKeyTable<W, R> {
+        let key_table = if B::USIZE > 0 && B::USIZE.is_multiple_of(W::Bytes::USIZE) {
+            let mut key_table = Self::initialize_expanded_key_table();
+            mix_key_table_in_place(&mut key_table, key);
+            key_table
+        } else {
+            Self::substitute_key(key)
+        };
+        key.fill(0);
 
-        (a, b)
+        key_table
     }
 
-    fn block_from_words(a: W, b: W, out_block: &mut Block<W>) {
-        let (left, right) = out_block.split_at_mut(W::Bytes::USIZE);
+    /// Expand `N` keys at once, see [mix_key_tables].
+    fn substitute_keys<const N: usize>(keys: [&Key<B>; N]) -> [ExpandedKeyTable<W, R>; N] {
+        let mut keys_as_words = keys.map(Self::key_into_words);
+        let mut key_tables = [(); N].map(|_| Self::initialize_expanded_key_table());
 
-        left.copy_from_slice(&a.to_le_bytes());
-        right.copy_from_slice(&b.to_le_bytes());
+        mix_key_tables(
+            &mut key_tables.each_mut().map(|table| table.as_mut_slice()),
+            &mut keys_as_words.each_mut().map(|words| words.as_mut_slice()),
+        );
+
+        key_tables
+    }
+
+    fn words_from_block(block: &Block<W>) -> (W, W) {
+        crate::packing::block_to_words(block)
+    }
+
+    fn block_from_words(a: W, b: W, out_block: &mut Block<W>) {
+        crate::packing::words_to_block(a, b, out_block)
     }
 
     fn key_into_words(key: &Key<B>) -> KeyAsWords<W, B> {
-        // can be uninitialized
+        // must be zero initialized
         let mut key_as_words: GenericArray<W, KeyAsWordsSize<W, B>> = GenericArray::default();
-
-        for i in (0..B::USIZE).rev() {
-            key_as_words[i / W::Bytes::USIZE] =
-                key_as_words[i / W::Bytes::USIZE].rotate_left(W::EIGHT) + key[i].into();
-            // no need for wrapping addition since we are adding a byte sized uint onto an uint with its lsb byte zeroed
-        }
+        key_bytes_into_words(key, &mut key_as_words);
 
         key_as_words
     }
 
     fn initialize_expanded_key_table() -> ExpandedKeyTable<W, R> {
-        // must be zero initialized
         let mut expanded_key_table: GenericArray<W, Prod<Sum<R, U1>, U2>> =
             generic_array::GenericArray::generate(|_| W::ZERO); // TODO: use default
-
-        expanded_key_table[0] = W::P;
-        for i in 1..expanded_key_table.len() {
-            expanded_key_table[i] = expanded_key_table[i - 1].wrapping_add(W::Q);
-        }
+        initialize_key_table(&mut expanded_key_table);
 
         expanded_key_table
     }
@@ -135,29 +190,200 @@ where
         mut key_table: ExpandedKeyTable<W, R>,
         mut key_as_words: KeyAsWords<W, B>,
     ) -> ExpandedKeyTable<W, R> {
-        let (mut expanded_key_index, mut key_as_words_index) = (0, 0);
-        let (mut a, mut b) = (W::ZERO, W::ZERO);
+        mix_key_table(&mut key_table, &mut key_as_words);
 
-        for _ in 0..3 * max(key_as_words.len(), key_table.len()) {
-            key_table[expanded_key_index] = key_table[expanded_key_index]
-                .wrapping_add(a)
-                .wrapping_add(b)
-                .rotate_left(W::THREE);
+        key_table
+    }
+}
+
+// The rounds take the key table as a slice so that with the `opt-size` feature one out of line
+// copy per word type serves every number of rounds and key size. Otherwise they are inlined into
+// the cipher, where the table length is a constant and the loop can be unrolled.
+
+/// Encrypt the words `(A, B)`, the number of rounds is given by the length of `key`.
+#[cfg_attr(feature = "opt-size", inline(never))]
+pub(crate) fn encrypt_words<W: Word>((mut a, mut b): (W, W), key: &[W]) -> (W, W) {
+    a = a.wrapping_add(key[0]);
+    b = b.wrapping_add(key[1]);
+
+    for round in key[2..].chunks_exact(2) {
+        a = a.bitxor(b).rotate_left(b).wrapping_add(round[0]);
+        b = b.bitxor(a).rotate_left(a).wrapping_add(round[1]);
+    }
+
+    (a, b)
+}
+
+/// Decrypt the words `(A, B)`, the inverse of [encrypt_words].
+#[cfg_attr(feature = "opt-size", inline(never))]
+pub(crate) fn decrypt_words<W: Word>((mut a, mut b): (W, W), key: &[W]) -> (W, W) {
+    for round in key[2..].chunks_exact(2).rev() {
+        b = b.wrapping_sub(round[1]).rotate_right(a).bitxor(a);
+        a = a.wrapping_sub(round[0]).rotate_right(b).bitxor(b);
+    }
+
+    b = b.wrapping_sub(key[1]);
+    a = a.wrapping_sub(key[0]);
+
+    (a, b)
+}
 
-            a = key_table[expanded_key_index];
+/// Compare two expanded key tables in constant time.
+///
+/// The time taken depends only on the table lengths, not on where the tables differ, so secret
+/// key schedules can be compared without a timing side channel.
+pub fn key_tables_ct_eq<W: Word>(a: &[W], b: &[W]) -> bool {
+    use subtle::ConstantTimeEq;
 
-            key_as_words[key_as_words_index] = key_as_words[key_as_words_index]
-                .wrapping_add(a)
-                .wrapping_add(b)
-                // rhs <= word::BITS, which is an u8. so the unwrap is safe
-                .rotate_left(a.wrapping_add(b));
+    if a.len() != b.len() {
+        return false;
+    }
+
+    a.iter()
+        .zip(b)
+        .fold(subtle::Choice::from(1), |equal, (a, b)| {
+            equal & a.to_le_bytes().ct_eq(&b.to_le_bytes())
+        })
+        .into()
+}
 
-            b = key_as_words[key_as_words_index];
+// The key schedule steps work on slices so they can also be used with a number of rounds
+// only known at runtime.
+
+/// Load the key bytes into words, `key_as_words` must be zero initialized.
+pub(crate) fn key_bytes_into_words<W: Word>(key: &[u8], key_as_words: &mut [W]) {
+    for i in (0..key.len()).rev() {
+        key_as_words[i / W::Bytes::USIZE] =
+            key_as_words[i / W::Bytes::USIZE].rotate_left(W::EIGHT) + key[i].into();
+        // no need for wrapping addition since we are adding a byte sized uint onto an uint with its lsb byte zeroed
+    }
+}
 
-            expanded_key_index = (expanded_key_index + 1) % key_table.len();
-            key_as_words_index = (key_as_words_index + 1) % key_as_words.len();
+pub(crate) fn initialize_key_table<W: Word>(key_table: &mut [W]) {
+    key_table[0] = W::P;
+    for i in 1..key_table.len() {
+        key_table[i] = key_table[i - 1].wrapping_add(W::Q);
+    }
+}
+
+/// Mix `L` into the key table. An empty key is mixed in as the single zero word the paper gives
+/// it, `c = max(1, ceil(b / u))`.
+pub(crate) fn mix_key_table<W: Word>(key_table: &mut [W], key_as_words: &mut [W]) {
+    let mut zero = [W::ZERO];
+    let key_as_words = match key_as_words {
+        [] => &mut zero[..],
+        words => words,
+    };
+
+    let (mut expanded_key_index, mut key_as_words_index) = (0, 0);
+    let (mut a, mut b) = (W::ZERO, W::ZERO);
+
+    for _ in 0..3 * max(key_as_words.len(), key_table.len()) {
+        key_table[expanded_key_index] = key_table[expanded_key_index]
+            .wrapping_add(a)
+            .wrapping_add(b)
+            .rotate_left(W::THREE);
+
+        a = key_table[expanded_key_index];
+
+        key_as_words[key_as_words_index] = key_as_words[key_as_words_index]
+            .wrapping_add(a)
+            .wrapping_add(b)
+            .rotate_left(a.wrapping_add(b));
+
+        b = key_as_words[key_as_words_index];
+
+        expanded_key_index = (expanded_key_index + 1) % key_table.len();
+        key_as_words_index = (key_as_words_index + 1) % key_as_words.len();
+    }
+}
+
+/// [mix_key_table] with `L` stored little endian in `key`, whose length must be a non zero
+/// multiple of the word size.
+pub(crate) fn mix_key_table_in_place<W: Word>(key_table: &mut [W], key: &mut [u8]) {
+    let word_bytes = W::Bytes::USIZE;
+    let key_words = key.len() / word_bytes;
+
+    let (mut expanded_key_index, mut key_as_words_index) = (0, 0);
+    let (mut a, mut b) = (W::ZERO, W::ZERO);
+
+    for _ in 0..3 * max(key_words, key_table.len()) {
+        key_table[expanded_key_index] = key_table[expanded_key_index]
+            .wrapping_add(a)
+            .wrapping_add(b)
+            .rotate_left(W::THREE);
+
+        a = key_table[expanded_key_index];
+
+        let word = &mut key[key_as_words_index * word_bytes..][..word_bytes];
+        b = W::from_le_bytes(GenericArray::from_slice(word))
+            .wrapping_add(a)
+            .wrapping_add(b)
+            .rotate_left(a.wrapping_add(b));
+        word.copy_from_slice(&b.to_le_bytes());
+
+        expanded_key_index = (expanded_key_index + 1) % key_table.len();
+        key_as_words_index = (key_as_words_index + 1) % key_words;
+    }
+}
+
+/// [mix_key_table] for `N` keys in lockstep.
+///
+/// The tables are interleaved so the same step of every key is done on adjacent words, which
+/// lets the compiler vectorize the mixing loop across keys. All tables must have the same length,
+/// as must all `keys_as_words`. Empty keys are mixed in as one zero word, like in [mix_key_table].
+pub(crate) fn mix_key_tables<W: Word, const N: usize>(
+    key_tables: &mut [&mut [W]; N],
+    keys_as_words: &mut [&mut [W]; N],
+) {
+    let interleave = |slices: &[&mut [W]; N]| -> Vec<[W; N]> {
+        (0..slices.first().map_or(0, |slice| slice.len()))
+            .map(|i| std::array::from_fn(|key| slices[key][i]))
+            .collect()
+    };
+    let (mut key_table, mut key_as_words) = (interleave(key_tables), interleave(keys_as_words));
+    if key_as_words.is_empty() {
+        key_as_words.push([W::ZERO; N]);
+    }
+
+    let (mut expanded_key_index, mut key_as_words_index) = (0, 0);
+    let (mut a, mut b) = ([W::ZERO; N], [W::ZERO; N]);
+
+    for _ in 0..3 * max(key_as_words.len(), key_table.len()) {
+        let words = &mut key_table[expanded_key_index];
+        for key in 0..N {
+            words[key] = words[key]
+                .wrapping_add(a[key])
+                .wrapping_add(b[key])
+                .rotate_left(W::THREE);
+            a[key] = words[key];
         }
 
-        key_table
+        let words = &mut key_as_words[key_as_words_index];
+        for key in 0..N {
+            words[key] = words[key]
+                .wrapping_add(a[key])
+                .wrapping_add(b[key])
+                .rotate_left(a[key].wrapping_add(b[key]));
+            b[key] = words[key];
+        }
+
+        expanded_key_index = (expanded_key_index + 1) % key_table.len();
+        key_as_words_index = (key_as_words_index + 1) % key_as_words.len();
+    }
+
+    for (i, words) in key_table.iter().enumerate() {
+        for key in 0..N {
+            key_tables[key][i] = words[key];
+        }
+    }
+
+    // the interleaved copies hold the key tables and the mixed keys
+    #[cfg(feature = "zeroize")]
+    {
+        use cipher::zeroize::Zeroize;
+
+        key_table.zeroize();
+        key_as_words.zeroize();
     }
 }
@@ -0,0 +1,115 @@
+use std::{
+    fs::{self, File},
+    io::{self, Write},
+    path::PathBuf,
+};
+
+/// Durable storage for a [PersistentCounter].
+pub trait CounterStore {
+    /// The last stored value, `None` if nothing was stored yet.
+    fn load(&mut self) -> io::Result<Option<u64>>;
+
+    /// Replace the stored value. Must be atomic and durable once it returns, after a crash
+    /// [CounterStore::load] returns either the old or the new value.
+    fn store(&mut self, value: u64) -> io::Result<()>;
+}
+
+/// [CounterStore] in a file holding the value as 8 big endian bytes.
+///
+/// Values are written to a temporary file next to it, synced and renamed over the file, so a
+/// crash never leaves a torn value behind.
+pub struct FileCounterStore {
+    path: PathBuf,
+}
+
+impl FileCounterStore {
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self { path: path.into() }
+    }
+}
+
+impl CounterStore for FileCounterStore {
+    fn load(&mut self) -> io::Result<Option<u64>> {
+        match fs::read(&self.path) {
+            Ok(bytes) => {
+                let bytes: [u8; 8] = bytes.try_into().map_err(|_| {
+                    io::Error::new(io::ErrorKind::InvalidData, "counter file is corrupt")
+                })?;
+                Ok(Some(u64::from_be_bytes(bytes)))
+            }
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
+    fn store(&mut self, value: u64) -> io::Result<()> {
+        let mut temp = self.path.clone().into_os_string();
+        temp.push(".tmp");
+
+        let mut file = File::create(&temp)?;
+        file.write_all(&value.to_be_bytes())?;
+        file.sync_all()?;
+        fs::rename(&temp, &self.path)?;
+
+        // make the rename itself durable
+        #[cfg(unix)]
+        if let Some(dir) = self.path.parent() {
+            let dir = if dir.as_os_str().is_empty() {
+                File::open(".")?
+            } else {
+                File::open(dir)?
+            };
+            dir.sync_all()?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Message counter that is never reused across process restarts, for CTR IVs and nonces.
+///
+/// Values are reserved in batches: before handing out the first value of a batch the end of the
+/// batch is persisted, so after a restart counting resumes past every value that may have been
+/// used. A crash skips at most `reserve` values and never repeats one. Larger reservations mean
+/// fewer writes to the store.
+pub struct PersistentCounter<S> {
+    store: S,
+    next: u64,
+    reserved_until: u64,
+    reserve: u64,
+}
+
+impl<S: CounterStore> PersistentCounter<S> {
+    /// Resume from the value in `store`, starting at zero if it is empty.
+    pub fn open(mut store: S, reserve: u64) -> io::Result<Self> {
+        let next = store.load()?.unwrap_or(0);
+
+        Ok(Self {
+            store,
+            next,
+            reserved_until: next,
+            reserve: reserve.max(1),
+        })
+    }
+
+    /// The next unused value, persisting a new reservation first when needed.
+    pub fn next_value(&mut self) -> io::Result<u64> {
+        if self.next == self.reserved_until {
+            let reserved_until = self
+                .next
+                .checked_add(self.reserve)
+                .ok_or_else(|| io::Error::other("counter exhausted, rotate the key"))?;
+            self.store.store(reserved_until)?;
+            self.reserved_until = reserved_until;
+        }
+
+        let value = self.next;
+        self.next += 1;
+
+        Ok(value)
+    }
+
+    pub fn into_inner(self) -> S {
+        self.store
+    }
+}
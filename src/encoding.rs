@@ -0,0 +1,83 @@
+//! Small binary-to-text encodings used by the higher level helpers.
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+const BASE64_URL: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Unpadded URL-safe base64 (RFC 4648 section 5).
+pub(crate) fn base64_url_encode(bytes: &[u8]) -> String {
+    let mut encoded = String::with_capacity((bytes.len() * 4).div_ceil(3));
+
+    for chunk in bytes.chunks(3) {
+        let group = chunk.iter().enumerate().fold(0u32, |group, (i, byte)| {
+            group | u32::from(*byte) << (16 - 8 * i)
+        });
+
+        for i in 0..=chunk.len() {
+            encoded.push(BASE64_URL[(group >> (18 - 6 * i) & 0x3f) as usize].into());
+        }
+    }
+
+    encoded
+}
+
+/// Decode unpadded URL-safe base64, rejecting non canonical encodings.
+pub(crate) fn base64_url_decode(encoded: &str) -> Option<Vec<u8>> {
+    base64_decode_with(encoded.as_bytes(), BASE64_URL)
+}
+
+/// Decode padded standard base64 (RFC 4648 section 4), rejecting non canonical encodings.
+pub(crate) fn base64_decode(encoded: &[u8]) -> Option<Vec<u8>> {
+    if !encoded.len().is_multiple_of(4) {
+        return None;
+    }
+
+    let unpadded = encoded
+        .strip_suffix(b"==")
+        .or_else(|| encoded.strip_suffix(b"="))
+        .unwrap_or(encoded);
+    base64_decode_with(unpadded, BASE64)
+}
+
+fn base64_decode_with(encoded: &[u8], alphabet: &[u8; 64]) -> Option<Vec<u8>> {
+    if encoded.len() % 4 == 1 {
+        return None;
+    }
+
+    let mut bytes = Vec::with_capacity(encoded.len() * 3 / 4);
+    for chunk in encoded.chunks(4) {
+        let mut group = 0u32;
+        for (i, char) in chunk.iter().enumerate() {
+            let value = alphabet.iter().position(|c| c == char)? as u32;
+            group |= value << (18 - 6 * i);
+        }
+
+        let len = chunk.len() - 1;
+        // bits past the last byte must be zero for the encoding to be canonical
+        if group & (0xffffff >> (8 * len)) != 0 {
+            return None;
+        }
+        bytes.extend_from_slice(&group.to_be_bytes()[1..=len]);
+    }
+
+    Some(bytes)
+}
+
+/// Lowercase hex.
+pub(crate) fn hex_encode(bytes: &[u8]) -> String {
+    bytes.iter().map(|byte| format!("{byte:02x}")).collect()
+}
+
+/// Decode hex of either case, two digits per byte and nothing else.
+pub(crate) fn hex_decode(encoded: &str) -> Option<Vec<u8>> {
+    if !encoded.len().is_multiple_of(2) {
+        return None;
+    }
+
+    // not `u8::from_str_radix`, which takes a sign, so `+f` would pass as a byte
+    let digit = |char: u8| char::from(char).to_digit(16);
+    encoded
+        .as_bytes()
+        .chunks_exact(2)
+        .map(|digits| Some((digit(digits[0])? << 4 | digit(digits[1])?) as u8))
+        .collect()
+}
@@ -0,0 +1,193 @@
+use std::fmt;
+
+use cipher::{typenum::Unsigned, Block};
+use digest::Mac;
+
+use crate::{core::Word, EtM, EtmMode, KeyRing, KeystreamMode, Plaintext, Rc5Variant};
+
+/// Format version written by [EtM::seal](crate::EtM::seal), the only one decrypted.
+pub const ENVELOPE_VERSION: u8 = 1;
+
+/// Header in front of every envelope, naming what it was encrypted with.
+///
+/// Encoded as `version || word size || rounds || key size || mode || flags || key ID`, the word
+/// size in bits, the key size in bytes, the key ID a big endian `u32` and the rest one byte each,
+/// [EnvelopeHeader::LEN] bytes. The mode is `1` for CBC-Pad, `2` for CTR and `3` for OFB. Bit 0
+/// of the flags is set for Padmé padded plaintexts, see [EtM::padme], bit 1 for a tag committing
+/// to the keys, see [EtM::committing], the other bits are zero.
+///
+/// The header is authenticated with the envelope. Decryption reads the mode from it and checks
+/// the variant against the cipher, so data can move to stronger parameters while old envelopes
+/// are still opened with the keys and variant they name: parse the header first to pick them.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct EnvelopeHeader {
+    pub version: u8,
+    /// Word size in bits.
+    pub word_size: u8,
+    pub rounds: u8,
+    /// Key size in bytes.
+    pub key_size: u8,
+    pub mode: EtmMode,
+    /// The plaintext was padded to its Padmé length.
+    pub padme: bool,
+    /// The tag commits to the keys.
+    pub committing: bool,
+    /// ID of the key the envelope is encrypted with, see [KeyRing](crate::KeyRing).
+    pub key_id: u32,
+}
+
+impl EnvelopeHeader {
+    /// Encoded length in bytes.
+    pub const LEN: usize = 10;
+
+    /// Header of an envelope encrypted with the RC5 variant `V` in `mode` under the key `key_id`.
+    pub fn new<V: Rc5Variant>(mode: EtmMode, key_id: u32) -> Self {
+        Self {
+            version: ENVELOPE_VERSION,
+            word_size: (<V::Word as Word>::Bytes::USIZE * 8) as u8,
+            rounds: V::Rounds::USIZE as u8,
+            key_size: <V as Rc5Variant>::KeySize::USIZE as u8,
+            mode,
+            padme: false,
+            committing: false,
+            key_id,
+        }
+    }
+
+    /// Whether the header names the RC5 variant `V`.
+    pub fn is_variant<V: Rc5Variant>(&self) -> bool {
+        let expected = Self::new::<V>(self.mode, self.key_id);
+        (self.word_size, self.rounds, self.key_size)
+            == (expected.word_size, expected.rounds, expected.key_size)
+    }
+
+    pub fn to_bytes(&self) -> [u8; Self::LEN] {
+        let mode = match self.mode {
+            EtmMode::CbcPad => 1,
+            EtmMode::Keystream(KeystreamMode::Ctr) => 2,
+            EtmMode::Keystream(KeystreamMode::Ofb) => 3,
+        };
+
+        let mut bytes = [0; Self::LEN];
+        bytes[..6].copy_from_slice(&[
+            self.version,
+            self.word_size,
+            self.rounds,
+            self.key_size,
+            mode,
+            u8::from(self.padme) | u8::from(self.committing) << 1,
+        ]);
+        bytes[6..].copy_from_slice(&self.key_id.to_be_bytes());
+        bytes
+    }
+
+    /// Parse the header at the start of `envelope`, returning it and the rest of the envelope.
+    pub fn parse(envelope: &[u8]) -> Result<(Self, &[u8]), EnvelopeError> {
+        let (header, rest) = envelope
+            .split_first_chunk::<{ Self::LEN }>()
+            .ok_or(EnvelopeError::Malformed)?;
+        let &[version, word_size, rounds, key_size, mode, flags, ..] = header;
+
+        if version != ENVELOPE_VERSION {
+            return Err(EnvelopeError::UnsupportedVersion);
+        }
+        let mode = match mode {
+            1 => EtmMode::CbcPad,
+            2 => EtmMode::Keystream(KeystreamMode::Ctr),
+            3 => EtmMode::Keystream(KeystreamMode::Ofb),
+            _ => return Err(EnvelopeError::Malformed),
+        };
+        if flags & !0b11 != 0 {
+            return Err(EnvelopeError::Malformed);
+        }
+
+        let header = Self {
+            version,
+            word_size,
+            rounds,
+            key_size,
+            mode,
+            padme: flags & 1 != 0,
+            committing: flags & 0b10 != 0,
+            key_id: u32::from_be_bytes(header[6..].try_into().expect("4 bytes")),
+        };
+        Ok((header, rest))
+    }
+}
+
+/// Envelopes under rotating keys: sealed with the current key, opened with the key whose ID the
+/// header names.
+impl<C, M> KeyRing<EtM<C, M>>
+where
+    C: Rc5Variant,
+    M: Mac + Clone,
+{
+    /// [EtM::seal] with the current key and its ID.
+    #[cfg(feature = "rand_core")]
+    pub fn seal(
+        &self,
+        associated_data: &[u8],
+        plaintext: &[u8],
+        rng: &mut (impl cipher::rand_core::CryptoRng + cipher::rand_core::RngCore),
+    ) -> Result<Vec<u8>, EnvelopeError> {
+        let (id, etm) = self.current().ok_or(EnvelopeError::NoCurrentKey)?;
+        Ok(etm.seal(id, associated_data, plaintext, rng))
+    }
+
+    /// [EtM::seal_with_iv] with the current key and its ID.
+    pub fn seal_with_iv(
+        &self,
+        iv: &Block<C>,
+        associated_data: &[u8],
+        plaintext: &[u8],
+    ) -> Result<Vec<u8>, EnvelopeError> {
+        let (id, etm) = self.current().ok_or(EnvelopeError::NoCurrentKey)?;
+        Ok(etm.seal_with_iv(id, iv, associated_data, plaintext))
+    }
+
+    /// [EtM::open] with the key the envelope was sealed with, valid or not.
+    pub fn open(
+        &self,
+        associated_data: &[u8],
+        envelope: &[u8],
+    ) -> Result<Plaintext, EnvelopeError> {
+        let (header, _) = EnvelopeHeader::parse(envelope)?;
+        self.get(header.key_id)
+            .ok_or(EnvelopeError::UnknownKey)?
+            .open(associated_data, envelope)
+    }
+}
+
+/// Error sealing or opening an envelope.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EnvelopeError {
+    /// The header is truncated or names an unknown mode or flag.
+    Malformed,
+    /// The envelope has a format version this crate doesn't know.
+    UnsupportedVersion,
+    /// The envelope was encrypted with another RC5 variant than the cipher.
+    VariantMismatch,
+    /// No key of the ring is valid now.
+    NoCurrentKey,
+    /// The key the envelope was sealed with isn't in the ring.
+    UnknownKey,
+    /// The envelope was modified, truncated or encrypted with other keys or associated data.
+    InvalidCiphertext,
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Malformed => f.write_str("malformed envelope header"),
+            Self::UnsupportedVersion => f.write_str("unsupported envelope version"),
+            Self::VariantMismatch => f.write_str("envelope encrypted with another RC5 variant"),
+            Self::NoCurrentKey => f.write_str("no current key"),
+            Self::UnknownKey => f.write_str("unknown key ID"),
+            Self::InvalidCiphertext => f.write_str("invalid ciphertext"),
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
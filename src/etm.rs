@@ -0,0 +1,292 @@
+use cipher::{typenum::Unsigned, Block, BlockDecrypt, BlockEncrypt};
+use digest::Mac;
+
+use crate::{
+    cbc, padding::padme_padded, unpad_padme, EnvelopeError, EnvelopeHeader, InvalidCiphertext,
+    Keystream, KeystreamMode, Plaintext, Rc5Variant,
+};
+
+/// Mode of operation of [EtM].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum EtmMode {
+    /// CBC with PKCS#7 padding.
+    CbcPad,
+    /// A [Keystream] mode, the ciphertext is as long as the plaintext.
+    Keystream(KeystreamMode),
+}
+
+/// Encrypt-then-MAC over a block cipher mode and any [Mac], HMAC, CMAC or the like.
+///
+/// A message is `IV || ciphertext || tag`, with the tag over
+/// `associated data || IV || ciphertext || associated data length`, the length in bits as a big
+/// endian `u64` like RFC 7518 section 5.2. [EtM::decrypt] checks the tag in constant time before
+/// decrypting anything, so the CBC padding is no oracle.
+///
+/// The cipher and the MAC must have independent keys. The MAC is passed keyed and cloned for each
+/// message.
+///
+/// With an RC5 cipher [EtM::seal] writes an [EnvelopeHeader] in front of the message, naming the
+/// variant, mode and key ID, and authenticates it with the associated data.
+///
+/// Without [EtM::committing] a message can verify under several cipher keys, any cipher key with
+/// the same MAC key, and decrypt to a different plaintext under each.
+pub struct EtM<C, M> {
+    cipher: C,
+    mac: M,
+    mode: EtmMode,
+    padme: bool,
+    committing: bool,
+}
+
+impl<C, M> EtM<C, M>
+where
+    C: BlockEncrypt + BlockDecrypt,
+    M: Mac + Clone,
+{
+    pub fn new(cipher: C, mac: M, mode: EtmMode) -> Self {
+        Self {
+            cipher,
+            mac,
+            mode,
+            padme: false,
+            committing: false,
+        }
+    }
+
+    /// Commit to the keys, so a message only verifies under the keys it was encrypted with.
+    ///
+    /// The tag is also computed over the encryptions of the blocks `0xA5 ... 0xA5 || i` for
+    /// `i = 1, 2, ...`, enough of them for at least 128 bits, which identify the cipher key
+    /// without revealing it. That commits to the MAC key as well when the MAC is collision
+    /// resistant over key and input like HMAC with SHA-2, which CMAC isn't known to be. Needed
+    /// where a ciphertext must not open under two keys, for example when it serves as an
+    /// identifier across tenants. [EtM::decrypt] must be set up the same, [EtM::open] rejects
+    /// envelopes without the commitment.
+    pub fn committing(mut self) -> Self {
+        self.committing = true;
+        self
+    }
+
+    /// Pad plaintexts to their Padmé length before encrypting, see [pad_padme](crate::pad_padme),
+    /// so ciphertext lengths leak less about plaintext lengths. [EtM::decrypt] must be set up the
+    /// same, [EtM::open] follows the flag in the envelope header.
+    pub fn padme(mut self) -> Self {
+        self.padme = true;
+        self
+    }
+
+    /// Encrypt and authenticate `plaintext` and `associated_data` with a random IV.
+    #[cfg(feature = "rand_core")]
+    pub fn encrypt(
+        &self,
+        associated_data: &[u8],
+        plaintext: &[u8],
+        rng: &mut (impl cipher::rand_core::CryptoRng + cipher::rand_core::RngCore),
+    ) -> Vec<u8> {
+        let iv = crate::keystream::random_iv::<C>(rng);
+        self.encrypt_with_iv(&iv, associated_data, plaintext)
+    }
+
+    /// [EtM::encrypt] with the given IV, which must never repeat under one key, and be
+    /// unpredictable for CBC.
+    pub fn encrypt_with_iv(
+        &self,
+        iv: &Block<C>,
+        associated_data: &[u8],
+        plaintext: &[u8],
+    ) -> Vec<u8> {
+        self.encrypt_to(self.mode, iv, associated_data, plaintext, Vec::new())
+    }
+
+    /// Verify and decrypt a message from [EtM::encrypt] with the same associated data.
+    pub fn decrypt(
+        &self,
+        associated_data: &[u8],
+        message: &[u8],
+    ) -> Result<Plaintext, InvalidCiphertext> {
+        self.decrypt_in(
+            self.mode,
+            self.padme,
+            self.committing,
+            associated_data,
+            0,
+            message,
+        )
+    }
+
+    /// Append `IV || ciphertext || tag` to `message`, with the tag over what it held before too.
+    fn encrypt_to(
+        &self,
+        mode: EtmMode,
+        iv: &Block<C>,
+        associated_data: &[u8],
+        plaintext: &[u8],
+        mut message: Vec<u8>,
+    ) -> Vec<u8> {
+        let padded;
+        let plaintext = if self.padme {
+            padded = padme_padded(plaintext);
+            &padded[..]
+        } else {
+            plaintext
+        };
+
+        let start = message.len();
+        message.extend_from_slice(iv);
+        match mode {
+            EtmMode::CbcPad => {
+                cbc::encrypt_padded(&self.cipher, iv, plaintext, &mut message);
+                #[cfg(feature = "nonce-tracking")]
+                crate::nonce_tracking::NonceUse::new("etm", &self.cipher, iv)
+                    .check(plaintext, &message[start + iv.len()..]);
+            }
+            EtmMode::Keystream(mode) => {
+                message.extend_from_slice(plaintext);
+                Keystream::new(&self.cipher, mode, iv).apply(&mut message[start + iv.len()..]);
+            }
+        }
+
+        let tag = self
+            .tag(self.committing, associated_data, &message)
+            .finalize()
+            .into_bytes();
+        message.extend_from_slice(&tag);
+
+        message
+    }
+
+    /// Verify `message` and decrypt what follows its first `prefix` bytes.
+    fn decrypt_in(
+        &self,
+        mode: EtmMode,
+        padme: bool,
+        committing: bool,
+        associated_data: &[u8],
+        prefix: usize,
+        message: &[u8],
+    ) -> Result<Plaintext, InvalidCiphertext> {
+        let block_size = C::BlockSize::USIZE;
+        let tag_size = M::OutputSize::USIZE;
+        if message.len() < prefix + block_size + tag_size {
+            return Err(InvalidCiphertext);
+        }
+
+        let (message, tag) = message.split_at(message.len() - tag_size);
+        if self
+            .tag(committing, associated_data, message)
+            .verify_slice(tag)
+            .is_err()
+        {
+            #[cfg(feature = "metrics")]
+            crate::telemetry::tag_failure("etm");
+            #[cfg(feature = "tracing")]
+            tracing::warn!(scheme = "etm", "authentication failed");
+            return Err(InvalidCiphertext);
+        }
+
+        let (iv, ciphertext) = message[prefix..].split_at(block_size);
+        let mut plaintext = match mode {
+            EtmMode::CbcPad => {
+                cbc::decrypt_padded(&self.cipher, iv, ciphertext).ok_or(InvalidCiphertext)?
+            }
+            EtmMode::Keystream(mode) => {
+                let mut plaintext = Plaintext::from(ciphertext.to_vec());
+                Keystream::new(&self.cipher, mode, Block::<C>::from_slice(iv))
+                    .apply(&mut plaintext);
+                plaintext
+            }
+        };
+
+        if padme {
+            unpad_padme(&mut plaintext).map_err(|_| InvalidCiphertext)?;
+        }
+        Ok(plaintext)
+    }
+
+    fn tag(&self, committing: bool, associated_data: &[u8], message: &[u8]) -> M {
+        let mut mac = self.mac.clone();
+        if committing {
+            let blocks = 16usize.div_ceil(C::BlockSize::USIZE) as u8;
+            for i in 1..=blocks {
+                let mut block = Block::<C>::default();
+                block.fill(0xA5);
+                *block.last_mut().expect("non-empty block") = i;
+                self.cipher.encrypt_block(&mut block);
+                mac.update(&block);
+            }
+        }
+        mac.update(associated_data);
+        mac.update(message);
+        mac.update(&(associated_data.len() as u64 * 8).to_be_bytes());
+        mac
+    }
+}
+
+impl<C, M> EtM<C, M>
+where
+    C: Rc5Variant,
+    M: Mac + Clone,
+{
+    /// Encrypt and authenticate `plaintext` into an envelope, `header || message`, stamped with
+    /// `key_id` and a random IV.
+    #[cfg(feature = "rand_core")]
+    pub fn seal(
+        &self,
+        key_id: u32,
+        associated_data: &[u8],
+        plaintext: &[u8],
+        rng: &mut (impl cipher::rand_core::CryptoRng + cipher::rand_core::RngCore),
+    ) -> Vec<u8> {
+        let iv = crate::keystream::random_iv::<C>(rng);
+        self.seal_with_iv(key_id, &iv, associated_data, plaintext)
+    }
+
+    /// [EtM::seal] with the given IV, which must never repeat under one key, and be
+    /// unpredictable for CBC.
+    pub fn seal_with_iv(
+        &self,
+        key_id: u32,
+        iv: &Block<C>,
+        associated_data: &[u8],
+        plaintext: &[u8],
+    ) -> Vec<u8> {
+        let header = EnvelopeHeader {
+            padme: self.padme,
+            committing: self.committing,
+            ..EnvelopeHeader::new::<C>(self.mode, key_id)
+        };
+        let mut envelope = header.to_bytes().to_vec();
+        envelope.reserve(C::BlockSize::USIZE + plaintext.len() + M::OutputSize::USIZE);
+
+        self.encrypt_to(self.mode, iv, associated_data, plaintext, envelope)
+    }
+
+    /// Verify and decrypt an envelope from [EtM::seal] in the mode its header names.
+    ///
+    /// The key ID isn't checked, it is for picking the key before, see [EnvelopeHeader::parse].
+    pub fn open(
+        &self,
+        associated_data: &[u8],
+        envelope: &[u8],
+    ) -> Result<Plaintext, EnvelopeError> {
+        let (header, _) = EnvelopeHeader::parse(envelope)?;
+        if !header.is_variant::<C>() {
+            return Err(EnvelopeError::VariantMismatch);
+        }
+        // whoever can choose the keys can also leave the commitment out
+        if self.committing && !header.committing {
+            return Err(EnvelopeError::InvalidCiphertext);
+        }
+
+        self.decrypt_in(
+            header.mode,
+            header.padme,
+            header.committing,
+            associated_data,
+            EnvelopeHeader::LEN,
+            envelope,
+        )
+        .map_err(|_| EnvelopeError::InvalidCiphertext)
+    }
+}
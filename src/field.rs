@@ -0,0 +1,355 @@
+use std::{fmt, ops::Add};
+
+use cipher::{
+    generic_array::{ArrayLength, GenericArray},
+    typenum::{Sum, Unsigned},
+    Block, BlockEncrypt, Key, KeyInit, KeySizeUser,
+};
+use dbl::Dbl;
+use subtle::ConstantTimeEq;
+
+use crate::{
+    encoding::{base64_url_decode, base64_url_encode},
+    mac::{cmac, xor, Cmac},
+    KeyRing, Keystream, Plaintext,
+};
+
+/// Deterministic encryption of database fields, SIV mode (RFC 5297) over a block cipher.
+///
+/// The synthetic IV `V = S2V(K1, associated data, plaintext)` is a CMAC over the inputs, the
+/// plaintext is encrypted in CTR mode under `K2` starting at `V` with bits 31 and 63 cleared, and
+/// the ciphertext is `V || C`. With a 128 bit block cipher this is exactly RFC 5297.
+/// Equal plaintexts with equal associated data give equal ciphertexts, so encrypted columns can
+/// be compared for equality, and any change to the ciphertext is detected on decryption.
+///
+/// The trade-offs:
+/// - equality leaks by design, anyone seeing the column learns which rows hold the same value and
+///   how often each value occurs, which is enough to recover low cardinality fields
+/// - the ciphertext length reveals the plaintext length
+/// - with a 64 bit block the synthetic IV is only 64 bits, IV collisions and forgeries become
+///   likely after about `2^32` encryptions under one key, rotate keys well before that
+///
+/// Use the row or column name as associated data so values can't be moved between fields.
+pub struct DeterministicCipher<C> {
+    mac: C,
+    cipher: C,
+}
+
+impl<C> DeterministicCipher<C>
+where
+    C: BlockEncrypt + KeyInit,
+    Block<C>: Dbl,
+{
+    /// Independent keys for the synthetic IV and the CTR encryption.
+    pub fn new(mac_key: &Key<C>, encryption_key: &Key<C>) -> Self {
+        Self {
+            mac: C::new(mac_key),
+            cipher: C::new(encryption_key),
+        }
+    }
+
+    /// Encrypt `plaintext` bound to `associated_data`, returning `V || C`.
+    pub fn encrypt(&self, associated_data: &[&[u8]], plaintext: &[u8]) -> Vec<u8> {
+        let iv = self.s2v(associated_data, plaintext);
+
+        let mut ciphertext = Vec::with_capacity(iv.len() + plaintext.len());
+        ciphertext.extend_from_slice(&iv);
+        ciphertext.extend_from_slice(plaintext);
+        self.apply_keystream(&iv, &mut ciphertext[iv.len()..]);
+
+        ciphertext
+    }
+
+    /// Decrypt and verify a ciphertext produced by [DeterministicCipher::encrypt] with the same
+    /// associated data.
+    pub fn decrypt(
+        &self,
+        associated_data: &[&[u8]],
+        ciphertext: &[u8],
+    ) -> Result<Plaintext, InvalidCiphertext> {
+        if ciphertext.len() < C::BlockSize::USIZE {
+            return Err(InvalidCiphertext);
+        }
+        let (iv, ciphertext) = ciphertext.split_at(C::BlockSize::USIZE);
+        let iv = Block::<C>::clone_from_slice(iv);
+
+        // wiped on drop with the `zeroize` feature, including when verification fails
+        let mut plaintext = Plaintext::from(ciphertext.to_vec());
+        self.apply_keystream(&iv, &mut plaintext);
+
+        let expected = self.s2v(associated_data, &plaintext);
+        // subtle keeps the optimizer from turning the comparison into an early exit
+        if !bool::from(expected.as_slice().ct_eq(&iv)) {
+            #[cfg(feature = "metrics")]
+            crate::telemetry::tag_failure("siv");
+            #[cfg(feature = "tracing")]
+            tracing::warn!(scheme = "siv", "authentication failed");
+            return Err(InvalidCiphertext);
+        }
+
+        Ok(plaintext)
+    }
+
+    fn apply_keystream(&self, iv: &Block<C>, data: &mut [u8]) {
+        // RFC 5297 clears the top bit of the two last 32 bit words so the counter doesn't carry
+        // across them
+        let mut counter = iv.clone();
+        let len = counter.len();
+        counter[len.saturating_sub(8)] &= 0x7F;
+        counter[len - 4] &= 0x7F;
+
+        Keystream::ctr_at(&self.cipher, &counter, 0).apply(data);
+    }
+
+    /// S2V of RFC 5297 section 2.4 over the associated data and the plaintext.
+    fn s2v(&self, associated_data: &[&[u8]], plaintext: &[u8]) -> Block<C> {
+        let mut d = cmac(&self.mac, &Block::<C>::default());
+        for data in associated_data {
+            d = d.dbl();
+            xor(&mut d, &cmac(&self.mac, data));
+        }
+
+        let block_size = C::BlockSize::USIZE;
+        if plaintext.len() >= block_size {
+            let (head, tail) = plaintext.split_at(plaintext.len() - block_size);
+            xor(&mut d, tail);
+            let mut mac = Cmac::new(&self.mac);
+            mac.update(head);
+            mac.update(&d);
+            mac.finalize()
+        } else {
+            d = d.dbl();
+            xor(&mut d, plaintext);
+            d[plaintext.len()] ^= 0x80;
+            cmac(&self.mac, &d)
+        }
+    }
+}
+
+impl<C> KeySizeUser for DeterministicCipher<C>
+where
+    C: KeySizeUser,
+    C::KeySize: Add<C::KeySize>,
+    Sum<C::KeySize, C::KeySize>: ArrayLength<u8>,
+{
+    type KeySize = Sum<C::KeySize, C::KeySize>;
+}
+
+/// Key `K1 || K2` of RFC 5297, the first half for the synthetic IV, the second for CTR.
+impl<C> KeyInit for DeterministicCipher<C>
+where
+    C: BlockEncrypt + KeyInit,
+    C::KeySize: Add<C::KeySize>,
+    Sum<C::KeySize, C::KeySize>: ArrayLength<u8>,
+    Block<C>: Dbl,
+{
+    fn new(key: &Key<Self>) -> Self {
+        let (mac_key, encryption_key) = key.split_at(C::KeySize::USIZE);
+        Self::new(
+            GenericArray::from_slice(mac_key),
+            GenericArray::from_slice(encryption_key),
+        )
+    }
+}
+
+/// Keyed tags for equality lookups over encrypted columns.
+///
+/// The tag is a CMAC of the value, stored in an indexed column next to the ciphertext. Like
+/// [DeterministicCipher] it leaks which rows hold equal values. Truncating the tag, for example to
+/// a few bytes, trades lookup false positives, which the application filters after decryption,
+/// for less leakage. Use a key independent of the encryption keys.
+pub struct BlindIndex<C> {
+    mac: C,
+}
+
+impl<C> BlindIndex<C>
+where
+    C: BlockEncrypt + KeyInit,
+    Block<C>: Dbl,
+{
+    pub fn new(key: &Key<C>) -> Self {
+        Self { mac: C::new(key) }
+    }
+
+    /// Tag of `value`, truncate it to trade precision for leakage.
+    pub fn tag(&self, value: &[u8]) -> Block<C> {
+        cmac(&self.mac, value)
+    }
+}
+
+/// The ciphertext was modified, truncated or encrypted with other keys or associated data.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InvalidCiphertext;
+
+impl fmt::Display for InvalidCiphertext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid ciphertext")
+    }
+}
+
+impl std::error::Error for InvalidCiphertext {}
+
+/// Struct fields sealed in place for storage, with keys from a [KeyRing].
+///
+/// Derive it with `#[derive(EncryptedFields)]` and the `derive` feature, marking each `String` or
+/// `Vec<u8>` field to encrypt with `#[encrypted]`:
+///
+/// ```ignore
+/// #[derive(EncryptedFields)]
+/// struct Customer {
+///     id: u64,
+///     #[encrypted]
+///     email: String,
+///     #[encrypted]
+///     notes: Vec<u8>,
+/// }
+///
+/// customer.seal_fields(&keys)?;
+/// // store customer
+/// customer.open_fields(&keys)?;
+/// ```
+///
+/// Each field is encrypted with [DeterministicCipher] under the current key of the ring, with the
+/// field name as associated data, see [SealField]. The leaks of deterministic encryption apply,
+/// equal values of a field seal to equal ciphertexts until the key rotates. When a field fails,
+/// the fields before it are already converted, discard the value.
+pub trait EncryptedFields {
+    /// Replace every encrypted field with its ciphertext under the current key.
+    fn seal_fields<C>(&mut self, keys: &KeyRing<DeterministicCipher<C>>) -> Result<(), FieldError>
+    where
+        C: BlockEncrypt + KeyInit,
+        Block<C>: Dbl;
+
+    /// Replace every encrypted field with its plaintext, with the key the field was sealed with.
+    fn open_fields<C>(&mut self, keys: &KeyRing<DeterministicCipher<C>>) -> Result<(), FieldError>
+    where
+        C: BlockEncrypt + KeyInit,
+        Block<C>: Dbl;
+}
+
+/// A field value [EncryptedFields] can seal, as the big endian key ID followed by the
+/// [DeterministicCipher] ciphertext, bound to the key ID and the field name. `String` fields hold
+/// it in unpadded URL-safe base64.
+pub trait SealField {
+    fn seal_field<C>(
+        &mut self,
+        name: &str,
+        keys: &KeyRing<DeterministicCipher<C>>,
+    ) -> Result<(), FieldError>
+    where
+        C: BlockEncrypt + KeyInit,
+        Block<C>: Dbl;
+
+    fn open_field<C>(
+        &mut self,
+        name: &str,
+        keys: &KeyRing<DeterministicCipher<C>>,
+    ) -> Result<(), FieldError>
+    where
+        C: BlockEncrypt + KeyInit,
+        Block<C>: Dbl;
+}
+
+impl SealField for Vec<u8> {
+    fn seal_field<C>(
+        &mut self,
+        name: &str,
+        keys: &KeyRing<DeterministicCipher<C>>,
+    ) -> Result<(), FieldError>
+    where
+        C: BlockEncrypt + KeyInit,
+        Block<C>: Dbl,
+    {
+        let (id, cipher) = keys.current().ok_or(FieldError::NoCurrentKey)?;
+        let id = id.to_be_bytes();
+
+        let mut sealed = id.to_vec();
+        sealed.extend(cipher.encrypt(&[&id, name.as_bytes()], self));
+        *self = sealed;
+
+        Ok(())
+    }
+
+    fn open_field<C>(
+        &mut self,
+        name: &str,
+        keys: &KeyRing<DeterministicCipher<C>>,
+    ) -> Result<(), FieldError>
+    where
+        C: BlockEncrypt + KeyInit,
+        Block<C>: Dbl,
+    {
+        let (id, ciphertext) = self
+            .split_first_chunk::<4>()
+            .ok_or(FieldError::InvalidCiphertext)?;
+        let cipher = keys
+            .get(u32::from_be_bytes(*id))
+            .ok_or(FieldError::UnknownKey)?;
+
+        *self = cipher
+            .decrypt(&[id, name.as_bytes()], ciphertext)
+            .map_err(|_| FieldError::InvalidCiphertext)?
+            .to_vec();
+
+        Ok(())
+    }
+}
+
+impl SealField for String {
+    fn seal_field<C>(
+        &mut self,
+        name: &str,
+        keys: &KeyRing<DeterministicCipher<C>>,
+    ) -> Result<(), FieldError>
+    where
+        C: BlockEncrypt + KeyInit,
+        Block<C>: Dbl,
+    {
+        let mut sealed = self.as_bytes().to_vec();
+        sealed.seal_field(name, keys)?;
+        *self = base64_url_encode(&sealed);
+
+        Ok(())
+    }
+
+    fn open_field<C>(
+        &mut self,
+        name: &str,
+        keys: &KeyRing<DeterministicCipher<C>>,
+    ) -> Result<(), FieldError>
+    where
+        C: BlockEncrypt + KeyInit,
+        Block<C>: Dbl,
+    {
+        let mut opened = base64_url_decode(self).ok_or(FieldError::InvalidCiphertext)?;
+        opened.open_field(name, keys)?;
+        *self = String::from_utf8(opened).map_err(|_| FieldError::InvalidCiphertext)?;
+
+        Ok(())
+    }
+}
+
+/// Error sealing or opening a field.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum FieldError {
+    /// No key of the ring is valid now.
+    NoCurrentKey,
+    /// The key the field was sealed with isn't in the ring.
+    UnknownKey,
+    /// The field was modified, truncated, sealed under another name or isn't sealed.
+    InvalidCiphertext,
+}
+
+impl fmt::Display for FieldError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoCurrentKey => f.write_str("no current key"),
+            Self::UnknownKey => f.write_str("unknown key ID"),
+            Self::InvalidCiphertext => f.write_str("invalid ciphertext"),
+        }
+    }
+}
+
+impl std::error::Error for FieldError {}
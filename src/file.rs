@@ -0,0 +1,48 @@
+use std::{fs::OpenOptions, io, path::Path};
+
+use cipher::{Block, BlockEncrypt};
+use memmap2::MmapMut;
+
+use crate::{Keystream, KeystreamMode};
+
+/// Bytes encrypted and flushed to disk at a time.
+const WINDOW_SIZE: usize = 1 << 24;
+
+/// Encrypt the file at `path` in place with the keystream of `cipher` in `mode`, starting at `iv`.
+///
+/// The file is memory mapped and processed in windows of 16 MiB, each flushed to disk before
+/// moving on, so multi-GB files don't have to fit in memory or be copied. Keystream modes are
+/// their own inverse, calling this again with the same key and IV decrypts the file.
+///
+/// The file must not be modified or truncated by another process while it is being encrypted.
+/// An error halfway leaves the file partially encrypted.
+pub fn encrypt_file_in_place<C: BlockEncrypt>(
+    path: impl AsRef<Path>,
+    cipher: &C,
+    mode: KeystreamMode,
+    iv: &Block<C>,
+) -> io::Result<()> {
+    let file = OpenOptions::new().read(true).write(true).open(path)?;
+    if file.metadata()?.len() == 0 {
+        // empty files can't be mapped
+        return Ok(());
+    }
+
+    // SAFETY: the mapping is only accessed through `map`, concurrent modification of the file is
+    // ruled out by the documented contract.
+    let mut map = unsafe { MmapMut::map_mut(&file)? };
+
+    let window_size = WINDOW_SIZE / C::block_size() * C::block_size();
+    let mut keystream = Keystream::new(cipher, mode, iv);
+
+    let len = map.len();
+    for start in (0..len).step_by(window_size) {
+        let window = &mut map[start..(start + window_size).min(len)];
+        keystream.apply(window);
+
+        let window_len = window.len();
+        map.flush_range(start, window_len)?;
+    }
+
+    Ok(())
+}
@@ -0,0 +1,70 @@
+use cipher::{Block, BlockEncrypt};
+use embedded_storage::nor_flash::{ErrorType, NorFlash, ReadNorFlash};
+
+use crate::chunks::apply_ctr_at;
+
+/// NOR flash adapter encrypting everything written to the inner flash.
+///
+/// Data is CTR encrypted with the counter derived from the flash address, so every block of the
+/// flash gets its own keystream and pages can be read and written independently and in any order.
+/// `iv` separates flashes or partitions sharing a key.
+///
+/// The keystream only depends on the address: rewriting a page after an erase reuses its
+/// keystream, and someone who can read the flash before and after learns the XOR of the old and
+/// new contents. There is no integrity protection either, flipped ciphertext bits flip the same
+/// plaintext bits. Erased areas don't read back as `0xFF` through the adapter, and tricks writing
+/// the same word several times to clear more bits don't work on encrypted data.
+pub struct EncryptedFlash<S, C: BlockEncrypt> {
+    flash: S,
+    cipher: C,
+    iv: Block<C>,
+}
+
+impl<S, C: BlockEncrypt> EncryptedFlash<S, C> {
+    pub fn new(flash: S, cipher: C, iv: &Block<C>) -> Self {
+        Self {
+            flash,
+            cipher,
+            iv: iv.clone(),
+        }
+    }
+
+    pub fn into_inner(self) -> S {
+        self.flash
+    }
+}
+
+impl<S: ErrorType, C: BlockEncrypt> ErrorType for EncryptedFlash<S, C> {
+    type Error = S::Error;
+}
+
+impl<S: ReadNorFlash, C: BlockEncrypt> ReadNorFlash for EncryptedFlash<S, C> {
+    const READ_SIZE: usize = S::READ_SIZE;
+
+    fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+        self.flash.read(offset, bytes)?;
+        apply_ctr_at(&self.cipher, &self.iv, offset.into(), bytes);
+
+        Ok(())
+    }
+
+    fn capacity(&self) -> usize {
+        self.flash.capacity()
+    }
+}
+
+impl<S: NorFlash, C: BlockEncrypt> NorFlash for EncryptedFlash<S, C> {
+    const WRITE_SIZE: usize = S::WRITE_SIZE;
+    const ERASE_SIZE: usize = S::ERASE_SIZE;
+
+    fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+        self.flash.erase(from, to)
+    }
+
+    fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+        let mut encrypted = bytes.to_vec();
+        apply_ctr_at(&self.cipher, &self.iv, offset.into(), &mut encrypted);
+
+        self.flash.write(offset, &encrypted)
+    }
+}
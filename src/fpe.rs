@@ -0,0 +1,114 @@
+use std::ops::Range;
+
+use cipher::{consts::U8, BlockEncrypt};
+
+/// Feistel rounds of the small domain permutation, as in FF1.
+const ROUNDS: u64 = 10;
+
+/// Encrypt `x` into another value of `range`, preserving the domain.
+///
+/// A balanced Feistel network with the block cipher as round function permutes the smallest
+/// even bit width covering the range, and cycle walking repeats the permutation until the result
+/// falls back into the range. The permuted domain is at most four times as large as the range,
+/// so only a few walks are needed. Every range gets an unrelated permutation.
+///
+/// # Panics
+/// If `x` is not in `range`.
+pub fn encrypt_in_range<C: BlockEncrypt<BlockSize = U8>>(
+    cipher: &C,
+    x: u64,
+    range: Range<u64>,
+) -> u64 {
+    assert!(range.contains(&x), "value must be in the range");
+    let feistel = Feistel::new(cipher, &range);
+
+    let mut y = feistel.encrypt(x - range.start);
+    while y >= feistel.len {
+        y = feistel.encrypt(y);
+    }
+
+    y + range.start
+}
+
+/// Recover the value encrypted by [encrypt_in_range] with the same range.
+///
+/// # Panics
+/// If `y` is not in `range`.
+pub fn decrypt_in_range<C: BlockEncrypt<BlockSize = U8>>(
+    cipher: &C,
+    y: u64,
+    range: Range<u64>,
+) -> u64 {
+    assert!(range.contains(&y), "value must be in the range");
+    let feistel = Feistel::new(cipher, &range);
+
+    let mut x = feistel.decrypt(y - range.start);
+    while x >= feistel.len {
+        x = feistel.decrypt(x);
+    }
+
+    x + range.start
+}
+
+struct Feistel<'a, C> {
+    cipher: &'a C,
+    len: u64,
+    half_bits: u32,
+    tweak: u64,
+}
+
+impl<'a, C: BlockEncrypt<BlockSize = U8>> Feistel<'a, C> {
+    fn new(cipher: &'a C, range: &Range<u64>) -> Self {
+        let len = range.end - range.start;
+        let bits = u64::BITS - (len - 1).leading_zeros();
+        let mut feistel = Self {
+            cipher,
+            len,
+            half_bits: bits.div_ceil(2).max(1),
+            tweak: 0,
+        };
+        // CBC-MAC over the two fixed length fields, so ranges of the same length starting elsewhere
+        // don't share a permutation
+        feistel.tweak = feistel.encrypt_block(feistel.encrypt_block(range.start) ^ len);
+
+        feistel
+    }
+
+    fn encrypt(&self, x: u64) -> u64 {
+        let (mut left, mut right) = self.split(x);
+        for round in 0..ROUNDS {
+            (left, right) = (right, left ^ self.round(round, right));
+        }
+
+        left << self.half_bits | right
+    }
+
+    fn decrypt(&self, y: u64) -> u64 {
+        let (mut left, mut right) = self.split(y);
+        for round in (0..ROUNDS).rev() {
+            (left, right) = (right ^ self.round(round, left), left);
+        }
+
+        left << self.half_bits | right
+    }
+
+    fn split(&self, x: u64) -> (u64, u64) {
+        (x >> self.half_bits, x & self.mask())
+    }
+
+    /// Round `round` output for `half`, the round number sits above the at most 32 bit half.
+    fn round(&self, round: u64, half: u64) -> u64 {
+        self.encrypt_block(self.tweak ^ (round << 56 | half)) & self.mask()
+    }
+
+    fn mask(&self) -> u64 {
+        (1 << self.half_bits) - 1
+    }
+
+    fn encrypt_block(&self, x: u64) -> u64 {
+        let mut block = x.to_be_bytes().into();
+        self.cipher.encrypt_block(&mut block);
+
+        u64::from_be_bytes(block.into())
+    }
+}
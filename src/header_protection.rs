@@ -0,0 +1,61 @@
+use cipher::{Block, BlockEncrypt, Key, KeyInit};
+
+/// Sample based header protection, the QUIC (RFC 9001, section 5.4) construction over any block
+/// cipher.
+///
+/// The mask is the encryption of a one block sample of the packet ciphertext under a key used
+/// only for header protection. Its first byte masks selected bits of the flags byte and the
+/// following bytes mask the packet number, hiding them from on-path observers. XOR makes
+/// protecting and removing the protection the same operation, but the receiver has to unmask the
+/// flags first when they encode the packet number length.
+///
+/// Take the sample at a fixed offset that assumes the longest packet number, so the sample never
+/// overlaps the packet number whatever its actual length. This obfuscates metadata, integrity
+/// still has to come from authenticating the header with the packet.
+pub struct HeaderProtection<C> {
+    cipher: C,
+}
+
+impl<C: BlockEncrypt + KeyInit> HeaderProtection<C> {
+    pub fn new(key: &Key<C>) -> Self {
+        Self::from_cipher(C::new(key))
+    }
+}
+
+impl<C: BlockEncrypt> HeaderProtection<C> {
+    pub fn from_cipher(cipher: C) -> Self {
+        Self { cipher }
+    }
+
+    /// The mask for a ciphertext `sample`.
+    pub fn mask(&self, sample: &Block<C>) -> Block<C> {
+        let mut mask = sample.clone();
+        self.cipher.encrypt_block(&mut mask);
+        mask
+    }
+
+    /// Mask the bits of `flags` set in `flags_mask` and all of `packet_number`, protecting or
+    /// unprotecting them.
+    ///
+    /// # Panics
+    /// If the packet number is longer than the block size minus one byte.
+    pub fn apply(
+        &self,
+        sample: &Block<C>,
+        flags: &mut u8,
+        flags_mask: u8,
+        packet_number: &mut [u8],
+    ) {
+        assert!(
+            packet_number.len() < C::block_size(),
+            "packet number longer than the mask"
+        );
+
+        let mask = self.mask(sample);
+        *flags ^= mask[0] & flags_mask;
+        packet_number
+            .iter_mut()
+            .zip(&mask[1..])
+            .for_each(|(byte, mask)| *byte ^= mask);
+    }
+}
@@ -0,0 +1,100 @@
+use std::fmt;
+
+use cipher::{consts::U8, BlockDecrypt, BlockEncrypt};
+
+use crate::encoding::{base64_url_decode, base64_url_encode};
+
+/// Encrypt a 64 bit identifier into an 11 character URL-safe token.
+///
+/// The identifier is encrypted as a single big endian block, so this needs a cipher with 64 bit
+/// blocks like [RC5_32_12_16][crate::RC5_32_12_16]. Tokens are deterministic: the same id always
+/// maps to the same token, which hides sequential ids but not repeated ones.
+pub fn encrypt_u64<C: BlockEncrypt<BlockSize = U8>>(cipher: &C, id: u64) -> String {
+    let mut block = id.to_be_bytes().into();
+    cipher.encrypt_block(&mut block);
+
+    base64_url_encode(&block)
+}
+
+/// Recover the identifier from a token created by [encrypt_u64].
+pub fn decrypt_u64<C: BlockDecrypt<BlockSize = U8>>(
+    cipher: &C,
+    token: &str,
+) -> Result<u64, InvalidToken> {
+    let bytes = base64_url_decode(token).ok_or(InvalidToken)?;
+    let bytes: [u8; 8] = bytes.try_into().map_err(|_| InvalidToken)?;
+
+    let mut block = bytes.into();
+    cipher.decrypt_block(&mut block);
+
+    Ok(u64::from_be_bytes(block.into()))
+}
+
+/// Number of Feistel rounds of the UUID permutation, four give a strong pseudorandom permutation.
+const UUID_ROUNDS: u64 = 4;
+
+/// Encrypt a 128 bit UUID into another 128 bit value.
+///
+/// A 64 bit block is too small, so the UUID halves go through a four round Feistel network with
+/// a CBC-MAC of the half and the round number as round function. The result has the same
+/// width but, being a pseudorandom value, generally not valid version and variant bits. Use
+/// `Uuid::from_bytes` and `Uuid::as_bytes` of the `uuid` crate to convert.
+pub fn encrypt_uuid<C: BlockEncrypt<BlockSize = U8>>(cipher: &C, uuid: [u8; 16]) -> [u8; 16] {
+    let (mut left, mut right) = split_uuid(uuid);
+
+    for round in 0..UUID_ROUNDS {
+        (left, right) = (right, left ^ uuid_round(cipher, right, round));
+    }
+
+    join_uuid(left, right)
+}
+
+/// Recover the UUID encrypted by [encrypt_uuid].
+pub fn decrypt_uuid<C: BlockEncrypt<BlockSize = U8>>(cipher: &C, uuid: [u8; 16]) -> [u8; 16] {
+    let (mut left, mut right) = split_uuid(uuid);
+
+    for round in (0..UUID_ROUNDS).rev() {
+        (left, right) = (right ^ uuid_round(cipher, left, round), left);
+    }
+
+    join_uuid(left, right)
+}
+
+/// Round function `E(E(half) ^ round)`, CBC-MAC over the two blocks `half || round`.
+///
+/// The half fills a whole block, so the round number goes in a block of its own. XORing it into
+/// the half would make every round function a shifted copy of the first, `F_r(x) = F_0(x ^ r)`,
+/// while a MAC over both blocks gives each round an independent pseudorandom function.
+fn uuid_round<C: BlockEncrypt<BlockSize = U8>>(cipher: &C, half: u64, round: u64) -> u64 {
+    let mut block = half.to_be_bytes().into();
+    cipher.encrypt_block(&mut block);
+    let mut block = (u64::from_be_bytes(block.into()) ^ round)
+        .to_be_bytes()
+        .into();
+    cipher.encrypt_block(&mut block);
+
+    u64::from_be_bytes(block.into())
+}
+
+fn split_uuid(uuid: [u8; 16]) -> (u64, u64) {
+    let uuid = u128::from_be_bytes(uuid);
+
+    ((uuid >> 64) as u64, uuid as u64)
+}
+
+fn join_uuid(left: u64, right: u64) -> [u8; 16] {
+    (u128::from(left) << 64 | u128::from(right)).to_be_bytes()
+}
+
+/// The token is not the encoding of an encrypted identifier.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InvalidToken;
+
+impl fmt::Display for InvalidToken {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid token")
+    }
+}
+
+impl std::error::Error for InvalidToken {}
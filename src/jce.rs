@@ -0,0 +1,114 @@
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Sub},
+};
+
+use cipher::{
+    consts::{U1, U2},
+    generic_array::{ArrayLength, GenericArray},
+    typenum::{Diff, Quot, Sum, Unsigned},
+    Block, KeyInit,
+};
+
+use crate::{
+    core::{BlockSize, ExpandedKeyTableSize, Word},
+    Rc5,
+};
+
+/// Version field of RC5 1.0, the only version defined by RFC 2040.
+const RC5_VERSION_1_0: u32 = 0x10;
+
+/// The parameters of Java's `javax.crypto.spec.RC5ParameterSpec`.
+///
+/// Old Java backends store these next to the ciphertext, [Rc5::from_parameter_spec] checks them
+/// against an RC5 parameterization so data they wrote can be read back.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Rc5ParameterSpec {
+    /// `getVersion()`, always `0x10` for RC5 1.0.
+    pub version: u32,
+    /// `getRounds()`
+    pub rounds: u32,
+    /// `getWordSize()` in bits.
+    pub word_size: u32,
+    /// `getIV()`, `None` when the spec was created without an IV. JCE providers then generate a
+    /// random IV on encryption, which has to be stored separately.
+    pub iv: Option<Vec<u8>>,
+}
+
+impl<W, R, B> Rc5<W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Unsigned,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+    B: ArrayLength<u8>,
+    B: Add<W::Bytes>,
+    Sum<B, W::Bytes>: Sub<U1>,
+    Diff<Sum<B, W::Bytes>, U1>: Div<W::Bytes>,
+    Quot<Diff<Sum<B, W::Bytes>, U1>, W::Bytes>: ArrayLength<W>,
+{
+    /// Initialize the cipher from a JCE key and `RC5ParameterSpec`, returning the IV of the spec.
+    ///
+    /// JCE picks the word size and number of rounds at runtime, the spec has to match the ones of
+    /// this type and the key has to be `B` bytes long. A JCE IV is one block, as in `RC5/CBC`.
+    pub fn from_parameter_spec(
+        key: &[u8],
+        spec: &Rc5ParameterSpec,
+    ) -> Result<(Self, Option<Block<Self>>), ParameterSpecError> {
+        if spec.version != RC5_VERSION_1_0 {
+            return Err(ParameterSpecError::UnsupportedVersion);
+        }
+        if spec.word_size as usize != W::Bytes::USIZE * 8 {
+            return Err(ParameterSpecError::WordSizeMismatch);
+        }
+        if spec.rounds as usize != R::USIZE {
+            return Err(ParameterSpecError::RoundsMismatch);
+        }
+        if key.len() != B::USIZE {
+            return Err(ParameterSpecError::InvalidKeyLength);
+        }
+
+        let iv = match &spec.iv {
+            Some(iv) if iv.len() != BlockSize::<W>::USIZE => {
+                return Err(ParameterSpecError::InvalidIvLength)
+            }
+            Some(iv) => Some(GenericArray::clone_from_slice(iv)),
+            None => None,
+        };
+
+        Ok((Self::new(GenericArray::from_slice(key)), iv))
+    }
+}
+
+/// Error mapping an [Rc5ParameterSpec] onto an [Rc5] parameterization.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParameterSpecError {
+    /// The version isn't RC5 1.0.
+    UnsupportedVersion,
+    /// The word size differs from the one of the cipher.
+    WordSizeMismatch,
+    /// The number of rounds differs from the one of the cipher.
+    RoundsMismatch,
+    /// The key doesn't have the key size of the cipher.
+    InvalidKeyLength,
+    /// The IV isn't exactly one block.
+    InvalidIvLength,
+}
+
+impl fmt::Display for ParameterSpecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnsupportedVersion => f.write_str("unsupported RC5 version"),
+            Self::WordSizeMismatch => f.write_str("word size doesn't match the cipher"),
+            Self::RoundsMismatch => f.write_str("number of rounds doesn't match the cipher"),
+            Self::InvalidKeyLength => f.write_str("invalid key length"),
+            Self::InvalidIvLength => f.write_str("invalid IV length"),
+        }
+    }
+}
+
+impl std::error::Error for ParameterSpecError {}
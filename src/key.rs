@@ -0,0 +1,245 @@
+use std::{fmt, str::FromStr};
+
+use cipher::{generic_array::ArrayLength, InvalidLength};
+use generic_array::GenericArray;
+
+/// RC5 key of `B` bytes.
+///
+/// Converts from byte arrays, slices and hex strings. The [fmt::Debug] output doesn't show the
+/// key bytes.
+#[derive(Clone)]
+pub struct Rc5Key<B: ArrayLength<u8>>(GenericArray<u8, B>);
+
+impl<B: ArrayLength<u8>> Rc5Key<B> {
+    pub fn new(key: GenericArray<u8, B>) -> Self {
+        Self(key)
+    }
+
+    /// Random key drawn from `rng`.
+    #[cfg(feature = "rand_core")]
+    pub fn generate(
+        rng: &mut (impl cipher::rand_core::CryptoRng + cipher::rand_core::RngCore),
+    ) -> Self {
+        let mut key = Self(GenericArray::default());
+        rng.fill_bytes(&mut key.0);
+        key
+    }
+
+    pub fn as_bytes(&self) -> &GenericArray<u8, B> {
+        &self.0
+    }
+
+    /// Check the key for obvious weaknesses.
+    ///
+    /// Only catches mistakes like hard coded placeholder keys or passwords used directly as keys,
+    /// an empty result doesn't mean the key is random. Keys should come from a CSPRNG or a KDF.
+    pub fn audit(&self) -> Vec<KeyWarning> {
+        let key = self.0.as_slice();
+        if key.iter().all(|&byte| byte == 0) {
+            return vec![KeyWarning::AllZero];
+        }
+
+        let mut warnings = Vec::new();
+
+        let mut counts = [0usize; 256];
+        key.iter().for_each(|&byte| counts[byte as usize] += 1);
+        let entropy: f64 = counts
+            .iter()
+            .filter(|&&count| count > 0)
+            .map(|&count| {
+                let p = count as f64 / key.len() as f64;
+                -p * p.log2()
+            })
+            .sum();
+        // random keys get close to the maximum of log2(len) bits per byte
+        if entropy < (key.len().min(256) as f64).log2() / 2.0 {
+            warnings.push(KeyWarning::LowEntropy);
+        }
+
+        if key.iter().all(|byte| (0x20..=0x7E).contains(byte)) {
+            warnings.push(KeyWarning::AsciiPassword);
+        }
+
+        warnings
+    }
+
+    /// Split the key into `n` random shares which XOR to the key, for dual control.
+    ///
+    /// Any `n - 1` shares reveal nothing about the key, all of them are needed to
+    /// [Rc5Key::combine] it. The shares are [Rc5Key]s, so they are zeroized on drop with the
+    /// `zeroize` feature, as are the intermediate values.
+    ///
+    /// # Panics
+    /// If `n` is zero.
+    #[cfg(feature = "rand_core")]
+    pub fn split(
+        &self,
+        n: usize,
+        rng: &mut (impl cipher::rand_core::CryptoRng + cipher::rand_core::RngCore),
+    ) -> Vec<Self> {
+        assert!(n > 0, "a key needs at least one share");
+
+        let mut last = self.clone();
+        let mut shares: Vec<Self> = (1..n)
+            .map(|_| {
+                let share = Self::generate(rng);
+                xor(&mut last.0, &share.0);
+                share
+            })
+            .collect();
+        shares.push(last);
+
+        shares
+    }
+
+    /// Recombine the shares created by [Rc5Key::split].
+    ///
+    /// # Panics
+    /// If `shares` is empty.
+    pub fn combine(shares: &[Self]) -> Self {
+        let (first, rest) = shares.split_first().expect("no key shares");
+
+        let mut key = first.clone();
+        rest.iter().for_each(|share| xor(&mut key.0, &share.0));
+        key
+    }
+}
+
+fn xor(key: &mut [u8], share: &[u8]) {
+    key.iter_mut().zip(share).for_each(|(k, s)| *k ^= s);
+}
+
+/// Weakness found by [Rc5Key::audit].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum KeyWarning {
+    /// Every key byte is zero, usually a placeholder that was never replaced.
+    AllZero,
+    /// Few distinct byte values, far from what a random key looks like.
+    LowEntropy,
+    /// Every byte is printable ASCII, the key is likely a password used without a KDF.
+    AsciiPassword,
+}
+
+impl fmt::Display for KeyWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AllZero => f.write_str("key is all zero"),
+            Self::LowEntropy => f.write_str("key has very low byte entropy"),
+            Self::AsciiPassword => f.write_str("key looks like an ASCII password"),
+        }
+    }
+}
+
+impl<B: ArrayLength<u8>> AsRef<[u8]> for Rc5Key<B> {
+    fn as_ref(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl<B: ArrayLength<u8>> From<GenericArray<u8, B>> for Rc5Key<B> {
+    fn from(key: GenericArray<u8, B>) -> Self {
+        Self(key)
+    }
+}
+
+impl<B: ArrayLength<u8>, const N: usize> From<[u8; N]> for Rc5Key<B>
+where
+    GenericArray<u8, B>: From<[u8; N]>,
+{
+    fn from(key: [u8; N]) -> Self {
+        Self(key.into())
+    }
+}
+
+impl<B: ArrayLength<u8>> TryFrom<&[u8]> for Rc5Key<B> {
+    type Error = InvalidLength;
+
+    fn try_from(key: &[u8]) -> Result<Self, Self::Error> {
+        if key.len() != B::USIZE {
+            return Err(InvalidLength);
+        }
+
+        Ok(Self(GenericArray::clone_from_slice(key)))
+    }
+}
+
+impl<B: ArrayLength<u8>> FromStr for Rc5Key<B> {
+    type Err = ParseKeyError;
+
+    /// Parse a key from `2 * B` hex digits.
+    fn from_str(hex: &str) -> Result<Self, Self::Err> {
+        let hex = hex.trim().as_bytes();
+        if hex.len() != 2 * B::USIZE {
+            return Err(ParseKeyError::InvalidLength);
+        }
+
+        let mut key = GenericArray::default();
+        for (byte, digits) in key.iter_mut().zip(hex.chunks_exact(2)) {
+            *byte = hex_digit(digits[0])? << 4 | hex_digit(digits[1])?;
+        }
+
+        Ok(Self(key))
+    }
+}
+
+impl<B: ArrayLength<u8>> subtle::ConstantTimeEq for Rc5Key<B> {
+    fn ct_eq(&self, other: &Self) -> subtle::Choice {
+        self.0.as_slice().ct_eq(other.0.as_slice())
+    }
+}
+
+/// Compares in constant time.
+impl<B: ArrayLength<u8>> PartialEq for Rc5Key<B> {
+    fn eq(&self, other: &Self) -> bool {
+        subtle::ConstantTimeEq::ct_eq(self, other).into()
+    }
+}
+
+impl<B: ArrayLength<u8>> Eq for Rc5Key<B> {}
+
+impl<B: ArrayLength<u8>> fmt::Debug for Rc5Key<B> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Rc5Key<{}>(..)", B::USIZE)
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<B: ArrayLength<u8>> cipher::zeroize::ZeroizeOnDrop for Rc5Key<B> {}
+
+#[cfg(feature = "zeroize")]
+impl<B: ArrayLength<u8>> Drop for Rc5Key<B> {
+    fn drop(&mut self) {
+        cipher::zeroize::Zeroize::zeroize(self.0.as_mut_slice());
+    }
+}
+
+/// Error parsing a hex encoded [Rc5Key].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParseKeyError {
+    /// The string doesn't hold exactly two hex digits per key byte.
+    InvalidLength,
+    /// The string contains a character which isn't a hex digit.
+    InvalidDigit,
+}
+
+impl fmt::Display for ParseKeyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidLength => f.write_str("invalid key length"),
+            Self::InvalidDigit => f.write_str("invalid hex digit in key"),
+        }
+    }
+}
+
+impl std::error::Error for ParseKeyError {}
+
+fn hex_digit(digit: u8) -> Result<u8, ParseKeyError> {
+    match digit {
+        b'0'..=b'9' => Ok(digit - b'0'),
+        b'a'..=b'f' => Ok(digit - b'a' + 10),
+        b'A'..=b'F' => Ok(digit - b'A' + 10),
+        _ => Err(ParseKeyError::InvalidDigit),
+    }
+}
@@ -0,0 +1,117 @@
+//! Loading keys from files.
+//!
+//! Key files hold the key as raw bytes, hex, standard base64 or base64 armored between
+//! `-----BEGIN RC5 KEY-----` and `-----END RC5 KEY-----` lines. Surrounding whitespace is ignored
+//! for the text formats. On Unix files readable or writable by the group or others are
+//! rejected, like `ssh` does for private keys.
+//!
+//! Errors are [io::Error]s, decoding failures have the [io::ErrorKind::InvalidData] kind and
+//! don't include any of the file contents.
+
+use std::{fs, io, path::Path};
+
+use cipher::generic_array::ArrayLength;
+
+use crate::{encoding::base64_decode, Rc5Key};
+
+const ARMOR_BEGIN: &str = "-----BEGIN RC5 KEY-----";
+const ARMOR_END: &str = "-----END RC5 KEY-----";
+
+/// Encoding of a key file.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyFormat {
+    /// The key bytes as is.
+    Raw,
+    /// Two hex digits per key byte.
+    Hex,
+    /// Padded standard base64.
+    Base64,
+    /// Base64 between armor lines, possibly split over several lines.
+    Armored,
+}
+
+/// Read a key of `B` bytes from the file at `path`.
+pub fn load<B: ArrayLength<u8>>(
+    path: impl AsRef<Path>,
+    format: KeyFormat,
+) -> io::Result<Rc5Key<B>> {
+    let path = path.as_ref();
+    check_permissions(path)?;
+
+    let mut contents = fs::read(path)?;
+    let key = parse(&contents, format);
+    wipe(&mut contents);
+
+    key
+}
+
+/// Decode a key of `B` bytes from the contents of a key file.
+pub fn parse<B: ArrayLength<u8>>(contents: &[u8], format: KeyFormat) -> io::Result<Rc5Key<B>> {
+    let text = || {
+        std::str::from_utf8(contents)
+            .map(str::trim)
+            .map_err(|_| invalid("key file is not valid text"))
+    };
+
+    let mut bytes = match format {
+        KeyFormat::Raw => contents.to_vec(),
+        KeyFormat::Hex => {
+            return text()?
+                .parse()
+                .map_err(|_| invalid("key file is not valid hex"))
+        }
+        KeyFormat::Base64 => base64_decode(text()?.as_bytes())
+            .ok_or_else(|| invalid("key file is not valid base64"))?,
+        KeyFormat::Armored => {
+            let body = text()?
+                .strip_prefix(ARMOR_BEGIN)
+                .and_then(|body| body.strip_suffix(ARMOR_END))
+                .ok_or_else(|| invalid("key file is missing the armor lines"))?;
+            let mut base64: Vec<u8> = body
+                .bytes()
+                .filter(|byte| !byte.is_ascii_whitespace())
+                .collect();
+            let bytes = base64_decode(&base64);
+            wipe(&mut base64);
+
+            bytes.ok_or_else(|| invalid("key file is not valid base64"))?
+        }
+    };
+
+    let key = Rc5Key::try_from(bytes.as_slice())
+        .map_err(|_| invalid("key file has the wrong key length"));
+    wipe(&mut bytes);
+
+    key
+}
+
+fn invalid(message: &'static str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[cfg(unix)]
+fn check_permissions(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+
+    if fs::metadata(path)?.permissions().mode() & 0o077 != 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "key file is accessible by group or others",
+        ));
+    }
+
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_permissions(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Clear a copy of key material.
+fn wipe(bytes: &mut [u8]) {
+    #[cfg(feature = "zeroize")]
+    cipher::zeroize::Zeroize::zeroize(bytes);
+    #[cfg(not(feature = "zeroize"))]
+    bytes.fill(0);
+}
@@ -0,0 +1,92 @@
+use std::{collections::BTreeMap, time::SystemTime};
+
+use cipher::{Key, KeyInit};
+
+/// Keys identified by an ID, each valid for a period of time, for key rotation.
+///
+/// New data is encrypted with the [current](KeyRing::current) key, the newest one valid right now,
+/// and its ID is stored next to the ciphertext, in the envelope header for a ring of
+/// [EtM](crate::EtM) keys. Older data is decrypted by looking its key up by ID
+/// with [get](KeyRing::get), which ignores validity so expired keys can still decrypt.
+pub struct KeyRing<C> {
+    keys: BTreeMap<u32, RingKey<C>>,
+}
+
+struct RingKey<C> {
+    cipher: C,
+    not_before: SystemTime,
+    not_after: Option<SystemTime>,
+}
+
+impl<C> KeyRing<C> {
+    pub fn new() -> Self {
+        Self {
+            keys: BTreeMap::new(),
+        }
+    }
+
+    /// Add or replace the key `id`, valid from `not_before` until `not_after` (exclusive), forever
+    /// if `None`.
+    pub fn insert(
+        &mut self,
+        id: u32,
+        key: &Key<C>,
+        not_before: SystemTime,
+        not_after: Option<SystemTime>,
+    ) where
+        C: KeyInit,
+    {
+        self.insert_cipher(id, C::new(key), not_before, not_after);
+    }
+
+    /// [KeyRing::insert] for ciphers built from more than a key, like [EtM](crate::EtM).
+    pub fn insert_cipher(
+        &mut self,
+        id: u32,
+        cipher: C,
+        not_before: SystemTime,
+        not_after: Option<SystemTime>,
+    ) {
+        self.keys.insert(
+            id,
+            RingKey {
+                cipher,
+                not_before,
+                not_after,
+            },
+        );
+    }
+
+    /// Remove the key `id`, data encrypted with it can't be decrypted anymore.
+    pub fn remove(&mut self, id: u32) -> bool {
+        self.keys.remove(&id).is_some()
+    }
+
+    /// The key `id` regardless of its validity.
+    pub fn get(&self, id: u32) -> Option<&C> {
+        self.keys.get(&id).map(|key| &key.cipher)
+    }
+
+    /// ID and cipher of the key to encrypt with now, see [KeyRing::current_at].
+    pub fn current(&self) -> Option<(u32, &C)> {
+        self.current_at(SystemTime::now())
+    }
+
+    /// ID and cipher of the newest key valid at `time`, the one with the latest `not_before`.
+    /// Ties go to the higher ID.
+    pub fn current_at(&self, time: SystemTime) -> Option<(u32, &C)> {
+        self.keys
+            .iter()
+            .filter(|(_, key)| {
+                key.not_before <= time && key.not_after.is_none_or(|not_after| time < not_after)
+            })
+            .max_by_key(|(&id, key)| (key.not_before, id))
+            .map(|(&id, key)| (id, &key.cipher))
+    }
+}
+
+impl<C> Default for KeyRing<C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
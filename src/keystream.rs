@@ -0,0 +1,206 @@
+#[cfg(feature = "rand_core")]
+use cipher::{
+    rand_core::{CryptoRng, RngCore},
+    BlockSizeUser,
+};
+use cipher::{Block, BlockEncrypt};
+
+#[cfg(feature = "nonce-tracking")]
+use crate::nonce_tracking::{NonceUse, PREFIX};
+
+/// How keystream blocks are chained.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum KeystreamMode {
+    /// Encrypt a big endian counter starting at the IV.
+    Ctr,
+    /// Repeatedly encrypt the previous output starting with the IV.
+    Ofb,
+}
+
+#[cfg(feature = "nonce-tracking")]
+impl KeystreamMode {
+    fn name(self) -> &'static str {
+        match self {
+            Self::Ctr => "CTR",
+            Self::Ofb => "OFB",
+        }
+    }
+}
+
+/// Keystream of a block cipher in CTR or OFB mode.
+pub struct Keystream<'a, C: BlockEncrypt> {
+    cipher: &'a C,
+    mode: KeystreamMode,
+    state: Block<C>,
+    #[cfg(feature = "nonce-tracking")]
+    nonce_use: Option<NonceUse>,
+}
+
+impl<'a, C: BlockEncrypt> Keystream<'a, C> {
+    /// Keystream starting at `iv`.
+    ///
+    /// With the `nonce-tracking` feature the first [Keystream::apply], or the first data passed
+    /// through a stream adapter like [StreamEncryptor](crate::StreamEncryptor) built on it, panics
+    /// if a different message was processed with the same key and IV before, decrypting or
+    /// encrypting the same message again is allowed.
+    pub fn new(cipher: &'a C, mode: KeystreamMode, iv: &Block<C>) -> Self {
+        Self {
+            cipher,
+            mode,
+            state: iv.clone(),
+            #[cfg(feature = "nonce-tracking")]
+            nonce_use: Some(NonceUse::new(mode.name(), cipher, iv)),
+        }
+    }
+
+    /// Keystream starting at a fresh IV drawn from `rng`, returned for the receiver.
+    ///
+    /// Never reuse an IV with the same key. With a 64 bit block random CTR IVs are likely to
+    /// produce overlapping counter ranges after about `2^32` messages, rotate keys well before.
+    #[cfg(feature = "rand_core")]
+    pub fn new_with_random_iv(
+        cipher: &'a C,
+        mode: KeystreamMode,
+        rng: &mut (impl CryptoRng + RngCore),
+    ) -> (Self, Block<C>) {
+        let iv = random_iv::<C>(rng);
+        (Self::new(cipher, mode, &iv), iv)
+    }
+
+    /// CTR keystream starting `block` blocks after `iv`, so independent ranges of a message can be
+    /// processed in parallel. Ranges of one message share the IV, so its use isn't tracked, the
+    /// parallel adapters check the message as a whole.
+    pub fn ctr_at(cipher: &'a C, iv: &Block<C>, block: u64) -> Self {
+        let mut state = iv.clone();
+        add_be(&mut state, block);
+
+        Self {
+            cipher,
+            mode: KeystreamMode::Ctr,
+            state,
+            #[cfg(feature = "nonce-tracking")]
+            nonce_use: None,
+        }
+    }
+
+    /// The next keystream block.
+    pub fn next_block(&mut self) -> Block<C> {
+        let mut block = self.state.clone();
+        self.cipher.encrypt_block(&mut block);
+
+        match self.mode {
+            KeystreamMode::Ctr => increment_be(&mut self.state),
+            KeystreamMode::Ofb => self.state = block.clone(),
+        }
+
+        block
+    }
+
+    /// Fill `buf` with keystream, discarding the unused rest of the last block.
+    pub fn fill(&mut self, buf: &mut [u8]) {
+        for chunk in buf.chunks_mut(C::block_size()) {
+            chunk.copy_from_slice(&self.next_block()[..chunk.len()]);
+        }
+    }
+
+    /// XOR the keystream into `data`, encrypting or decrypting it in place. Like [Keystream::fill]
+    /// the unused rest of the last block is discarded.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "trace",
+            name = "rc5::keystream",
+            skip_all,
+            fields(mode = ?self.mode, bytes = data.len())
+        )
+    )]
+    pub fn apply(&mut self, data: &mut [u8]) {
+        #[cfg(feature = "nonce-tracking")]
+        if !data.is_empty() {
+            if let Some(nonce_use) = self.nonce_use.take() {
+                let len = data.len().min(PREFIX);
+                let mut input = [0; PREFIX];
+                input[..len].copy_from_slice(&data[..len]);
+                self.xor(data);
+                nonce_use.check(&input[..len], &data[..len]);
+                return;
+            }
+        }
+
+        self.xor(data);
+    }
+
+    /// Hand the nonce use over to an adapter which checks the first data it processes.
+    #[cfg(feature = "nonce-tracking")]
+    pub(crate) fn take_nonce_use(&mut self) -> Option<NonceUse> {
+        self.nonce_use.take()
+    }
+
+    fn xor(&mut self, data: &mut [u8]) {
+        for chunk in data.chunks_mut(C::block_size()) {
+            let block = self.next_block();
+            chunk
+                .iter_mut()
+                .zip(block.iter())
+                .for_each(|(byte, key)| *byte ^= key);
+        }
+    }
+
+    /// [Keystream::apply] to a shared buffer, in place if `data` is its only handle and on a copy
+    /// otherwise.
+    #[cfg(feature = "bytes")]
+    pub fn apply_bytes(&mut self, data: bytes::Bytes) -> bytes::Bytes {
+        let mut data = data
+            .try_into_mut()
+            .unwrap_or_else(|shared| bytes::BytesMut::from(&shared[..]));
+        self.apply(&mut data);
+
+        data.freeze()
+    }
+}
+
+/// Check the nonce use of a CTR message processed in [Keystream::ctr_at] ranges, from the start of
+/// its output. The input is recovered by applying the keystream again.
+#[cfg(feature = "nonce-tracking")]
+pub(crate) fn check_ctr<C: BlockEncrypt>(cipher: &C, iv: &Block<C>, output: &[u8]) {
+    if output.is_empty() {
+        return;
+    }
+
+    let len = output.len().min(PREFIX);
+    let mut input = [0; PREFIX];
+    input[..len].copy_from_slice(&output[..len]);
+    Keystream::ctr_at(cipher, iv, 0).xor(&mut input[..len]);
+    NonceUse::new(KeystreamMode::Ctr.name(), cipher, iv).check(&input[..len], &output[..len]);
+}
+
+/// A block of random bytes to use as IV.
+#[cfg(feature = "rand_core")]
+pub(crate) fn random_iv<C: BlockSizeUser>(rng: &mut (impl CryptoRng + RngCore)) -> Block<C> {
+    let mut iv = Block::<C>::default();
+    rng.fill_bytes(&mut iv);
+    iv
+}
+
+fn increment_be(counter: &mut [u8]) {
+    for byte in counter.iter_mut().rev() {
+        *byte = byte.wrapping_add(1);
+        if *byte != 0 {
+            break;
+        }
+    }
+}
+
+fn add_be(counter: &mut [u8], mut n: u64) {
+    let mut carry = 0;
+    for byte in counter.iter_mut().rev() {
+        let sum = u16::from(*byte) + (n & 0xFF) as u16 + carry;
+        *byte = sum as u8;
+        carry = sum >> 8;
+        n >>= 8;
+        if n == 0 && carry == 0 {
+            break;
+        }
+    }
+}
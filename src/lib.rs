@@ -1,4 +1,5 @@
-//! Implementation of the RC5-32/12/16 block cipher. Based on the [RC5 paper].
+//! Implementation of the RC5 block cipher, RC5-32/12/16 and any other parameterization through
+//! [Rc5]. Based on the [RC5 paper].
 //!
 //! ## WARNING
 //! This crate implements the low-level RC5 block function. It is intended for implementing
@@ -6,11 +7,117 @@
 //!
 //! [RC5 paper]: https://www.grc.com/r&d/rc5.pdf
 
+#![cfg_attr(feature = "simd", feature(portable_simd))]
+
+#[cfg(all(feature = "simd", feature = "opt-size"))]
+compile_error!("the `simd` and `opt-size` features are mutually exclusive");
+
+mod append_log;
 mod block_cipher;
+mod cascade;
+mod cbc;
+mod chunks;
+mod cookie;
 #[cfg(not(feature = "core"))]
 mod core;
 #[cfg(feature = "core")]
 pub mod core;
+mod counter;
+mod encoding;
+#[cfg(feature = "etm")]
+mod envelope;
+#[cfg(feature = "etm")]
+mod etm;
+mod field;
+#[cfg(feature = "mmap")]
+mod file;
+#[cfg(feature = "embedded-storage")]
+mod flash;
+mod fpe;
+mod header_protection;
+mod id;
+mod jce;
+mod key;
+pub mod keyfile;
+mod keyring;
+mod keystream;
+mod mac;
+mod manifest;
+#[cfg(feature = "nonce-tracking")]
+mod nonce_tracking;
+#[cfg(feature = "openssl")]
+pub mod openssl;
+pub mod packing;
+mod padding;
+mod parallel;
+mod parameters;
+mod pipeline;
+#[cfg(feature = "research")]
+pub mod research;
+pub mod search;
+#[cfg(all(feature = "zeroize", feature = "rand_core"))]
+mod secure_buffer;
+mod self_test;
+mod shuffle;
+#[cfg(feature = "metrics")]
+mod telemetry;
+mod token;
+mod triple;
+mod whitening;
+mod xctr;
 
 pub use crate::core::consts::*;
+pub use append_log::*;
 pub use block_cipher::*;
+pub use cascade::*;
+pub use cbc::*;
+pub use chunks::*;
+pub use cookie::*;
+pub use counter::*;
+#[cfg(feature = "etm")]
+pub use envelope::*;
+#[cfg(feature = "etm")]
+pub use etm::*;
+pub use field::*;
+#[cfg(feature = "mmap")]
+pub use file::*;
+#[cfg(feature = "embedded-storage")]
+pub use flash::*;
+pub use fpe::*;
+pub use header_protection::*;
+pub use id::*;
+pub use jce::*;
+pub use key::*;
+pub use keyring::*;
+pub use keystream::*;
+pub use manifest::*;
+pub use padding::*;
+pub use parallel::*;
+pub use parameters::*;
+pub use pipeline::*;
+#[cfg(all(feature = "zeroize", feature = "rand_core"))]
+pub use secure_buffer::*;
+pub use self_test::*;
+pub use shuffle::*;
+pub use token::*;
+pub use triple::*;
+pub use whitening::*;
+pub use xctr::*;
+
+#[cfg(feature = "derive")]
+pub use rc5_derive::EncryptedFields;
+
+/// Decrypted data returned by the one-shot decryptions, wiped on drop with the `zeroize` feature.
+#[cfg(feature = "zeroize")]
+pub type Plaintext = zeroize::Zeroizing<Vec<u8>>;
+/// Decrypted data returned by the one-shot decryptions, wiped on drop with the `zeroize` feature.
+#[cfg(not(feature = "zeroize"))]
+pub type Plaintext = Vec<u8>;
+
+/// Paths used by the code `#[derive(EncryptedFields)]` generates.
+#[cfg(feature = "derive")]
+#[doc(hidden)]
+pub mod __private {
+    pub use cipher::{Block, BlockEncrypt, KeyInit};
+    pub use dbl::Dbl;
+}
@@ -0,0 +1,75 @@
+use cipher::{typenum::Unsigned, Block, BlockEncrypt};
+use dbl::Dbl;
+
+/// CMAC of NIST SP 800-38B.
+pub(crate) fn cmac<C: BlockEncrypt>(cipher: &C, data: &[u8]) -> Block<C>
+where
+    Block<C>: Dbl,
+{
+    let mut mac = Cmac::new(cipher);
+    mac.update(data);
+    mac.finalize()
+}
+
+/// Incremental [cmac], for inputs that don't fit in memory.
+pub(crate) struct Cmac<'a, C: BlockEncrypt> {
+    cipher: &'a C,
+    mac: Block<C>,
+    // the last block is only known to be the last one on finalization, so it is held back
+    buffer: Block<C>,
+    buffered: usize,
+}
+
+impl<'a, C: BlockEncrypt> Cmac<'a, C>
+where
+    Block<C>: Dbl,
+{
+    pub(crate) fn new(cipher: &'a C) -> Self {
+        Self {
+            cipher,
+            mac: Block::<C>::default(),
+            buffer: Block::<C>::default(),
+            buffered: 0,
+        }
+    }
+
+    pub(crate) fn update(&mut self, mut data: &[u8]) {
+        let block_size = C::BlockSize::USIZE;
+
+        while !data.is_empty() {
+            if self.buffered == block_size {
+                xor(&mut self.mac, &self.buffer);
+                self.cipher.encrypt_block(&mut self.mac);
+                self.buffered = 0;
+            }
+
+            let take = data.len().min(block_size - self.buffered);
+            self.buffer[self.buffered..][..take].copy_from_slice(&data[..take]);
+            self.buffered += take;
+            data = &data[take..];
+        }
+    }
+
+    pub(crate) fn finalize(mut self) -> Block<C> {
+        let mut subkey = Block::<C>::default();
+        self.cipher.encrypt_block(&mut subkey);
+        let subkey = subkey.dbl();
+
+        // the last block is complete and masked with the first subkey, or padded and masked with
+        // the second one
+        xor(&mut self.mac, &self.buffer[..self.buffered]);
+        if self.buffered == C::BlockSize::USIZE {
+            xor(&mut self.mac, &subkey);
+        } else {
+            self.mac[self.buffered] ^= 0x80;
+            xor(&mut self.mac, &subkey.dbl());
+        }
+        self.cipher.encrypt_block(&mut self.mac);
+
+        self.mac
+    }
+}
+
+pub(crate) fn xor(block: &mut [u8], data: &[u8]) {
+    block.iter_mut().zip(data).for_each(|(b, d)| *b ^= d);
+}
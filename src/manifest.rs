@@ -0,0 +1,235 @@
+use std::{
+    collections::BTreeMap,
+    fmt, fs,
+    io::{self, Read},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    thread,
+};
+
+use cipher::{Block, BlockEncrypt};
+use dbl::Dbl;
+use subtle::ConstantTimeEq;
+
+use crate::{
+    encoding::{hex_decode, hex_encode},
+    mac::Cmac,
+};
+
+// domain separation of file tags and the manifest tag
+const FILE_TAG: u8 = 0x01;
+const MANIFEST_TAG: u8 = 0x02;
+
+/// Integrity manifests of directory trees, with a CMAC per file.
+///
+/// A manifest has one `<tag> <path>` line per regular file, sorted by path, and ends with a
+/// `manifest <tag>` line authenticating all lines before it. File tags cover the relative path
+/// and the contents, so files can't be swapped or renamed without detection. Anyone with the key
+/// can create manifests, this proves the tree is unchanged since someone holding the key vouched
+/// for it, not who that was.
+///
+/// Files are hashed in parallel. Symbolic links and other special files are skipped, paths must be
+/// valid UTF-8 without line breaks.
+pub struct ManifestSigner<'a, C> {
+    cipher: &'a C,
+    threads: usize,
+}
+
+impl<'a, C> ManifestSigner<'a, C>
+where
+    C: BlockEncrypt + Sync,
+    Block<C>: Dbl,
+{
+    pub fn new(cipher: &'a C) -> Self {
+        Self {
+            cipher,
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        }
+    }
+
+    /// Number of threads, defaults to the available parallelism.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Manifest of every file under `root`.
+    pub fn create(&self, root: impl AsRef<Path>) -> io::Result<String> {
+        let tags = self.tag_tree(root.as_ref())?;
+
+        let mut manifest: String = tags
+            .iter()
+            .map(|(path, tag)| format!("{} {path}\n", hex_encode(tag)))
+            .collect();
+        let tag = self.manifest_tag(&manifest);
+        manifest.push_str(&format!("manifest {}\n", hex_encode(&tag)));
+
+        Ok(manifest)
+    }
+
+    /// Compare the tree under `root` with a manifest, returning every difference.
+    ///
+    /// Fails with [io::ErrorKind::InvalidData] if the manifest is malformed or its tag doesn't
+    /// verify, in which case none of its entries can be trusted.
+    pub fn verify(&self, root: impl AsRef<Path>, manifest: &str) -> io::Result<Vec<Mismatch>> {
+        let expected = self.parse(manifest)?;
+        let actual = self.tag_tree(root.as_ref())?;
+
+        let mut mismatches = Vec::new();
+        for (path, tag) in &expected {
+            match actual.get(path) {
+                None => mismatches.push(Mismatch::Missing(path.into())),
+                Some(actual) if !bool::from(actual.ct_eq(tag)) => {
+                    #[cfg(feature = "metrics")]
+                    crate::telemetry::tag_failure("manifest");
+                    #[cfg(feature = "tracing")]
+                    tracing::warn!(scheme = "manifest", "authentication failed");
+                    mismatches.push(Mismatch::Modified(path.into()))
+                }
+                Some(_) => {}
+            }
+        }
+        mismatches.extend(
+            actual
+                .keys()
+                .filter(|path| !expected.contains_key(*path))
+                .map(|path| Mismatch::Added(path.into())),
+        );
+
+        Ok(mismatches)
+    }
+
+    fn parse(&self, manifest: &str) -> io::Result<BTreeMap<String, Vec<u8>>> {
+        let invalid = || io::Error::new(io::ErrorKind::InvalidData, "invalid manifest");
+
+        let body = manifest.strip_suffix('\n').ok_or_else(invalid)?;
+        let (entries, tag) = match body.rsplit_once('\n') {
+            Some((entries, tag)) => (&manifest[..entries.len() + 1], tag),
+            None => ("", body),
+        };
+        let tag = tag
+            .strip_prefix("manifest ")
+            .and_then(hex_decode)
+            .ok_or_else(invalid)?;
+        if !bool::from(self.manifest_tag(entries).as_slice().ct_eq(&tag)) {
+            #[cfg(feature = "metrics")]
+            crate::telemetry::tag_failure("manifest");
+            #[cfg(feature = "tracing")]
+            tracing::warn!(scheme = "manifest", "authentication failed");
+            return Err(invalid());
+        }
+
+        entries
+            .lines()
+            .map(|line| {
+                let (tag, path) = line.split_once(' ').ok_or_else(invalid)?;
+                Ok((path.to_owned(), hex_decode(tag).ok_or_else(invalid)?))
+            })
+            .collect()
+    }
+
+    fn tag_tree(&self, root: &Path) -> io::Result<BTreeMap<String, Vec<u8>>> {
+        let mut files = Vec::new();
+        list_files(root, &mut PathBuf::new(), &mut files)?;
+
+        let per_thread = files.len().div_ceil(self.threads).max(1);
+        thread::scope(|scope| {
+            let workers: Vec<_> = files
+                .chunks(per_thread)
+                .map(|files| {
+                    scope.spawn(move || {
+                        files
+                            .iter()
+                            .map(|path| Ok((path.clone(), self.file_tag(root, path)?)))
+                            .collect::<io::Result<Vec<_>>>()
+                    })
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .map(|worker| worker.join().expect("manifest worker panicked"))
+                .try_fold(BTreeMap::new(), |mut tags, chunk| {
+                    tags.extend(chunk?);
+                    Ok(tags)
+                })
+        })
+    }
+
+    fn file_tag(&self, root: &Path, path: &str) -> io::Result<Vec<u8>> {
+        let mut mac = Cmac::new(self.cipher);
+        mac.update(&[FILE_TAG]);
+        mac.update(path.as_bytes());
+        mac.update(&[0]);
+
+        let mut file = fs::File::open(root.join(path))?;
+        let mut buf = vec![0; 1 << 16];
+        loop {
+            match file.read(&mut buf) {
+                Ok(0) => break,
+                Ok(read) => mac.update(&buf[..read]),
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Err(err),
+            }
+        }
+
+        Ok(mac.finalize().to_vec())
+    }
+
+    fn manifest_tag(&self, entries: &str) -> Block<C> {
+        let mut mac = Cmac::new(self.cipher);
+        mac.update(&[MANIFEST_TAG]);
+        mac.update(entries.as_bytes());
+        mac.finalize()
+    }
+}
+
+/// Collect the `/` separated paths of the regular files under `root.join(dir)`.
+fn list_files(root: &Path, dir: &mut PathBuf, files: &mut Vec<String>) -> io::Result<()> {
+    for entry in fs::read_dir(root.join(&dir))? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+
+        dir.push(entry.file_name());
+        if file_type.is_dir() {
+            list_files(root, dir, files)?;
+        } else if file_type.is_file() {
+            let path = dir
+                .iter()
+                .map(|part| part.to_str())
+                .collect::<Option<Vec<_>>>()
+                .filter(|parts| parts.iter().all(|part| !part.contains(['\n', '\r'])))
+                .ok_or_else(|| {
+                    io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("unsupported file name {}", dir.display()),
+                    )
+                })?;
+            files.push(path.join("/"));
+        }
+        dir.pop();
+    }
+
+    Ok(())
+}
+
+/// Difference between a directory tree and its manifest.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Mismatch {
+    /// The file contents changed.
+    Modified(PathBuf),
+    /// The file is in the manifest but not in the tree.
+    Missing(PathBuf),
+    /// The file is in the tree but not in the manifest.
+    Added(PathBuf),
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Modified(path) => write!(f, "modified: {}", path.display()),
+            Self::Missing(path) => write!(f, "missing: {}", path.display()),
+            Self::Added(path) => write!(f, "added: {}", path.display()),
+        }
+    }
+}
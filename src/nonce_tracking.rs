@@ -0,0 +1,91 @@
+//! Detection of nonces reused under one key, a development aid behind the `nonce-tracking`
+//! feature.
+//!
+//! The first data processed under a (key, nonce) pair is remembered by a hash of its first
+//! [PREFIX] bytes after processing. A later use of the pair is a repeat of the same message if its
+//! input or output hashes the same, its decryption or the same encryption again, and a reuse
+//! otherwise. Every thread remembers the [CAPACITY] most recent pairs it used, reuse across
+//! threads isn't detected.
+
+use std::{
+    cell::RefCell,
+    collections::{HashMap, VecDeque},
+    hash::{BuildHasher, Hash, RandomState},
+};
+
+use cipher::{Block, BlockEncrypt};
+
+/// Bytes of a message compared to tell a repeat from a different message.
+pub(crate) const PREFIX: usize = 64;
+
+/// Pairs remembered before the oldest is forgotten.
+const CAPACITY: usize = 1 << 16;
+
+struct Uses {
+    hasher: RandomState,
+    /// Prefix length and hash of the first data processed per pair.
+    first: HashMap<u64, (usize, u64)>,
+    order: VecDeque<u64>,
+}
+
+impl Uses {
+    fn hash(&self, value: impl Hash) -> u64 {
+        self.hasher.hash_one(value)
+    }
+}
+
+thread_local! {
+    static USES: RefCell<Uses> = RefCell::new(Uses {
+        hasher: RandomState::new(),
+        first: HashMap::new(),
+        order: VecDeque::new(),
+    });
+}
+
+/// A nonce used under a key, checked against earlier uses of the pair by [NonceUse::check].
+pub(crate) struct NonceUse {
+    scheme: &'static str,
+    pair: u64,
+}
+
+impl NonceUse {
+    pub(crate) fn new<C: BlockEncrypt>(scheme: &'static str, cipher: &C, nonce: &[u8]) -> Self {
+        // the key is identified by its encryption of the zero block, only a hash of it is kept
+        let mut key_id = Block::<C>::default();
+        cipher.encrypt_block(&mut key_id);
+
+        Self {
+            scheme,
+            pair: USES.with_borrow(|uses| uses.hash((scheme, &key_id[..], nonce))),
+        }
+    }
+
+    /// Check the first data processed under the nonce, before and after processing. Only the
+    /// first [PREFIX] bytes of each are used.
+    ///
+    /// # Panics
+    /// If a different message was processed under the same key and nonce before.
+    pub(crate) fn check(self, input: &[u8], output: &[u8]) {
+        let reused = USES.with_borrow_mut(|uses| match uses.first.get(&self.pair) {
+            Some(&(len, first)) => {
+                let differs = |data: &[u8]| data.len() < len || uses.hash(&data[..len]) != first;
+                output.len() >= len && differs(input) && differs(output)
+            }
+            None => {
+                let len = output.len().min(PREFIX);
+                let first = uses.hash(&output[..len]);
+                uses.first.insert(self.pair, (len, first));
+                uses.order.push_back(self.pair);
+                if uses.order.len() > CAPACITY {
+                    let oldest = uses.order.pop_front().expect("over capacity");
+                    uses.first.remove(&oldest);
+                }
+                false
+            }
+        });
+
+        if reused {
+            panic!("{}: nonce reused under the same key", self.scheme);
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! The file format of `openssl enc`.
+//!
+//! `openssl enc -rc5` encrypts with RC5-32/12/16 in CBC mode with PKCS#7 padding, under a key and
+//! IV derived from the password by `EVP_BytesToKey`. With a salt, the default, the output starts
+//! with `Salted__` and the 8 byte salt, OpenSSL 3 leaves it out when the salt is passed with `-S`.
+//! OpenSSL 1.1 changed the default digest of the derivation
+//! from MD5 to SHA-256, pass `-md md5` to newer versions. OpenSSL 3 moved RC5 to the legacy
+//! provider, add `-provider legacy -provider default`. `-pbkdf2` isn't supported.
+//!
+//! ```sh
+//! openssl enc -rc5 -md md5 -in plain.txt -out secret.enc -pass pass:...
+//! ```
+//!
+//! The format has no MAC, modified data decrypts to garbage or fails on the padding, and the
+//! derivation is a single MD5 iteration, trivial to brute force for weak passwords. Use it to read
+//! existing files, not to protect new data.
+
+use std::fmt;
+
+use cipher::{Block, BlockDecrypt, BlockEncrypt, BlockSizeUser, Key, KeyInit};
+use md5::{Digest, Md5};
+
+use crate::{cbc, Plaintext};
+
+/// Start of salted files.
+const MAGIC: &[u8; 8] = b"Salted__";
+
+/// `EVP_BytesToKey` with MD5 and one iteration, filling `key` and then `iv`.
+pub fn bytes_to_key(password: &[u8], salt: Option<&[u8; 8]>, key: &mut [u8], iv: &mut [u8]) {
+    let mut derived = Vec::with_capacity(key.len() + iv.len() + 16);
+    let mut digest = Vec::new();
+
+    while derived.len() < key.len() + iv.len() {
+        let mut md5 = Md5::new();
+        md5.update(&digest);
+        md5.update(password);
+        if let Some(salt) = salt {
+            md5.update(salt);
+        }
+        digest = md5.finalize().to_vec();
+        derived.extend_from_slice(&digest);
+    }
+
+    let (derived_key, derived_iv) = derived.split_at(key.len());
+    key.copy_from_slice(derived_key);
+    iv.copy_from_slice(&derived_iv[..iv.len()]);
+}
+
+/// Encrypt `plaintext` like `openssl enc -md md5` does, with a random salt.
+#[cfg(feature = "rand_core")]
+pub fn encrypt<C: BlockEncrypt + KeyInit>(
+    password: &[u8],
+    plaintext: &[u8],
+    rng: &mut (impl cipher::rand_core::CryptoRng + cipher::rand_core::RngCore),
+) -> Vec<u8> {
+    let mut salt = [0; 8];
+    rng.fill_bytes(&mut salt);
+    encrypt_with_salt::<C>(password, plaintext, Some(&salt))
+}
+
+/// Encrypt `plaintext` with the given salt, or without one like `openssl enc -nosalt`.
+pub fn encrypt_with_salt<C: BlockEncrypt + KeyInit>(
+    password: &[u8],
+    plaintext: &[u8],
+    salt: Option<&[u8; 8]>,
+) -> Vec<u8> {
+    let (cipher, iv) = derive::<C>(password, salt);
+
+    let mut encrypted = Vec::new();
+    if let Some(salt) = salt {
+        encrypted.extend_from_slice(MAGIC);
+        encrypted.extend_from_slice(salt);
+    }
+    cbc::encrypt_padded(&cipher, &iv, plaintext, &mut encrypted);
+
+    encrypted
+}
+
+/// Decrypt the output of `openssl enc`, salted or not.
+pub fn decrypt<C: BlockDecrypt + KeyInit>(
+    password: &[u8],
+    encrypted: &[u8],
+) -> Result<Plaintext, DecryptError> {
+    let (salt, ciphertext) = match encrypted.strip_prefix(MAGIC) {
+        Some(salted) if salted.len() >= 8 => {
+            let (salt, ciphertext) = salted.split_at(8);
+            (Some(salt.try_into().expect("8 bytes")), ciphertext)
+        }
+        Some(_) => return Err(DecryptError),
+        None => (None, encrypted),
+    };
+
+    let (cipher, iv) = derive::<C>(password, salt);
+    cbc::decrypt_padded(&cipher, &iv, ciphertext).ok_or(DecryptError)
+}
+
+fn derive<C: BlockSizeUser + KeyInit>(password: &[u8], salt: Option<&[u8; 8]>) -> (C, Block<C>) {
+    let mut key = Key::<C>::default();
+    let mut iv = Block::<C>::default();
+    bytes_to_key(password, salt, &mut key, &mut iv);
+
+    (C::new(&key), iv)
+}
+
+/// The password is wrong or the data is truncated or corrupted, `openssl enc` reports this as
+/// "bad decrypt".
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DecryptError;
+
+impl fmt::Display for DecryptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("wrong password or corrupted data")
+    }
+}
+
+impl std::error::Error for DecryptError {}
@@ -0,0 +1,48 @@
+//! Little-endian packing of blocks into words, as defined by the RC5 paper.
+//!
+//! A block of `2 * w / 8` bytes holds the two words `A` and `B`. `A` is the first half of the
+//! block and `B` the second, each stored little-endian regardless of the platform: the block
+//! `00 11 22 33 44 55 66 77` of RC5-32 is `A = 0x33221100`, `B = 0x77665544`.
+
+use std::ops::Mul;
+
+use cipher::{consts::U2, generic_array::ArrayLength, typenum::Unsigned};
+
+use crate::core::{Block, BlockSize, Word};
+
+/// Split a block into its words `(A, B)`.
+pub fn block_to_words<W>(block: &Block<W>) -> (W, W)
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+{
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+    if let Some(words) = W::pair_from_aligned(block) {
+        return words;
+    }
+
+    // Block size is 2 * word::BYTES so both halves are exactly one word
+    let a = W::from_le_bytes(block[..W::Bytes::USIZE].into());
+    let b = W::from_le_bytes(block[W::Bytes::USIZE..].into());
+
+    (a, b)
+}
+
+/// Join the words `A` and `B` into `block`.
+pub fn words_to_block<W>(a: W, b: W, block: &mut Block<W>)
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+{
+    #[cfg(not(any(target_arch = "x86", target_arch = "x86_64", target_arch = "aarch64")))]
+    if W::pair_into_aligned(a, b, block) {
+        return;
+    }
+
+    let (left, right) = block.split_at_mut(W::Bytes::USIZE);
+
+    left.copy_from_slice(&a.to_le_bytes());
+    right.copy_from_slice(&b.to_le_bytes());
+}
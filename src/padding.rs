@@ -0,0 +1,67 @@
+use std::fmt;
+
+use crate::Plaintext;
+
+/// Padded length of a `len` byte message with Padmé.
+///
+/// Padmé (Nikitin et al., "Reducing Metadata Leakage from Encrypted Files and Communication with
+/// PURBs") rounds the length up so only its `O(log log len)` most significant bits remain,
+/// which leaks `O(log log M)` bits about the size of messages up to `M` bytes, at most 12%
+/// overhead.
+pub fn padme_len(len: usize) -> usize {
+    if len < 2 {
+        return len;
+    }
+
+    let exponent = len.ilog2();
+    let mantissa_bits = exponent.ilog2() + 1;
+    let mask = (1 << (exponent - mantissa_bits)) - 1;
+
+    (len + mask) & !mask
+}
+
+/// Pad `data` to the Padmé length of its length plus one with `0x80` followed by zeros, to be
+/// done before encryption so the ciphertext length hides the plaintext length. Works with every
+/// encryption API of the crate, [unpad_padme] after decryption.
+/// [CbcPad::padme](crate::CbcPad::padme) and `EtM::padme` do both.
+pub fn pad_padme(data: &mut Vec<u8>) {
+    let len = padme_len(data.len() + 1);
+
+    data.push(0x80);
+    data.resize(len, 0);
+}
+
+/// Copy of `data` padded by [pad_padme], wiped on drop with the `zeroize` feature.
+pub(crate) fn padme_padded(data: &[u8]) -> Plaintext {
+    let mut padded = Plaintext::default();
+    padded.reserve_exact(padme_len(data.len() + 1));
+    padded.extend_from_slice(data);
+    pad_padme(&mut padded);
+
+    padded
+}
+
+/// Remove the padding added by [pad_padme].
+pub fn unpad_padme(data: &mut Vec<u8>) -> Result<(), InvalidPadding> {
+    let marker = data
+        .iter()
+        .rposition(|&byte| byte != 0)
+        .filter(|&marker| data[marker] == 0x80)
+        .ok_or(InvalidPadding)?;
+
+    data.truncate(marker);
+    Ok(())
+}
+
+/// The data doesn't end in `0x80` followed by zeros.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct InvalidPadding;
+
+impl fmt::Display for InvalidPadding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("invalid padding")
+    }
+}
+
+impl std::error::Error for InvalidPadding {}
@@ -0,0 +1,104 @@
+use std::{num::NonZeroUsize, thread};
+
+use cipher::{Block, BlockDecrypt, BlockEncrypt};
+
+use crate::Keystream;
+
+/// Bulk ECB and CTR processing split across scoped std threads, without a thread pool.
+///
+/// The input is cut into one contiguous range per thread and every thread works in place on its
+/// own range, so the output is in input order and identical to single-threaded processing. Each
+/// call spawns and joins its threads, which only pays off for inputs of many kilobytes.
+pub struct ParallelCipher<'a, C> {
+    cipher: &'a C,
+    threads: usize,
+}
+
+impl<'a, C: Sync> ParallelCipher<'a, C> {
+    pub fn new(cipher: &'a C) -> Self {
+        Self {
+            cipher,
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        }
+    }
+
+    /// Number of threads, defaults to the available parallelism.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Encrypt `blocks` in place in ECB mode.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "rc5::parallel",
+            skip_all,
+            fields(op = "encrypt", bytes = blocks.len() * C::block_size(), threads = self.threads)
+        )
+    )]
+    pub fn encrypt_blocks(&self, blocks: &mut [Block<C>])
+    where
+        C: BlockEncrypt,
+    {
+        self.for_each_range(blocks, |range| self.cipher.encrypt_blocks(range));
+    }
+
+    /// Decrypt `blocks` in place in ECB mode.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "rc5::parallel",
+            skip_all,
+            fields(op = "decrypt", bytes = blocks.len() * C::block_size(), threads = self.threads)
+        )
+    )]
+    pub fn decrypt_blocks(&self, blocks: &mut [Block<C>])
+    where
+        C: BlockDecrypt,
+    {
+        self.for_each_range(blocks, |range| self.cipher.decrypt_blocks(range));
+    }
+
+    /// XOR the CTR keystream starting at `iv` into `data`, encrypting or decrypting it in place.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(
+            level = "debug",
+            name = "rc5::parallel",
+            skip_all,
+            fields(op = "ctr", bytes = data.len(), threads = self.threads)
+        )
+    )]
+    pub fn apply_ctr(&self, iv: &Block<C>, data: &mut [u8])
+    where
+        C: BlockEncrypt,
+    {
+        let block_size = C::block_size();
+        let blocks = data.len().div_ceil(block_size);
+        let blocks_per_thread = blocks.div_ceil(self.threads).max(1);
+
+        thread::scope(|scope| {
+            for (i, range) in data.chunks_mut(blocks_per_thread * block_size).enumerate() {
+                let first_block = (i * blocks_per_thread) as u64;
+                scope.spawn(move || Keystream::ctr_at(self.cipher, iv, first_block).apply(range));
+            }
+        });
+
+        #[cfg(feature = "nonce-tracking")]
+        crate::keystream::check_ctr(self.cipher, iv, data);
+    }
+
+    fn for_each_range<T: Send>(&self, items: &mut [T], f: impl Fn(&mut [T]) + Sync) {
+        let per_thread = items.len().div_ceil(self.threads).max(1);
+
+        thread::scope(|scope| {
+            for range in items.chunks_mut(per_thread) {
+                let f = &f;
+                scope.spawn(move || f(range));
+            }
+        });
+    }
+}
@@ -0,0 +1,250 @@
+use std::{
+    fmt,
+    ops::{Add, Div, Mul, Sub},
+    str::FromStr,
+};
+
+use cipher::{
+    consts::{U1, U2},
+    generic_array::{ArrayLength, GenericArray},
+    typenum::{Diff, Quot, Sum, Unsigned},
+    KeyInit,
+};
+
+use crate::{
+    core::{BlockSize, ExpandedKeyTableSize, Word},
+    encoding::{base64_decode, hex_decode},
+    KeystreamMode, ParameterSpecError, Rc5,
+};
+
+/// Mode of operation named in a parameter string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Rc5Mode {
+    /// Each block encrypted on its own.
+    Ecb,
+    /// RC5-CBC of RFC 2040, the plaintext is a multiple of the block size.
+    Cbc,
+    /// RC5-CBC-Pad of RFC 2040, CBC with PKCS #7 style padding.
+    CbcPad,
+    /// RC5-CTS of RFC 2040, CBC with ciphertext stealing.
+    Cts,
+    /// Counter mode, see [KeystreamMode::Ctr].
+    Ctr,
+    /// Output feedback mode, see [KeystreamMode::Ofb].
+    Ofb,
+}
+
+impl Rc5Mode {
+    /// The [Keystream](crate::Keystream) mode for the stream modes.
+    pub fn keystream_mode(self) -> Option<KeystreamMode> {
+        match self {
+            Self::Ctr => Some(KeystreamMode::Ctr),
+            Self::Ofb => Some(KeystreamMode::Ofb),
+            _ => None,
+        }
+    }
+}
+
+impl FromStr for Rc5Mode {
+    type Err = ParseParametersError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        [
+            ("ECB", Self::Ecb),
+            ("CBC", Self::Cbc),
+            ("CBC-Pad", Self::CbcPad),
+            ("CTS", Self::Cts),
+            ("CTR", Self::Ctr),
+            ("OFB", Self::Ofb),
+        ]
+        .into_iter()
+        .find(|(name, _)| name.eq_ignore_ascii_case(s))
+        .map(|(_, mode)| mode)
+        .ok_or(ParseParametersError::UnknownMode)
+    }
+}
+
+/// An RC5 configuration written the way RFC 2040 and legacy documentation describe it.
+///
+/// Two forms are accepted, both starting with `RC5`:
+/// - `RC5-w/r/b[-MODE]`, for example `RC5-32/20/16-CTR`
+/// - `RC5[-MODE]/w/r[/b]`, for example `RC5-CBC-Pad/32/16` or `RC5/32/12/16`
+///
+/// where `w` is the word size in bits, `r` the number of rounds and `b` the key size in bytes.
+/// Either form may end with the key, `/hex:...` or `/b64:...` in padded standard base64, which
+/// then also gives the key size. The mode names are case insensitive.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Rc5Parameters {
+    /// Word size in bits.
+    pub word_size: u32,
+    pub rounds: u32,
+    /// Key size in bytes, `None` when the second form leaves it out and there is no key.
+    pub key_size: Option<u32>,
+    pub mode: Option<Rc5Mode>,
+    pub key: Option<Vec<u8>>,
+}
+
+impl fmt::Debug for Rc5Parameters {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Rc5Parameters")
+            .field("word_size", &self.word_size)
+            .field("rounds", &self.rounds)
+            .field("key_size", &self.key_size)
+            .field("mode", &self.mode)
+            .field("key", &self.key.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+impl FromStr for Rc5Parameters {
+    type Err = ParseParametersError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // the key goes first, base64 keys can contain `/`
+        let (s, key) = match s.split_once(':') {
+            Some((head, key)) => {
+                let (head, encoding) = head.rsplit_once('/').ok_or(ParseParametersError::Syntax)?;
+                let key = match encoding {
+                    "hex" => hex_decode(key),
+                    "b64" => base64_decode(key.as_bytes()),
+                    _ => None,
+                };
+                (head, Some(key.ok_or(ParseParametersError::InvalidKey)?))
+            }
+            None => (s, None),
+        };
+
+        let rest = s
+            .get(..3)
+            .filter(|prefix| prefix.eq_ignore_ascii_case("RC5"))
+            .map(|_| &s[3..])
+            .ok_or(ParseParametersError::Syntax)?;
+        // `RC5-w/r/b[-MODE]` or `RC5[-MODE]/w/r[/b]`
+        let (mode, numbers, key_size_optional) = match rest.strip_prefix('-') {
+            Some(rest) if rest.starts_with(|c: char| c.is_ascii_digit()) => {
+                match rest.split_once('-') {
+                    Some((numbers, mode)) => (Some(mode), numbers, false),
+                    None => (None, rest, false),
+                }
+            }
+            Some(rest) => {
+                let (mode, numbers) = rest.split_once('/').ok_or(ParseParametersError::Syntax)?;
+                (Some(mode), numbers, true)
+            }
+            None => {
+                let numbers = rest.strip_prefix('/').ok_or(ParseParametersError::Syntax)?;
+                (None, numbers, true)
+            }
+        };
+        let mode = mode.map(str::parse).transpose()?;
+
+        let numbers = numbers
+            .split('/')
+            .map(|field| {
+                // `parse` alone takes a leading `+`
+                if field.is_empty() || !field.bytes().all(|char| char.is_ascii_digit()) {
+                    return Err(ParseParametersError::Syntax);
+                }
+                field.parse().map_err(|_| ParseParametersError::Syntax)
+            })
+            .collect::<Result<Vec<u32>, _>>()?;
+        let (word_size, rounds, key_size) = match (&numbers[..], &key) {
+            (&[w, r, b], _) => (w, r, Some(b)),
+            (&[w, r], Some(key)) => (w, r, Some(key.len() as u32)),
+            (&[w, r], None) if key_size_optional => (w, r, None),
+            _ => return Err(ParseParametersError::Syntax),
+        };
+
+        if ![8, 16, 32, 64].contains(&word_size)
+            || rounds > 255
+            || key_size.is_some_and(|key_size| key_size > 255)
+        {
+            return Err(ParseParametersError::OutOfRange);
+        }
+        if let (Some(key), Some(key_size)) = (&key, key_size) {
+            if key.len() != key_size as usize {
+                return Err(ParseParametersError::KeyLengthMismatch);
+            }
+        }
+
+        Ok(Self {
+            word_size,
+            rounds,
+            key_size,
+            mode,
+            key,
+        })
+    }
+}
+
+impl<W, R, B> Rc5<W, R, B>
+where
+    W: Word,
+    W::Bytes: Mul<U2>,
+    BlockSize<W>: ArrayLength<u8>,
+    R: Unsigned,
+    R: Add<U1>,
+    Sum<R, U1>: Mul<U2>,
+    ExpandedKeyTableSize<R>: ArrayLength<W>,
+    B: ArrayLength<u8>,
+    B: Add<W::Bytes>,
+    Sum<B, W::Bytes>: Sub<U1>,
+    Diff<Sum<B, W::Bytes>, U1>: Div<W::Bytes>,
+    Quot<Diff<Sum<B, W::Bytes>, U1>, W::Bytes>: ArrayLength<W>,
+{
+    /// Initialize the cipher from `key` after checking `parameters` describe this type.
+    ///
+    /// The key can be the one embedded in the parameter string, `parameters.key`, or one stored
+    /// elsewhere. The mode is left to the caller.
+    pub fn from_parameters(
+        key: &[u8],
+        parameters: &Rc5Parameters,
+    ) -> Result<Self, ParameterSpecError> {
+        if parameters.word_size as usize != W::Bytes::USIZE * 8 {
+            return Err(ParameterSpecError::WordSizeMismatch);
+        }
+        if parameters.rounds as usize != R::USIZE {
+            return Err(ParameterSpecError::RoundsMismatch);
+        }
+        if parameters
+            .key_size
+            .is_some_and(|key_size| key_size as usize != B::USIZE)
+            || key.len() != B::USIZE
+        {
+            return Err(ParameterSpecError::InvalidKeyLength);
+        }
+
+        Ok(Self::new(GenericArray::from_slice(key)))
+    }
+}
+
+/// Error parsing an [Rc5Parameters] string.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ParseParametersError {
+    /// The string doesn't have one of the accepted forms.
+    Syntax,
+    /// The mode of operation isn't known.
+    UnknownMode,
+    /// The key isn't valid hex or base64.
+    InvalidKey,
+    /// The embedded key doesn't have the given key size.
+    KeyLengthMismatch,
+    /// The word size isn't 8, 16, 32 or 64 bits, or the rounds or key size exceed 255.
+    OutOfRange,
+}
+
+impl fmt::Display for ParseParametersError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax => f.write_str("invalid RC5 parameter string"),
+            Self::UnknownMode => f.write_str("unknown mode of operation"),
+            Self::InvalidKey => f.write_str("invalid key encoding"),
+            Self::KeyLengthMismatch => f.write_str("key length doesn't match the key size"),
+            Self::OutOfRange => f.write_str("RC5 parameters out of range"),
+        }
+    }
+}
+
+impl std::error::Error for ParseParametersError {}
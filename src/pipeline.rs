@@ -0,0 +1,155 @@
+use std::{
+    collections::BTreeMap,
+    io::{self, Read, Write},
+    num::NonZeroUsize,
+    sync::{mpsc, Arc, Mutex},
+    thread,
+};
+
+use cipher::{Block, BlockEncrypt};
+
+use crate::Keystream;
+
+/// Default number of bytes handed to a worker at a time.
+const CHUNK_SIZE: usize = 1 << 20;
+
+/// Multi-threaded CTR encryption of a byte stream.
+///
+/// A reader thread splits the input into chunks, worker threads XOR each chunk with the keystream
+/// of its own counter range and the calling thread writes the chunks back in order. At most two
+/// chunks per worker are in flight, so memory use is bounded regardless of the input size. CTR is
+/// its own inverse, running the pipeline again with the same key and IV decrypts.
+pub struct CtrPipeline<'a, C: BlockEncrypt> {
+    cipher: &'a C,
+    iv: Block<C>,
+    threads: usize,
+    chunk_size: usize,
+}
+
+impl<'a, C> CtrPipeline<'a, C>
+where
+    C: BlockEncrypt + Sync,
+{
+    pub fn new(cipher: &'a C, iv: &Block<C>) -> Self {
+        Self {
+            cipher,
+            iv: iv.clone(),
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            chunk_size: CHUNK_SIZE,
+        }
+    }
+
+    /// Pipeline starting at a fresh IV drawn from `rng`, returned for the receiver.
+    #[cfg(feature = "rand_core")]
+    pub fn new_with_random_iv(
+        cipher: &'a C,
+        rng: &mut (impl cipher::rand_core::CryptoRng + cipher::rand_core::RngCore),
+    ) -> (Self, Block<C>) {
+        let iv = crate::keystream::random_iv::<C>(rng);
+        (Self::new(cipher, &iv), iv)
+    }
+
+    /// Number of worker threads, defaults to the available parallelism.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Bytes per chunk, rounded up to whole blocks. Defaults to 1 MiB.
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1).div_ceil(C::block_size()) * C::block_size();
+        self
+    }
+
+    /// Encrypt everything read from `reader` into `writer`, returning the number of bytes.
+    ///
+    /// On error the output written so far is a prefix of the encrypted input.
+    pub fn run<R, W>(&self, mut reader: R, mut writer: W) -> io::Result<u64>
+    where
+        R: Read + Send,
+        W: Write,
+    {
+        let (chunk_tx, chunk_rx) = mpsc::sync_channel::<(u64, Vec<u8>)>(self.threads);
+        let (done_tx, done_rx) = mpsc::sync_channel::<(u64, Vec<u8>)>(self.threads);
+        let chunk_rx = Arc::new(Mutex::new(chunk_rx));
+
+        thread::scope(|scope| {
+            let reader = scope.spawn(move || -> io::Result<()> {
+                for index in 0.. {
+                    let mut chunk = Vec::with_capacity(self.chunk_size);
+                    reader
+                        .by_ref()
+                        .take(self.chunk_size as u64)
+                        .read_to_end(&mut chunk)?;
+
+                    if chunk.is_empty() || chunk_tx.send((index, chunk)).is_err() {
+                        break;
+                    }
+                }
+                Ok(())
+            });
+
+            for _ in 0..self.threads {
+                let (chunk_rx, done_tx) = (Arc::clone(&chunk_rx), done_tx.clone());
+                scope.spawn(move || self.work(&chunk_rx, &done_tx));
+            }
+            drop((chunk_rx, done_tx));
+
+            let written = self.write_in_order(&done_rx, &mut writer);
+            // unblock the workers and the reader if the writer failed
+            drop(done_rx);
+
+            reader.join().expect("pipeline reader panicked")?;
+            written
+        })
+    }
+
+    fn work(
+        &self,
+        chunks: &Mutex<mpsc::Receiver<(u64, Vec<u8>)>>,
+        done: &mpsc::SyncSender<(u64, Vec<u8>)>,
+    ) {
+        let blocks_per_chunk = (self.chunk_size / C::block_size()) as u64;
+
+        loop {
+            let next = chunks.lock().expect("pipeline worker panicked").recv();
+            let Ok((index, mut chunk)) = next else {
+                return;
+            };
+
+            Keystream::ctr_at(self.cipher, &self.iv, index * blocks_per_chunk).apply(&mut chunk);
+
+            if done.send((index, chunk)).is_err() {
+                return;
+            }
+        }
+    }
+
+    fn write_in_order(
+        &self,
+        done: &mpsc::Receiver<(u64, Vec<u8>)>,
+        writer: &mut impl Write,
+    ) -> io::Result<u64> {
+        let (mut next, mut written) = (0, 0);
+        let mut pending = BTreeMap::new();
+
+        for (index, chunk) in done {
+            pending.insert(index, chunk);
+
+            while let Some(chunk) = pending.remove(&next) {
+                // the workers' threads don't share the nonce uses of the caller
+                #[cfg(feature = "nonce-tracking")]
+                if next == 0 {
+                    crate::keystream::check_ctr(self.cipher, &self.iv, &chunk);
+                }
+
+                writer.write_all(&chunk)?;
+                written += chunk.len() as u64;
+                next += 1;
+            }
+        }
+
+        writer.flush()?;
+        Ok(written)
+    }
+}
@@ -0,0 +1,149 @@
+use cipher::{rand_core::RngCore, typenum::Unsigned};
+use generic_array::GenericArray;
+
+use super::{encrypt_rounds, key_schedule, random_bytes, random_word, xor};
+use crate::Word;
+
+/// Avalanche matrix: how often flipping an input bit flips each output bit.
+///
+/// Output bit `i` is bit `i % w` of `A` for `i < w` and of `B` otherwise, input bits of the
+/// plaintext are numbered the same way and key bits follow the little endian byte order. A well
+/// diffusing cipher flips every output bit with probability close to one half.
+#[derive(Clone, Debug)]
+pub struct Avalanche {
+    input_bits: usize,
+    output_bits: usize,
+    samples: u64,
+    flips: Vec<u64>,
+}
+
+impl Avalanche {
+    /// Flip every plaintext bit of `samples` random plaintexts, encrypted with `rounds` rounds
+    /// under random keys of `key_len` bytes.
+    pub fn plaintext<W: Word>(
+        key_len: usize,
+        rounds: usize,
+        samples: usize,
+        rng: &mut impl RngCore,
+    ) -> Self {
+        let output_bits = 2 * W::Bytes::USIZE * 8;
+        let mut avalanche = Self::new(output_bits, output_bits);
+
+        for _ in 0..samples {
+            let subkeys = key_schedule::<W>(&random_bytes(key_len, rng), rounds);
+            let plaintext = (random_word(rng), random_word(rng));
+            let ciphertext = encrypt_rounds(plaintext, &subkeys);
+
+            for bit in 0..output_bits {
+                let flipped = encrypt_rounds(flip_bit(plaintext, bit), &subkeys);
+                avalanche.record(bit, xor(ciphertext, flipped));
+            }
+            avalanche.samples += 1;
+        }
+
+        avalanche
+    }
+
+    /// Flip every key bit of `samples` random keys of `key_len` bytes, encrypting a random
+    /// plaintext with `rounds` rounds.
+    pub fn key<W: Word>(
+        key_len: usize,
+        rounds: usize,
+        samples: usize,
+        rng: &mut impl RngCore,
+    ) -> Self {
+        let mut avalanche = Self::new(key_len * 8, 2 * W::Bytes::USIZE * 8);
+
+        for _ in 0..samples {
+            let mut key = random_bytes(key_len, rng);
+            let plaintext = (random_word(rng), random_word(rng));
+            let ciphertext = encrypt_rounds(plaintext, &key_schedule::<W>(&key, rounds));
+
+            for bit in 0..key_len * 8 {
+                key[bit / 8] ^= 1 << (bit % 8);
+                let flipped = encrypt_rounds(plaintext, &key_schedule::<W>(&key, rounds));
+                key[bit / 8] ^= 1 << (bit % 8);
+
+                avalanche.record(bit, xor(ciphertext, flipped));
+            }
+            avalanche.samples += 1;
+        }
+
+        avalanche
+    }
+
+    fn new(input_bits: usize, output_bits: usize) -> Self {
+        Self {
+            input_bits,
+            output_bits,
+            samples: 0,
+            flips: vec![0; input_bits * output_bits],
+        }
+    }
+
+    fn record<W: Word>(&mut self, input_bit: usize, difference: (W, W)) {
+        let row = &mut self.flips[input_bit * self.output_bits..][..self.output_bits];
+        let bytes = difference.0.to_le_bytes().into_iter();
+
+        for (byte_index, byte) in bytes.chain(difference.1.to_le_bytes()).enumerate() {
+            for bit in 0..8 {
+                row[byte_index * 8 + bit] += u64::from(byte >> bit & 1);
+            }
+        }
+    }
+
+    pub fn input_bits(&self) -> usize {
+        self.input_bits
+    }
+
+    pub fn output_bits(&self) -> usize {
+        self.output_bits
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Probability that flipping `input_bit` flips `output_bit`.
+    pub fn probability(&self, input_bit: usize, output_bit: usize) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+
+        self.flips[input_bit * self.output_bits + output_bit] as f64 / self.samples as f64
+    }
+
+    /// The full matrix of flip probabilities, indexed by input and then output bit.
+    pub fn matrix(&self) -> Vec<Vec<f64>> {
+        (0..self.input_bits)
+            .map(|input| {
+                (0..self.output_bits)
+                    .map(|output| self.probability(input, output))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Average flip probability over the whole matrix.
+    pub fn mean(&self) -> f64 {
+        if self.samples == 0 || self.flips.is_empty() {
+            return 0.0;
+        }
+
+        let flips: u64 = self.flips.iter().sum();
+        flips as f64 / (self.samples as f64 * self.flips.len() as f64)
+    }
+}
+
+fn flip_bit<W: Word>((a, b): (W, W), bit: usize) -> (W, W) {
+    let word_bits = W::Bytes::USIZE * 8;
+    let mut bytes = GenericArray::<u8, W::Bytes>::default();
+    bytes[bit % word_bits / 8] = 1 << (bit % 8);
+    let mask = W::from_le_bytes(&bytes);
+
+    if bit < word_bits {
+        (a.bitxor(mask), b)
+    } else {
+        (a, b.bitxor(mask))
+    }
+}
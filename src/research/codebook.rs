@@ -0,0 +1,90 @@
+use std::io::{self, Write};
+
+use super::{encrypt_rounds, key_schedule};
+
+/// Full codebook of RC5 with 8 bit words, where a block is small enough to enumerate.
+///
+/// Blocks are numbered by their little endian value, so `A` is the low and `B` the high byte.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Codebook {
+    table: Vec<u16>,
+}
+
+impl Codebook {
+    /// Codebook of RC5-8 with `rounds` rounds under `key`.
+    pub fn new(key: &[u8], rounds: usize) -> Self {
+        Self::from_subkeys(&key_schedule(key, rounds))
+    }
+
+    /// Codebook of RC5-8 with the given subkey table.
+    pub fn from_subkeys(subkeys: &[u8]) -> Self {
+        let table = (0..=u16::MAX)
+            .map(|block| {
+                let [a, b] = block.to_le_bytes();
+                let (a, b) = encrypt_rounds((a, b), subkeys);
+
+                u16::from_le_bytes([a, b])
+            })
+            .collect();
+
+        Self { table }
+    }
+
+    pub fn encrypt(&self, block: u16) -> u16 {
+        self.table[usize::from(block)]
+    }
+
+    /// Ciphertexts indexed by plaintext.
+    pub fn table(&self) -> &[u16] {
+        &self.table
+    }
+
+    /// The inverse permutation, ciphertexts mapped to plaintexts.
+    pub fn inverse(&self) -> Vec<u16> {
+        let mut inverse = vec![0; self.table.len()];
+        for (plaintext, ciphertext) in (0..=u16::MAX).zip(&self.table) {
+            inverse[usize::from(*ciphertext)] = plaintext;
+        }
+
+        inverse
+    }
+
+    /// Cycle lengths of the permutation, longest first.
+    pub fn cycles(&self) -> Vec<usize> {
+        let mut visited = vec![false; self.table.len()];
+        let mut cycles = Vec::new();
+
+        for start in 0..self.table.len() {
+            let (mut block, mut len) = (start, 0);
+            while !visited[block] {
+                visited[block] = true;
+                block = usize::from(self.table[block]);
+                len += 1;
+            }
+
+            if len > 0 {
+                cycles.push(len);
+            }
+        }
+        cycles.sort_unstable_by(|a, b| b.cmp(a));
+
+        cycles
+    }
+
+    /// Plaintexts which encrypt to themselves.
+    pub fn fixed_points(&self) -> Vec<u16> {
+        (0..=u16::MAX)
+            .filter(|block| self.encrypt(*block) == *block)
+            .collect()
+    }
+
+    /// Write one `plaintext ciphertext` hex pair per line.
+    pub fn dump(&self, out: &mut impl Write) -> io::Result<()> {
+        let mut out = io::BufWriter::new(out);
+        for (plaintext, ciphertext) in (0..=u16::MAX).zip(&self.table) {
+            writeln!(out, "{plaintext:04x} {ciphertext:04x}")?;
+        }
+
+        out.flush()
+    }
+}
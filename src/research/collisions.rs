@@ -0,0 +1,61 @@
+use std::{collections::HashMap, hash::Hash};
+
+use super::key_schedule;
+use crate::Word;
+
+/// Distinct keys whose expanded key tables agree on a common prefix.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KeyScheduleCollision<W> {
+    /// The shared table prefix.
+    pub prefix: Vec<W>,
+    /// All keys of the enumerated space producing `prefix`, in increasing order.
+    pub keys: Vec<Vec<u8>>,
+}
+
+/// Enumerate every key of `key_len` bytes and group the keys whose `rounds` round expanded key
+/// tables share their first `prefix_len` words. A `prefix_len` of at least `2 * rounds + 2`
+/// compares the full table.
+///
+/// Only groups of two or more keys are returned. Meant for the toy word sizes, the key space
+/// has `256^key_len` keys.
+///
+/// # Panics
+/// If `key_len` is not between 1 and 3.
+pub fn key_schedule_collisions<W: Word + Hash + Eq>(
+    key_len: usize,
+    rounds: usize,
+    prefix_len: usize,
+) -> Vec<KeyScheduleCollision<W>> {
+    assert!(
+        (1..=3).contains(&key_len),
+        "key space must have between 1 and 3 bytes"
+    );
+    let prefix_len = prefix_len.min(2 * rounds + 2);
+
+    let mut groups: HashMap<Vec<W>, Vec<u32>> = HashMap::new();
+    for index in 0..1u32 << (8 * key_len) {
+        let mut table = key_schedule::<W>(&key_from_index(index, key_len), rounds);
+        table.truncate(prefix_len);
+
+        groups.entry(table).or_default().push(index);
+    }
+
+    let mut collisions: Vec<_> = groups
+        .into_iter()
+        .filter(|(_, keys)| keys.len() > 1)
+        .map(|(prefix, keys)| KeyScheduleCollision {
+            prefix,
+            keys: keys
+                .into_iter()
+                .map(|index| key_from_index(index, key_len))
+                .collect(),
+        })
+        .collect();
+    collisions.sort_unstable_by(|a, b| a.keys.cmp(&b.keys));
+
+    collisions
+}
+
+fn key_from_index(index: u32, key_len: usize) -> Vec<u8> {
+    index.to_be_bytes()[4 - key_len..].to_vec()
+}
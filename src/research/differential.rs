@@ -0,0 +1,86 @@
+use std::{collections::HashMap, hash::Hash};
+
+use cipher::rand_core::RngCore;
+
+use super::{encrypt_rounds, random_word, xor};
+use crate::Word;
+
+/// Generate `count` chosen plaintext pairs `(p, p ^ difference)` with random `p`.
+pub fn chosen_pairs<W: Word>(
+    difference: (W, W),
+    count: usize,
+    rng: &mut impl RngCore,
+) -> Vec<((W, W), (W, W))> {
+    (0..count)
+        .map(|_| {
+            let p = (random_word(rng), random_word(rng));
+            (p, xor(p, difference))
+        })
+        .collect()
+}
+
+/// Output differences observed for a fixed input difference.
+#[derive(Clone, Debug)]
+pub struct DifferenceTable<W> {
+    input_difference: (W, W),
+    samples: u64,
+    counts: HashMap<(W, W), u64>,
+}
+
+impl<W: Word + Hash + Eq> DifferenceTable<W> {
+    /// Encrypt `samples` chosen plaintext pairs with `input_difference` under `subkeys` and
+    /// count the resulting output differences.
+    pub fn collect(
+        input_difference: (W, W),
+        subkeys: &[W],
+        samples: usize,
+        rng: &mut impl RngCore,
+    ) -> Self {
+        let mut counts = HashMap::new();
+
+        for (p, p_star) in chosen_pairs(input_difference, samples, rng) {
+            let difference = xor(encrypt_rounds(p, subkeys), encrypt_rounds(p_star, subkeys));
+            *counts.entry(difference).or_insert(0) += 1;
+        }
+
+        Self {
+            input_difference,
+            samples: samples as u64,
+            counts,
+        }
+    }
+
+    pub fn input_difference(&self) -> (W, W) {
+        self.input_difference
+    }
+
+    pub fn samples(&self) -> u64 {
+        self.samples
+    }
+
+    /// Number of pairs which ended with `output_difference`.
+    pub fn count(&self, output_difference: (W, W)) -> u64 {
+        self.counts.get(&output_difference).copied().unwrap_or(0)
+    }
+
+    /// Estimated probability of the characteristic `input_difference -> output_difference`.
+    pub fn probability(&self, output_difference: (W, W)) -> f64 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+
+        self.count(output_difference) as f64 / self.samples as f64
+    }
+
+    /// The `n` most frequent output differences with their estimated probabilities.
+    pub fn most_likely(&self, n: usize) -> Vec<((W, W), f64)> {
+        let mut counts: Vec<_> = self.counts.iter().map(|(d, c)| (*d, *c)).collect();
+        counts.sort_unstable_by_key(|(_, count)| std::cmp::Reverse(*count));
+
+        counts
+            .into_iter()
+            .take(n)
+            .map(|(difference, count)| (difference, count as f64 / self.samples as f64))
+            .collect()
+    }
+}
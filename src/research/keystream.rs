@@ -0,0 +1,59 @@
+use std::io::{self, Write};
+
+use cipher::BlockEncrypt;
+
+pub use crate::{Keystream, KeystreamMode};
+
+/// Input formats understood by the NIST statistical test suite.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StsFormat {
+    /// ASCII `'0'` and `'1'` characters.
+    Ascii,
+    /// Raw bytes, most significant bit first, so only whole bytes.
+    Binary,
+}
+
+/// Write `bits` keystream bits to `out` in a format the NIST statistical test suite can read.
+///
+/// Fails with [io::ErrorKind::InvalidInput] before writing anything if the format is
+/// [StsFormat::Binary] and `bits` isn't a multiple of 8, a partial last byte would add keystream
+/// bits the test suite reads as part of the sequence.
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", name = "rc5::sts_keystream", skip(keystream, out))
+)]
+pub fn write_sts_keystream<C: BlockEncrypt>(
+    keystream: &mut Keystream<'_, C>,
+    bits: u64,
+    format: StsFormat,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    if format == StsFormat::Binary && !bits.is_multiple_of(8) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "binary STS output needs a multiple of 8 bits",
+        ));
+    }
+
+    let mut out = io::BufWriter::new(out);
+    let mut remaining = bits;
+
+    while remaining > 0 {
+        let block = keystream.next_block();
+        let block_bits = remaining.min(block.len() as u64 * 8);
+
+        match format {
+            StsFormat::Ascii => {
+                let ascii: Vec<u8> = (0..block_bits as usize)
+                    .map(|bit| b'0' + (block[bit / 8] >> (7 - bit % 8) & 1))
+                    .collect();
+                out.write_all(&ascii)?;
+            }
+            StsFormat::Binary => out.write_all(&block[..block_bits as usize / 8])?,
+        }
+
+        remaining -= block_bits;
+    }
+
+    out.flush()
+}
@@ -0,0 +1,85 @@
+//! Cryptanalysis and statistics playground.
+//!
+//! Reduced-round RC5 is analysed with independent subkeys, as is customary in the differential
+//! cryptanalysis literature on RC5. A subkey table of `2 * r + 2` words describes an `r` round
+//! cipher, see [random_subkeys]. Blocks are handled as the word pair `(A, B)` and differences
+//! are XOR differences.
+//!
+//! Diffusion of plaintext and key bits can be measured with [Avalanche], which uses the real
+//! key schedule for the requested number of rounds, see [key_schedule]. CTR and OFB keystreams
+//! for the NIST statistical test suite are written by [write_sts_keystream].
+//!
+//! The toy 8 bit word size makes exhaustive experiments possible, for example searching small key
+//! spaces for colliding key schedules with [key_schedule_collisions] or dumping the whole
+//! permutation of a key as a [Codebook].
+//!
+//! **These utilities are meant for experimentation only.**
+
+mod avalanche;
+mod codebook;
+mod collisions;
+mod differential;
+mod keystream;
+
+pub use avalanche::*;
+pub use codebook::*;
+pub use collisions::*;
+pub use differential::*;
+pub use keystream::*;
+
+use cipher::{rand_core::RngCore, typenum::Unsigned};
+use generic_array::GenericArray;
+
+use crate::{
+    core::{encrypt_words, initialize_key_table, key_bytes_into_words, mix_key_table},
+    Word,
+};
+
+/// Subkey table for `rounds` rounds filled with random words.
+pub fn random_subkeys<W: Word>(rounds: usize, rng: &mut impl RngCore) -> Vec<W> {
+    (0..2 * rounds + 2).map(|_| random_word(rng)).collect()
+}
+
+/// Subkey table for `rounds` rounds derived from `key` with the RC5 key schedule. An empty key is
+/// one zero word, like for the cipher.
+pub fn key_schedule<W: Word>(key: &[u8], rounds: usize) -> Vec<W> {
+    let mut key_as_words = vec![W::ZERO; key.len().div_ceil(W::Bytes::USIZE)];
+    key_bytes_into_words(key, &mut key_as_words);
+
+    let mut subkeys = vec![W::ZERO; 2 * rounds + 2];
+    initialize_key_table(&mut subkeys);
+    mix_key_table(&mut subkeys, &mut key_as_words);
+
+    subkeys
+}
+
+/// Encrypt the word pair `(a, b)` with as many rounds as `subkeys` describes.
+///
+/// # Panics
+/// If `subkeys` has less than two or an odd number of words.
+pub fn encrypt_rounds<W: Word>(words: (W, W), subkeys: &[W]) -> (W, W) {
+    assert!(
+        subkeys.len() >= 2 && subkeys.len().is_multiple_of(2),
+        "subkey table must hold 2 * rounds + 2 words"
+    );
+
+    encrypt_words(words, subkeys)
+}
+
+pub(crate) fn random_bytes(len: usize, rng: &mut impl RngCore) -> Vec<u8> {
+    let mut bytes = vec![0; len];
+    rng.fill_bytes(&mut bytes);
+
+    bytes
+}
+
+pub(crate) fn xor<W: Word>(x: (W, W), y: (W, W)) -> (W, W) {
+    (x.0.bitxor(y.0), x.1.bitxor(y.1))
+}
+
+pub(crate) fn random_word<W: Word>(rng: &mut impl RngCore) -> W {
+    let mut bytes = GenericArray::<u8, W::Bytes>::default();
+    rng.fill_bytes(&mut bytes);
+
+    W::from_le_bytes(&bytes)
+}
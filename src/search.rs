@@ -0,0 +1,348 @@
+//! Brute-force key search over a restricted key space.
+//!
+//! Given a handful of known plaintext/ciphertext pairs, [KeySearch] enumerates every key of a
+//! [KeySpace] across several threads and returns the first key that maps all plaintexts to
+//! their ciphertexts. This is only practical for keys with few unknown bytes or a small
+//! charset, which is the usual situation in CTF and forensic work.
+//!
+//! [Challenge] scripts work on the RSA secret-key challenges like the distributed.net RC5-72
+//! project.
+
+use std::{
+    num::NonZeroUsize,
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+    thread,
+};
+
+use cipher::{typenum::Unsigned, Block, BlockEncrypt, Key, KeyInit};
+
+use crate::RC5_32_12_9;
+
+/// Number of candidates a worker tries between two progress reports.
+const PROGRESS_INTERVAL: u64 = 1 << 12;
+
+/// Candidate keys with some bytes known and the others drawn from a charset.
+///
+/// The total key length is given by the key size of the searched cipher, bytes past the end of
+/// the template are unknown.
+#[derive(Clone, Debug)]
+pub struct KeySpace {
+    template: Vec<Option<u8>>,
+    charset: Vec<u8>,
+}
+
+impl KeySpace {
+    /// Key space where every byte is drawn from `charset`.
+    pub fn new(charset: &[u8]) -> Self {
+        Self {
+            template: Vec::new(),
+            charset: charset.to_vec(),
+        }
+    }
+
+    /// Key space covering all 256 byte values.
+    pub fn full() -> Self {
+        Self::new(&(0..=u8::MAX).collect::<Vec<_>>())
+    }
+
+    /// Fix the leading bytes of every candidate key.
+    pub fn with_prefix(self, prefix: &[u8]) -> Self {
+        let template: Vec<_> = prefix.iter().copied().map(Some).collect();
+        self.with_template(&template)
+    }
+
+    /// Fix the bytes known at any position, `None` marks an unknown byte. Only the unknown bytes
+    /// are enumerated, as when part of a key leaked.
+    pub fn with_template(mut self, template: &[Option<u8>]) -> Self {
+        self.template = template.to_vec();
+        self
+    }
+
+    /// Number of candidates for keys of `key_len` bytes, `None` if the template is longer than
+    /// the key or the number doesn't fit an `u64`.
+    pub fn len(&self, key_len: usize) -> Option<u64> {
+        let beyond_template = key_len.checked_sub(self.template.len())?;
+        let free = self.template.iter().filter(|byte| byte.is_none()).count() + beyond_template;
+
+        (0..free).try_fold(1u64, |len, _| len.checked_mul(self.charset.len() as u64))
+    }
+
+    /// Write the candidate with the given index into `key`.
+    fn candidate(&self, mut index: u64, key: &mut [u8]) {
+        let radix = self.charset.len() as u64;
+
+        for (position, byte) in key.iter_mut().enumerate().rev() {
+            *byte = match self.template.get(position).copied().flatten() {
+                Some(known) => known,
+                None => {
+                    let unknown = self.charset[(index % radix) as usize];
+                    index /= radix;
+                    unknown
+                }
+            };
+        }
+    }
+}
+
+/// Parallel search of a [KeySpace] for a key consistent with known plaintext/ciphertext pairs.
+pub struct KeySearch<'a, C: BlockEncrypt> {
+    space: KeySpace,
+    pairs: &'a [(Block<C>, Block<C>)],
+    threads: usize,
+    progress: Option<&'a (dyn Fn(u64, u64) + Sync)>,
+}
+
+impl<'a, C> KeySearch<'a, C>
+where
+    C: BlockEncrypt + KeyInit,
+{
+    /// Search `space` for a key encrypting every `(plaintext, ciphertext)` pair.
+    pub fn new(space: KeySpace, pairs: &'a [(Block<C>, Block<C>)]) -> Self {
+        Self {
+            space,
+            pairs,
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+            progress: None,
+        }
+    }
+
+    /// Number of worker threads, defaults to the available parallelism.
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Periodically called with the number of tried keys and the size of the key space.
+    pub fn on_progress(mut self, progress: &'a (dyn Fn(u64, u64) + Sync)) -> Self {
+        self.progress = Some(progress);
+        self
+    }
+
+    /// Run the search, returning the first matching key.
+    ///
+    /// # Panics
+    /// If the template is longer than the key or the key space doesn't fit an `u64`.
+    #[cfg_attr(
+        feature = "tracing",
+        tracing::instrument(level = "info", name = "rc5::key_search", skip_all, fields(threads = self.threads))
+    )]
+    pub fn run(&self) -> Option<Key<C>> {
+        let total = self
+            .space
+            .len(C::KeySize::USIZE)
+            .expect("template longer than the key or key space too large");
+
+        let found = AtomicBool::new(false);
+        let tried = AtomicU64::new(0);
+        let threads = self.threads as u64;
+
+        let key = thread::scope(|scope| {
+            let workers: Vec<_> = (0..threads)
+                .map(|worker| {
+                    let (found, tried) = (&found, &tried);
+                    let start = total / threads * worker + worker.min(total % threads);
+                    let end = start + total / threads + u64::from(worker < total % threads);
+
+                    scope.spawn(move || self.search_range(start..end, total, found, tried))
+                })
+                .collect();
+
+            workers
+                .into_iter()
+                .find_map(|worker| worker.join().expect("search worker panicked"))
+        });
+
+        #[cfg(feature = "tracing")]
+        tracing::info!(
+            tried = tried.load(Ordering::Relaxed),
+            total,
+            found = key.is_some(),
+            "key search finished"
+        );
+
+        key
+    }
+
+    fn search_range(
+        &self,
+        range: std::ops::Range<u64>,
+        total: u64,
+        found: &AtomicBool,
+        tried: &AtomicU64,
+    ) -> Option<Key<C>> {
+        let mut key = Key::<C>::default();
+        let mut block = Block::<C>::default();
+        let mut unreported = 0;
+
+        for index in range {
+            if unreported == PROGRESS_INTERVAL {
+                if found.load(Ordering::Relaxed) {
+                    return None;
+                }
+                self.report(
+                    tried.fetch_add(unreported, Ordering::Relaxed) + unreported,
+                    total,
+                );
+                unreported = 0;
+            }
+            unreported += 1;
+
+            self.space.candidate(index, &mut key);
+            let cipher = C::new(&key);
+
+            let matches = self.pairs.iter().all(|(plaintext, ciphertext)| {
+                cipher.encrypt_block_b2b(plaintext, &mut block);
+                block == *ciphertext
+            });
+
+            if matches {
+                found.store(true, Ordering::Relaxed);
+                self.report(
+                    tried.fetch_add(unreported, Ordering::Relaxed) + unreported,
+                    total,
+                );
+                return Some(key);
+            }
+        }
+
+        self.report(
+            tried.fetch_add(unreported, Ordering::Relaxed) + unreported,
+            total,
+        );
+        None
+    }
+
+    fn report(&self, tried: u64, total: u64) {
+        if let Some(progress) = self.progress {
+            progress(tried, total);
+        }
+    }
+}
+
+/// A stats block of the distributed.net RC5-72 project, `2^32` consecutive keys.
+///
+/// The clients hold a key as the words `hi:mid:lo` that the key schedule loads as `L[2]`, `L[1]`
+/// and `L[0]`, so `lo` is bytes 0 to 3 of the key, `mid` bytes 4 to 7 and `hi` byte 8. They
+/// increment `hi`, carrying into `mid` and then `lo` in byte swapped order, which counts through
+/// the nine key bytes as a big endian number with the last byte changing fastest. A block fixes
+/// the first five bytes, its number, and covers every value of the last four.
+///
+/// Unverified: the order follows the client increment but hasn't been checked against a published
+/// block ID and key, so the type is hidden from the documentation and not covered by semver until
+/// it is. Use [Challenge::search_in] with an explicit [KeySpace] where the keys matter.
+#[doc(hidden)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct StatsBlock(u64);
+
+impl StatsBlock {
+    /// Keys in a block.
+    pub const KEYS: u64 = 1 << 32;
+
+    /// The block with the given number, `None` past the `2^40` blocks of the key space.
+    pub fn new(number: u64) -> Option<Self> {
+        (number < 1 << 40).then_some(Self(number))
+    }
+
+    /// The block holding `key`.
+    pub fn containing(key: &[u8; 9]) -> Self {
+        Self(
+            key[..5]
+                .iter()
+                .fold(0, |number, &byte| number << 8 | u64::from(byte)),
+        )
+    }
+
+    pub fn number(self) -> u64 {
+        self.0
+    }
+
+    /// The key as the `(hi, mid, lo)` words of the clients.
+    pub fn key_words(key: &[u8; 9]) -> (u8, u32, u32) {
+        let (lo, rest) = key.split_first_chunk::<4>().expect("9 bytes");
+        let (mid, hi) = rest.split_first_chunk::<4>().expect("5 bytes");
+        (hi[0], u32::from_le_bytes(*mid), u32::from_le_bytes(*lo))
+    }
+
+    /// The key with the `(hi, mid, lo)` words of the clients.
+    pub fn key_from_words(hi: u8, mid: u32, lo: u32) -> [u8; 9] {
+        let mut key = [0; 9];
+        key[..4].copy_from_slice(&lo.to_le_bytes());
+        key[4..8].copy_from_slice(&mid.to_le_bytes());
+        key[8] = hi;
+        key
+    }
+
+    /// The key at `index` in the order the clients try them.
+    pub fn key(self, index: u32) -> [u8; 9] {
+        let mut key = [0; 9];
+        key[..5].copy_from_slice(&self.0.to_be_bytes()[3..]);
+        key[5..].copy_from_slice(&index.to_be_bytes());
+        key
+    }
+
+    /// Every key of the block in order.
+    pub fn keys(self) -> impl Iterator<Item = [u8; 9]> {
+        (0..=u32::MAX).map(move |index| self.key(index))
+    }
+
+    /// The block as a key space for [KeySearch], enumerated in the same order.
+    pub fn key_space(self) -> KeySpace {
+        KeySpace::full().with_prefix(&self.key(0)[..5])
+    }
+}
+
+/// The known start of an RSA secret-key challenge message, three blocks.
+pub const CHALLENGE_PLAINTEXT: &[u8; 24] = b"The unknown message is: ";
+
+/// The known start of an RSA secret-key challenge message.
+///
+/// The challenges encrypt a message starting with [CHALLENGE_PLAINTEXT] in CBC mode under
+/// [RC5_32_12_9]. A single 64 bit block leaves about `2^8` false keys over the `2^72` key space,
+/// so every key is checked against all three known blocks, which leaves a false match with
+/// probability about `2^-120`.
+pub struct Challenge {
+    /// Each plaintext block XOR the previous ciphertext block, or the IV, with the ciphertext
+    /// block the cipher encrypts it to.
+    pairs: [(Block<RC5_32_12_9>, Block<RC5_32_12_9>); 3],
+}
+
+impl Challenge {
+    /// The challenge from its published IV and the first 24 bytes of its ciphertext.
+    pub fn new(iv: &[u8; 8], ciphertext: &[u8; 24]) -> Self {
+        let mut previous = *iv;
+        let pairs = std::array::from_fn(|i| {
+            let mut input =
+                Block::<RC5_32_12_9>::clone_from_slice(&CHALLENGE_PLAINTEXT[8 * i..][..8]);
+            input
+                .iter_mut()
+                .zip(previous)
+                .for_each(|(byte, c)| *byte ^= c);
+            let output = Block::<RC5_32_12_9>::clone_from_slice(&ciphertext[8 * i..][..8]);
+            previous = output.into();
+            (input, output)
+        });
+
+        Self { pairs }
+    }
+
+    /// Whether `key` decrypts the challenge.
+    pub fn check(&self, key: &[u8; 9]) -> bool {
+        let cipher = RC5_32_12_9::new(key.into());
+        self.pairs.iter().all(|(input, ciphertext)| {
+            let mut block = *input;
+            cipher.encrypt_block(&mut block);
+            block == *ciphertext
+        })
+    }
+
+    /// Search for the key in `space`, configure threads and progress on the returned search.
+    pub fn search_in(&self, space: KeySpace) -> KeySearch<'_, RC5_32_12_9> {
+        KeySearch::new(space, &self.pairs)
+    }
+
+    /// [Challenge::search_in] the keys of a [StatsBlock], unverified like it.
+    #[doc(hidden)]
+    pub fn search(&self, block: StatsBlock) -> KeySearch<'_, RC5_32_12_9> {
+        self.search_in(block.key_space())
+    }
+}
@@ -0,0 +1,94 @@
+use std::{fmt, marker::PhantomData, ops::Deref};
+
+use cipher::{
+    rand_core::{CryptoRng, RngCore},
+    zeroize::Zeroize,
+    Block, BlockEncrypt, KeyInit,
+};
+
+use crate::{Keystream, KeystreamMode, RC5_32_12_16};
+
+/// Secret kept encrypted in memory, decrypted only while a [SecureGuard] is alive.
+///
+/// The contents are CTR encrypted under a key used for this buffer only, and [SecureBuffer::read]
+/// decrypts them into a temporary which is zeroized when the guard is dropped. This shortens the
+/// time a plaintext secret sits in memory, so memory dumps and swapped pages taken at other times
+/// only show ciphertext. The expanded key lives in the same process, an attacker who can read all
+/// memory at any moment still gets both, so this is hardening, not isolation.
+pub struct SecureBuffer<C: BlockEncrypt = RC5_32_12_16> {
+    cipher: C,
+    data: Vec<u8>,
+}
+
+impl<C: BlockEncrypt + KeyInit> SecureBuffer<C> {
+    /// Encrypt `contents` in place under a fresh key from `rng` and take ownership of it.
+    pub fn new(rng: &mut (impl CryptoRng + RngCore), mut contents: Vec<u8>) -> Self {
+        let mut key = C::generate_key(rng);
+        let cipher = C::new(&key);
+        key.zeroize();
+        keystream(&cipher).apply(&mut contents);
+
+        Self {
+            cipher,
+            data: contents,
+        }
+    }
+}
+
+impl<C: BlockEncrypt> SecureBuffer<C> {
+    /// Decrypt the contents for as long as the returned guard lives.
+    pub fn read(&self) -> SecureGuard<'_> {
+        let mut plaintext = self.data.clone();
+        keystream(&self.cipher).apply(&mut plaintext);
+
+        SecureGuard {
+            plaintext,
+            buffer: PhantomData,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+}
+
+impl<C: BlockEncrypt> fmt::Debug for SecureBuffer<C> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecureBuffer({} bytes)", self.data.len())
+    }
+}
+
+/// Decrypted contents of a [SecureBuffer], zeroized on drop.
+pub struct SecureGuard<'a> {
+    plaintext: Vec<u8>,
+    buffer: PhantomData<&'a ()>,
+}
+
+impl Deref for SecureGuard<'_> {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        &self.plaintext
+    }
+}
+
+impl Drop for SecureGuard<'_> {
+    fn drop(&mut self) {
+        self.plaintext.as_mut_slice().zeroize();
+    }
+}
+
+impl fmt::Debug for SecureGuard<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "SecureGuard({} bytes)", self.plaintext.len())
+    }
+}
+
+// every buffer has its own key, so a fixed IV never repeats a keystream across buffers
+fn keystream<C: BlockEncrypt>(cipher: &C) -> Keystream<'_, C> {
+    Keystream::new(cipher, KeystreamMode::Ctr, &Block::<C>::default())
+}
@@ -0,0 +1,139 @@
+use std::fmt;
+
+use cipher::{
+    consts::{U12, U16, U20, U4},
+    generic_array::GenericArray,
+    BlockDecrypt, BlockEncrypt, KeyInit,
+};
+
+use crate::{Keystream, KeystreamMode, Rc5, RC5_16_16_8, RC5_32_12_16, RC5_64_24_24};
+
+const KEY_0_15: [u8; 16] = [
+    0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
+];
+
+/// Run the built-in known answer tests of the block ciphers and keystream modes.
+///
+/// Meant as a power-on self-test for products whose certification or policy requires checking
+/// the crypto code at startup. Takes a few microseconds.
+pub fn self_test() -> Result<(), SelfTestError> {
+    block_kat::<RC5_32_12_16>(
+        "RC5-32/12/16",
+        &KEY_0_15,
+        &[0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77],
+        &[0x2D, 0xDC, 0x14, 0x9B, 0xCF, 0x08, 0x8B, 0x9E],
+    )?;
+    block_kat::<Rc5<u32, U20, U16>>(
+        "RC5-32/20/16",
+        &KEY_0_15,
+        &[0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07],
+        &[0x2A, 0x0E, 0xDC, 0x0E, 0x94, 0x31, 0xFF, 0x73],
+    )?;
+    block_kat::<Rc5<u8, U12, U4>>(
+        "RC5-8/12/4",
+        &[0x00, 0x01, 0x02, 0x03],
+        &[0x00, 0x01],
+        &[0x21, 0x2A],
+    )?;
+    block_kat::<RC5_16_16_8>(
+        "RC5-16/16/8",
+        &KEY_0_15[..8],
+        &[0x00, 0x01, 0x02, 0x03],
+        &[0x23, 0xA8, 0xD7, 0x2E],
+    )?;
+    block_kat::<RC5_64_24_24>(
+        "RC5-64/24/24",
+        &[
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D,
+            0x0E, 0x0F, 0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+        ],
+        &KEY_0_15,
+        &[
+            0xA4, 0x67, 0x72, 0x82, 0x0E, 0xDB, 0xCE, 0x02, 0x35, 0xAB, 0xEA, 0x32, 0xAE, 0x71,
+            0x78, 0xDA,
+        ],
+    )?;
+
+    let rc5 = RC5_32_12_16::new(&KEY_0_15.into());
+    keystream_kat(
+        "CTR",
+        &rc5,
+        KeystreamMode::Ctr,
+        &[
+            0x45, 0x67, 0xC3, 0x88, 0xCE, 0xE7, 0x4D, 0x44, 0xBD, 0xB5, 0x43, 0x5E, 0xBA, 0xC7,
+            0x92, 0x79, 0x45, 0xBA, 0xE1, 0x72,
+        ],
+    )?;
+    keystream_kat(
+        "OFB",
+        &rc5,
+        KeystreamMode::Ofb,
+        &[
+            0x45, 0x67, 0xC3, 0x88, 0xCE, 0xE7, 0x4D, 0x44, 0x80, 0x90, 0x7F, 0x68, 0xDA, 0xB8,
+            0xB6, 0x00, 0xB6, 0xB6, 0xD2, 0x66,
+        ],
+    )
+}
+
+fn block_kat<C: BlockEncrypt + BlockDecrypt + KeyInit>(
+    name: &'static str,
+    key: &[u8],
+    plaintext: &[u8],
+    ciphertext: &[u8],
+) -> Result<(), SelfTestError> {
+    let cipher = C::new(GenericArray::from_slice(key));
+    let mut block = GenericArray::clone_from_slice(plaintext);
+
+    cipher.encrypt_block(&mut block);
+    if block[..] != *ciphertext {
+        return Err(SelfTestError::Encrypt(name));
+    }
+
+    cipher.decrypt_block(&mut block);
+    if block[..] != *plaintext {
+        return Err(SelfTestError::Decrypt(name));
+    }
+
+    Ok(())
+}
+
+/// Keystream from the IV `00000000000000FF`, which carries into the next counter byte.
+fn keystream_kat(
+    name: &'static str,
+    cipher: &RC5_32_12_16,
+    mode: KeystreamMode,
+    expected: &[u8],
+) -> Result<(), SelfTestError> {
+    let mut keystream = vec![0; expected.len()];
+    Keystream::new(cipher, mode, &[0, 0, 0, 0, 0, 0, 0, 0xFF].into()).fill(&mut keystream);
+
+    if keystream != expected {
+        return Err(SelfTestError::Keystream(name));
+    }
+
+    Ok(())
+}
+
+/// A known answer test of [self_test] failed.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum SelfTestError {
+    /// Encryption of the named cipher gave the wrong ciphertext.
+    Encrypt(&'static str),
+    /// Decryption of the named cipher didn't restore the plaintext.
+    Decrypt(&'static str),
+    /// The named keystream mode gave the wrong keystream.
+    Keystream(&'static str),
+}
+
+impl fmt::Display for SelfTestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encrypt(name) => write!(f, "{name} encryption self-test failed"),
+            Self::Decrypt(name) => write!(f, "{name} decryption self-test failed"),
+            Self::Keystream(name) => write!(f, "{name} keystream self-test failed"),
+        }
+    }
+}
+
+impl std::error::Error for SelfTestError {}
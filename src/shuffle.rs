@@ -0,0 +1,72 @@
+use cipher::{Block, BlockEncrypt};
+
+use crate::Keystream;
+
+/// Deterministically permute `slice` with a Fisher-Yates shuffle driven by the CTR keystream of
+/// `cipher`, started from the all zero counter.
+///
+/// The same key always produces the same permutation for a given length, [keyed_unshuffle]
+/// restores the original order.
+pub fn keyed_shuffle<C: BlockEncrypt, T>(cipher: &C, slice: &mut [T]) {
+    for (i, j) in swaps(cipher, slice.len()) {
+        slice.swap(i, j);
+    }
+}
+
+/// Undo [keyed_shuffle] with the same key.
+pub fn keyed_unshuffle<C: BlockEncrypt, T>(cipher: &C, slice: &mut [T]) {
+    let swaps: Vec<_> = swaps(cipher, slice.len()).collect();
+
+    for (i, j) in swaps.into_iter().rev() {
+        slice.swap(i, j);
+    }
+}
+
+fn swaps<C: BlockEncrypt>(cipher: &C, len: usize) -> impl Iterator<Item = (usize, usize)> + '_ {
+    let mut rng = KeystreamRng::new(cipher);
+
+    (1..len)
+        .rev()
+        .map(move |i| (i, rng.below(i as u64 + 1) as usize))
+}
+
+/// Uniform integers drawn from the CTR keystream.
+struct KeystreamRng<'a, C: BlockEncrypt> {
+    keystream: Keystream<'a, C>,
+    block: Block<C>,
+    position: usize,
+}
+
+impl<'a, C: BlockEncrypt> KeystreamRng<'a, C> {
+    fn new(cipher: &'a C) -> Self {
+        Self {
+            keystream: Keystream::ctr_at(cipher, &Default::default(), 0),
+            block: Default::default(),
+            position: C::block_size(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (0..8).fold(0, |value, _| {
+            if self.position == self.block.len() {
+                self.block = self.keystream.next_block();
+                self.position = 0;
+            }
+            self.position += 1;
+
+            value << 8 | u64::from(self.block[self.position - 1])
+        })
+    }
+
+    /// Uniform in `0..bound`, rejecting the biased tail of the `u64` range.
+    fn below(&mut self, bound: u64) -> u64 {
+        let zone = u64::MAX - u64::MAX % bound;
+
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
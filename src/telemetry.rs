@@ -0,0 +1,17 @@
+//! Counters reported through the `metrics` facade, the names are listed in the README.
+
+pub(crate) fn key_setups(count: u64) {
+    metrics::counter!("rc5_key_setups_total").increment(count);
+}
+
+pub(crate) fn blocks(op: &'static str, blocks: u64, block_size: usize) {
+    if blocks == 0 {
+        return;
+    }
+    metrics::counter!("rc5_blocks_total", "op" => op).increment(blocks);
+    metrics::counter!("rc5_bytes_total", "op" => op).increment(blocks * block_size as u64);
+}
+
+pub(crate) fn tag_failure(scheme: &'static str) {
+    metrics::counter!("rc5_tag_failures_total", "scheme" => scheme).increment(1);
+}
@@ -0,0 +1,182 @@
+use std::{
+    fmt,
+    ops::Mul,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use cipher::{
+    consts::U2,
+    generic_array::{ArrayLength, GenericArray},
+    typenum::{Prod, Unsigned},
+    Block, BlockDecrypt, BlockEncrypt, Key, KeyInit, KeySizeUser,
+};
+use dbl::Dbl;
+use subtle::ConstantTimeEq;
+
+use crate::{
+    cbc,
+    encoding::{base64_url_decode, base64_url_encode},
+    mac::cmac,
+    Plaintext,
+};
+
+/// Format version, the first byte of every token.
+const VERSION: u8 = 0x01;
+
+/// How far in the future a token timestamp may be, to allow for clock skew between servers.
+const MAX_CLOCK_SKEW: u64 = 60;
+
+/// Timestamped, encrypted and authenticated tokens, modeled on Fernet.
+///
+/// A token is the unpadded URL-safe base64 of `version || timestamp || IV || ciphertext || tag`:
+/// the version byte, the creation time as big endian Unix seconds, a random IV, the plaintext
+/// encrypted in CBC mode with PKCS#7 padding and a CMAC of everything before it. The MAC is
+/// checked before anything is decrypted, so there is no padding oracle, and the timestamp lets
+/// [TokenCipher::decrypt] reject tokens older than a TTL.
+///
+/// Tokens aren't compatible with Fernet itself, which uses AES-128 and HMAC-SHA256. With a 64
+/// bit block cipher the tag is 64 bits and random IVs start repeating after about `2^32` tokens
+/// per key, rotate keys well before that.
+///
+/// The [KeyInit] key is `mac_key || encryption_key`, so token ciphers can be kept in a
+/// [KeyRing](crate::KeyRing).
+pub struct TokenCipher<C> {
+    mac: C,
+    cipher: C,
+}
+
+impl<C> TokenCipher<C>
+where
+    C: BlockEncrypt + BlockDecrypt + KeyInit,
+    Block<C>: Dbl,
+{
+    /// Independent keys for the MAC and the encryption.
+    pub fn new(mac_key: &Key<C>, encryption_key: &Key<C>) -> Self {
+        Self {
+            mac: C::new(mac_key),
+            cipher: C::new(encryption_key),
+        }
+    }
+
+    /// Token for `plaintext` created now, with a random IV.
+    #[cfg(feature = "rand_core")]
+    pub fn encrypt(
+        &self,
+        plaintext: &[u8],
+        rng: &mut (impl cipher::rand_core::CryptoRng + cipher::rand_core::RngCore),
+    ) -> String {
+        let iv = crate::keystream::random_iv::<C>(rng);
+        self.encrypt_at(plaintext, unix_now(), &iv)
+    }
+
+    /// Token for `plaintext` created at `timestamp` Unix seconds. The IV must be random and never
+    /// reused, prefer [TokenCipher::encrypt].
+    pub fn encrypt_at(&self, plaintext: &[u8], timestamp: u64, iv: &Block<C>) -> String {
+        let mut token = vec![VERSION];
+        token.extend_from_slice(&timestamp.to_be_bytes());
+        token.extend_from_slice(iv);
+        cbc::encrypt_padded(&self.cipher, iv, plaintext, &mut token);
+
+        let tag = cmac(&self.mac, &token);
+        token.extend_from_slice(&tag);
+
+        base64_url_encode(&token)
+    }
+
+    /// Verify and decrypt a token, rejecting it if it is older than `ttl`.
+    pub fn decrypt(&self, token: &str, ttl: Option<Duration>) -> Result<Plaintext, TokenError> {
+        self.decrypt_at(token, ttl, unix_now())
+    }
+
+    /// [TokenCipher::decrypt] at `now` Unix seconds.
+    pub fn decrypt_at(
+        &self,
+        token: &str,
+        ttl: Option<Duration>,
+        now: u64,
+    ) -> Result<Plaintext, TokenError> {
+        let block_size = C::BlockSize::USIZE;
+        let token = base64_url_decode(token).ok_or(TokenError::Invalid)?;
+
+        // version, timestamp, IV, at least one ciphertext block and the tag
+        if token.len() < 1 + 8 + 3 * block_size
+            || !(token.len() - 1 - 8).is_multiple_of(block_size)
+            || token[0] != VERSION
+        {
+            return Err(TokenError::Invalid);
+        }
+
+        let (signed, tag) = token.split_at(token.len() - block_size);
+        if !bool::from(cmac(&self.mac, signed).as_slice().ct_eq(tag)) {
+            #[cfg(feature = "metrics")]
+            crate::telemetry::tag_failure("token");
+            #[cfg(feature = "tracing")]
+            tracing::warn!(scheme = "token", "authentication failed");
+            return Err(TokenError::Invalid);
+        }
+
+        let timestamp = u64::from_be_bytes(signed[1..9].try_into().expect("8 bytes"));
+        if timestamp > now.saturating_add(MAX_CLOCK_SKEW) {
+            return Err(TokenError::Invalid);
+        }
+        if ttl.is_some_and(|ttl| timestamp.saturating_add(ttl.as_secs()) < now) {
+            return Err(TokenError::Expired);
+        }
+
+        let (iv, ciphertext) = signed[9..].split_at(block_size);
+        // a padding error is only reachable with a valid tag, that is with the key
+        cbc::decrypt_padded(&self.cipher, iv, ciphertext).ok_or(TokenError::Invalid)
+    }
+}
+
+impl<C> KeySizeUser for TokenCipher<C>
+where
+    C: KeySizeUser,
+    C::KeySize: Mul<U2>,
+    Prod<C::KeySize, U2>: ArrayLength<u8>,
+{
+    type KeySize = Prod<C::KeySize, U2>;
+}
+
+impl<C> KeyInit for TokenCipher<C>
+where
+    C: KeyInit,
+    C::KeySize: Mul<U2>,
+    Prod<C::KeySize, U2>: ArrayLength<u8>,
+{
+    fn new(key: &Key<Self>) -> Self {
+        let (mac_key, encryption_key) = key.split_at(C::KeySize::USIZE);
+
+        Self {
+            mac: C::new(GenericArray::from_slice(mac_key)),
+            cipher: C::new(GenericArray::from_slice(encryption_key)),
+        }
+    }
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |since| since.as_secs())
+}
+
+/// Error verifying a token created by [TokenCipher].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum TokenError {
+    /// The token is malformed, was modified or was created with another key.
+    Invalid,
+    /// The token is authentic but older than the TTL.
+    Expired,
+}
+
+impl fmt::Display for TokenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Invalid => f.write_str("invalid token"),
+            Self::Expired => f.write_str("expired token"),
+        }
+    }
+}
+
+impl std::error::Error for TokenError {}
@@ -0,0 +1,127 @@
+use std::{fmt, ops::Mul};
+
+use cipher::{
+    consts::{U1, U3},
+    generic_array::{ArrayLength, GenericArray},
+    inout::InOut,
+    typenum::{Prod, Unsigned},
+    AlgorithmName, Block, BlockBackend, BlockCipher, BlockClosure, BlockDecrypt, BlockEncrypt,
+    BlockSizeUser, Key, KeyInit, KeySizeUser, ParBlocksSizeUser,
+};
+
+use crate::RC5_32_12_16;
+
+/// Triple encryption in EDE mode, by default over RC5-32/12/16.
+///
+/// `C = E(K3, D(K2, E(K1, P)))` with the key laid out as `K1 || K2 || K3`. The block size stays
+/// the same, so it can replace the single cipher wherever blocks are exchanged. Choosing
+/// `K1 == K2` (or `K2 == K3`) reduces it to a single encryption, which keeps it interoperable
+/// with peers still using one key.
+///
+/// Like triple DES, meet-in-the-middle attacks limit the strength to about two key lengths.
+pub struct TripleRc5<C = RC5_32_12_16> {
+    ciphers: [C; 3],
+}
+
+#[allow(non_camel_case_types)]
+pub type RC5_EDE3_32_12_16 = TripleRc5<RC5_32_12_16>;
+
+impl<C> KeySizeUser for TripleRc5<C>
+where
+    C: KeySizeUser,
+    C::KeySize: Mul<U3>,
+    Prod<C::KeySize, U3>: ArrayLength<u8>,
+{
+    type KeySize = Prod<C::KeySize, U3>;
+}
+
+impl<C> KeyInit for TripleRc5<C>
+where
+    C: KeyInit,
+    C::KeySize: Mul<U3>,
+    Prod<C::KeySize, U3>: ArrayLength<u8>,
+{
+    fn new(key: &Key<Self>) -> Self {
+        let mut keys = key.chunks_exact(C::KeySize::USIZE);
+        let ciphers = [(); 3].map(|_| C::new(GenericArray::from_slice(keys.next().unwrap())));
+
+        Self { ciphers }
+    }
+}
+
+impl<C: BlockSizeUser> BlockSizeUser for TripleRc5<C> {
+    type BlockSize = C::BlockSize;
+}
+
+impl<C: BlockCipher> BlockCipher for TripleRc5<C> {}
+
+impl<C: BlockEncrypt + BlockDecrypt> BlockEncrypt for TripleRc5<C> {
+    fn encrypt_with_backend(&self, f: impl BlockClosure<BlockSize = Self::BlockSize>) {
+        f.call(&mut EncryptBackend(self))
+    }
+}
+
+impl<C: BlockEncrypt + BlockDecrypt> BlockDecrypt for TripleRc5<C> {
+    fn decrypt_with_backend(&self, f: impl BlockClosure<BlockSize = Self::BlockSize>) {
+        f.call(&mut DecryptBackend(self))
+    }
+}
+
+impl<C: AlgorithmName> AlgorithmName for TripleRc5<C> {
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("EDE3<")?;
+        C::write_alg_name(f)?;
+        f.write_str(">")
+    }
+}
+
+struct EncryptBackend<'a, C>(&'a TripleRc5<C>);
+
+struct DecryptBackend<'a, C>(&'a TripleRc5<C>);
+
+impl<C: BlockSizeUser> BlockSizeUser for EncryptBackend<'_, C> {
+    type BlockSize = C::BlockSize;
+}
+
+impl<C: BlockSizeUser> ParBlocksSizeUser for EncryptBackend<'_, C> {
+    type ParBlocksSize = U1;
+}
+
+impl<C: BlockEncrypt + BlockDecrypt> BlockBackend for EncryptBackend<'_, C> {
+    #[inline(always)]
+    fn proc_block(&mut self, mut block: InOut<'_, '_, Block<Self>>) {
+        let [k1, k2, k3] = &self.0.ciphers;
+
+        let mut inner = block.clone_in();
+        k1.encrypt_block(&mut inner);
+        k2.decrypt_block(&mut inner);
+        k3.encrypt_block(&mut inner);
+
+        *block.get_out() = inner;
+    }
+}
+
+impl<C: BlockSizeUser> BlockSizeUser for DecryptBackend<'_, C> {
+    type BlockSize = C::BlockSize;
+}
+
+impl<C: BlockSizeUser> ParBlocksSizeUser for DecryptBackend<'_, C> {
+    type ParBlocksSize = U1;
+}
+
+impl<C: BlockEncrypt + BlockDecrypt> BlockBackend for DecryptBackend<'_, C> {
+    #[inline(always)]
+    fn proc_block(&mut self, mut block: InOut<'_, '_, Block<Self>>) {
+        let [k1, k2, k3] = &self.0.ciphers;
+
+        let mut inner = block.clone_in();
+        k3.decrypt_block(&mut inner);
+        k2.encrypt_block(&mut inner);
+        k1.decrypt_block(&mut inner);
+
+        *block.get_out() = inner;
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<C: cipher::zeroize::ZeroizeOnDrop> cipher::zeroize::ZeroizeOnDrop for TripleRc5<C> {}
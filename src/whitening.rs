@@ -0,0 +1,146 @@
+use std::{fmt, ops::Add, ops::Mul};
+
+use cipher::{
+    consts::{U1, U2},
+    generic_array::{ArrayLength, GenericArray},
+    inout::InOut,
+    typenum::{Prod, Sum, Unsigned},
+    AlgorithmName, Block, BlockBackend, BlockCipher, BlockClosure, BlockDecrypt, BlockEncrypt,
+    BlockSizeUser, Key, KeyInit, KeySizeUser, ParBlocksSizeUser,
+};
+
+use crate::RC5_32_12_16;
+
+/// DESX-style key whitening around a block cipher, by default RC5-32/12/16.
+///
+/// `C = K2 ^ E(K, P ^ K1)` with the key laid out as `K || K1 || K2`, the whitening keys `K1` and
+/// `K2` being one block each.
+///
+/// Whitening makes exhaustive search harder: recovering the key by brute force costs about
+/// `2^(k + n - 1 - log2(m))` encryptions for a `k` bit cipher key, `n` bit block and `m` known
+/// plaintexts (Kilian and Rogaway, "How to protect DES against exhaustive key search"). It does
+/// nothing against attacks on the cipher itself, like differential cryptanalysis, and the
+/// effective key length is still limited by the block size, so it is a way to stretch a small
+/// key, not a replacement for more rounds or a larger word size.
+pub struct Rc5X<C: BlockSizeUser = RC5_32_12_16> {
+    cipher: C,
+    pre_whitening: Block<C>,
+    post_whitening: Block<C>,
+}
+
+#[allow(non_camel_case_types)]
+pub type RC5X_32_12_16 = Rc5X<RC5_32_12_16>;
+
+impl<C> KeySizeUser for Rc5X<C>
+where
+    C: BlockSizeUser + KeySizeUser,
+    C::BlockSize: Mul<U2>,
+    C::KeySize: Add<Prod<C::BlockSize, U2>>,
+    Sum<C::KeySize, Prod<C::BlockSize, U2>>: ArrayLength<u8>,
+{
+    type KeySize = Sum<C::KeySize, Prod<C::BlockSize, U2>>;
+}
+
+impl<C> KeyInit for Rc5X<C>
+where
+    C: BlockSizeUser + KeyInit,
+    C::BlockSize: Mul<U2>,
+    C::KeySize: Add<Prod<C::BlockSize, U2>>,
+    Sum<C::KeySize, Prod<C::BlockSize, U2>>: ArrayLength<u8>,
+{
+    fn new(key: &Key<Self>) -> Self {
+        let (cipher_key, whitening) = key.split_at(C::KeySize::USIZE);
+        let (pre_whitening, post_whitening) = whitening.split_at(C::BlockSize::USIZE);
+
+        Self {
+            cipher: C::new(GenericArray::from_slice(cipher_key)),
+            pre_whitening: GenericArray::clone_from_slice(pre_whitening),
+            post_whitening: GenericArray::clone_from_slice(post_whitening),
+        }
+    }
+}
+
+impl<C: BlockSizeUser> BlockSizeUser for Rc5X<C> {
+    type BlockSize = C::BlockSize;
+}
+
+impl<C: BlockCipher> BlockCipher for Rc5X<C> {}
+
+impl<C: BlockEncrypt> BlockEncrypt for Rc5X<C> {
+    fn encrypt_with_backend(&self, f: impl BlockClosure<BlockSize = Self::BlockSize>) {
+        f.call(&mut EncryptBackend(self))
+    }
+}
+
+impl<C: BlockDecrypt> BlockDecrypt for Rc5X<C> {
+    fn decrypt_with_backend(&self, f: impl BlockClosure<BlockSize = Self::BlockSize>) {
+        f.call(&mut DecryptBackend(self))
+    }
+}
+
+impl<C: BlockSizeUser + AlgorithmName> AlgorithmName for Rc5X<C> {
+    fn write_alg_name(f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("RC5X<")?;
+        C::write_alg_name(f)?;
+        f.write_str(">")
+    }
+}
+
+struct EncryptBackend<'a, C: BlockSizeUser>(&'a Rc5X<C>);
+
+struct DecryptBackend<'a, C: BlockSizeUser>(&'a Rc5X<C>);
+
+impl<C: BlockSizeUser> BlockSizeUser for EncryptBackend<'_, C> {
+    type BlockSize = C::BlockSize;
+}
+
+impl<C: BlockSizeUser> ParBlocksSizeUser for EncryptBackend<'_, C> {
+    type ParBlocksSize = U1;
+}
+
+impl<C: BlockEncrypt> BlockBackend for EncryptBackend<'_, C> {
+    #[inline(always)]
+    fn proc_block(&mut self, mut block: InOut<'_, '_, Block<Self>>) {
+        let mut whitened = block.clone_in();
+        xor(&mut whitened, &self.0.pre_whitening);
+        self.0.cipher.encrypt_block(&mut whitened);
+        xor(&mut whitened, &self.0.post_whitening);
+
+        *block.get_out() = whitened;
+    }
+}
+
+impl<C: BlockSizeUser> BlockSizeUser for DecryptBackend<'_, C> {
+    type BlockSize = C::BlockSize;
+}
+
+impl<C: BlockSizeUser> ParBlocksSizeUser for DecryptBackend<'_, C> {
+    type ParBlocksSize = U1;
+}
+
+impl<C: BlockDecrypt> BlockBackend for DecryptBackend<'_, C> {
+    #[inline(always)]
+    fn proc_block(&mut self, mut block: InOut<'_, '_, Block<Self>>) {
+        let mut whitened = block.clone_in();
+        xor(&mut whitened, &self.0.post_whitening);
+        self.0.cipher.decrypt_block(&mut whitened);
+        xor(&mut whitened, &self.0.pre_whitening);
+
+        *block.get_out() = whitened;
+    }
+}
+
+fn xor(block: &mut [u8], key: &[u8]) {
+    block.iter_mut().zip(key).for_each(|(b, k)| *b ^= k);
+}
+
+#[cfg(feature = "zeroize")]
+impl<C: BlockSizeUser> Drop for Rc5X<C> {
+    fn drop(&mut self) {
+        cipher::zeroize::Zeroize::zeroize(self.pre_whitening.as_mut_slice());
+        cipher::zeroize::Zeroize::zeroize(self.post_whitening.as_mut_slice());
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<C: BlockSizeUser + cipher::zeroize::ZeroizeOnDrop> cipher::zeroize::ZeroizeOnDrop for Rc5X<C> {}
@@ -0,0 +1,82 @@
+use std::ops::Add;
+
+use cipher::{
+    consts::U16,
+    generic_array::{ArrayLength, GenericArray},
+    typenum::{Sum, Unsigned},
+    Block, BlockEncrypt, BlockSizeUser, Key, KeyInit,
+};
+use dbl::Dbl;
+
+use crate::{mac::Cmac, Keystream, KeystreamMode};
+
+/// Label of the subkey derivation.
+const LABEL: &[u8] = b"XCTR";
+
+/// Nonce of [XCtr], 16 bytes for the subkey followed by the IV, 192 bits with a 64 bit block.
+pub type XNonce<C> = GenericArray<u8, Sum<U16, <C as BlockSizeUser>::BlockSize>>;
+
+/// Extended nonce CTR mode, XRC5-CTR with RC5, in the spirit of XSalsa20.
+///
+/// Each message derives its own subkey from the first 16 bytes of the nonce with the KDF in
+/// counter mode of NIST SP 800-108 over CMAC, `CMAC(K, i || "XCTR" || 0x00 || prefix || L)`,
+/// and is encrypted in CTR mode under the subkey with the rest of the nonce as IV. Random nonces
+/// are then safe for about `2^64` messages per key rather than `2^32` with a random 64 bit IV, so
+/// applications can draw them at random instead of keeping counters.
+///
+/// The mode doesn't authenticate, pair the [subkey](XCtr::subkey) with a MAC or use an
+/// authenticated mode under it.
+pub struct XCtr<C> {
+    cipher: C,
+}
+
+impl<C> XCtr<C>
+where
+    C: BlockEncrypt + KeyInit,
+    Block<C>: Dbl,
+    U16: Add<C::BlockSize>,
+    Sum<U16, C::BlockSize>: ArrayLength<u8>,
+{
+    pub fn new(key: &Key<C>) -> Self {
+        Self {
+            cipher: C::new(key),
+        }
+    }
+
+    /// Cipher under the subkey of `nonce` and the CTR IV, to run another mode under the subkey.
+    pub fn subkey(&self, nonce: &XNonce<C>) -> (C, Block<C>) {
+        let (prefix, iv) = nonce.split_at(16);
+        let block_size = C::BlockSize::USIZE;
+        let key_bits = (C::KeySize::USIZE as u16) * 8;
+
+        let mut subkey = Key::<C>::default();
+        for (i, chunk) in subkey.chunks_mut(block_size).enumerate() {
+            let mut mac = Cmac::new(&self.cipher);
+            mac.update(&[i as u8 + 1]);
+            mac.update(LABEL);
+            mac.update(&[0]);
+            mac.update(prefix);
+            mac.update(&key_bits.to_be_bytes());
+            chunk.copy_from_slice(&mac.finalize()[..chunk.len()]);
+        }
+
+        (C::new(&subkey), Block::<C>::clone_from_slice(iv))
+    }
+
+    /// XOR the keystream of `nonce` into `data`, encrypting or decrypting it.
+    pub fn apply_keystream(&self, nonce: &XNonce<C>, data: &mut [u8]) {
+        let (cipher, iv) = self.subkey(nonce);
+        // tracked with the `nonce-tracking` feature, like any keystream from `Keystream::new`
+        Keystream::new(&cipher, KeystreamMode::Ctr, &iv).apply(data);
+    }
+
+    /// A random nonce.
+    #[cfg(feature = "rand_core")]
+    pub fn generate_nonce(
+        rng: &mut (impl cipher::rand_core::CryptoRng + cipher::rand_core::RngCore),
+    ) -> XNonce<C> {
+        let mut nonce = XNonce::<C>::default();
+        rng.fill_bytes(&mut nonce);
+        nonce
+    }
+}
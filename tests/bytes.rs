@@ -0,0 +1,35 @@
+#![cfg(feature = "bytes")]
+
+#[cfg(test)]
+mod tests {
+    use bytes::{Bytes, BytesMut};
+    use cipher::KeyInit;
+    use rc5::{Keystream, KeystreamMode, RC5_32_12_16};
+
+    #[test]
+    fn buffers() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let iv = [2; 8].into();
+        let keystream = || Keystream::new(&rc5, KeystreamMode::Ctr, &iv);
+
+        let mut expected = vec![0; 21];
+        keystream().fill(&mut expected);
+
+        let mut data = BytesMut::zeroed(21);
+        keystream().apply(&mut data);
+        assert_eq!(data[..], expected[..]);
+
+        // unique buffer, encrypted in place
+        let unique = Bytes::from(vec![0; 21]);
+        let ptr = unique.as_ptr();
+        let encrypted = keystream().apply_bytes(unique);
+        assert_eq!(encrypted[..], expected[..]);
+        assert_eq!(encrypted.as_ptr(), ptr);
+
+        // shared buffer, the other handle is left alone
+        let shared = Bytes::from(vec![0; 21]);
+        let encrypted = keystream().apply_bytes(shared.clone());
+        assert_eq!(encrypted[..], expected[..]);
+        assert_eq!(shared[..], [0; 21]);
+    }
+}
@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod tests {
+    use cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+    use rc5::{Cascade, RC5X_32_12_16, RC5_32_12_16};
+
+    #[test]
+    fn cascade() {
+        let mut key = [0; 48];
+        key[..16].fill(1);
+        key[16..].fill(2);
+        let cascade = <Cascade<RC5_32_12_16, RC5X_32_12_16> as KeyInit>::new(&key.into());
+
+        let first = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let second = <RC5X_32_12_16 as KeyInit>::new(&[2; 32].into());
+        let mut expected = [1, 2, 3, 4, 5, 6, 7, 8].into();
+        first.encrypt_block(&mut expected);
+        second.encrypt_block(&mut expected);
+
+        let mut block = [1, 2, 3, 4, 5, 6, 7, 8].into();
+        cascade.encrypt_block(&mut block);
+        assert_eq!(block, expected);
+
+        cascade.decrypt_block(&mut block);
+        assert_eq!(block, [1, 2, 3, 4, 5, 6, 7, 8].into());
+    }
+
+    #[test]
+    fn from_ciphers() {
+        let cascade = Cascade::from_ciphers(
+            <RC5_32_12_16 as KeyInit>::new(&[1; 16].into()),
+            <RC5_32_12_16 as KeyInit>::new(&[2; 16].into()),
+        );
+        let keyed = <Cascade<RC5_32_12_16, RC5_32_12_16> as KeyInit>::new_from_slice(
+            &[[1; 16], [2; 16]].concat(),
+        )
+        .unwrap();
+
+        let mut block = [1, 2, 3, 4, 5, 6, 7, 8].into();
+        let mut expected = block;
+        cascade.encrypt_block(&mut block);
+        keyed.encrypt_block(&mut expected);
+        assert_eq!(block, expected);
+    }
+}
@@ -0,0 +1,158 @@
+#[cfg(test)]
+mod tests {
+    use cipher::{Block, BlockEncrypt, KeyInit};
+    use rc5::{CbcPad, InvalidCiphertext, IvPlacement, RC5_32_12_16};
+
+    fn cbc() -> CbcPad<RC5_32_12_16> {
+        CbcPad::new(RC5_32_12_16::new(&[1; 16].into()))
+    }
+
+    #[test]
+    fn placements() {
+        let cbc = cbc();
+        let iv = [2; 8].into();
+
+        for len in [0, 1, 7, 8, 9, 100] {
+            let plaintext: Vec<u8> = (0..len).collect();
+            let message = cbc.encrypt_with_iv(&iv, &plaintext);
+            assert_eq!(message[..8], iv[..]);
+            assert_eq!(message.len(), 8 + (len as usize / 8 + 1) * 8);
+
+            for (placement, message) in [
+                (IvPlacement::Prepended, &message[..]),
+                (IvPlacement::Separate(&iv), &message[8..]),
+            ] {
+                assert_eq!(
+                    cbc.decrypt(placement, message)
+                        .map(|opened| opened.to_vec()),
+                    Ok(plaintext.clone())
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn separate_and_derived() {
+        let cbc = cbc();
+        let iv = [2; 8].into();
+
+        let separate = cbc.encrypt_separate(&iv, b"plaintext");
+        assert_eq!(separate, cbc.encrypt_with_iv(&iv, b"plaintext")[8..]);
+        assert_eq!(
+            cbc.decrypt(IvPlacement::Separate(&iv), &separate)
+                .map(|opened| opened.to_vec()),
+            Ok(b"plaintext".to_vec())
+        );
+
+        let derived = cbc.encrypt_derived(b"record 7", b"plaintext");
+        let iv = cbc.derive_iv(b"record 7");
+        assert_eq!(derived, cbc.encrypt_with_iv(&iv, b"plaintext")[8..]);
+        assert_eq!(
+            cbc.decrypt(IvPlacement::Derived(b"record 7"), &derived)
+                .map(|opened| opened.to_vec()),
+            Ok(b"plaintext".to_vec())
+        );
+    }
+
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn random_iv() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let cbc = cbc();
+        let mut rng = StdRng::seed_from_u64(3);
+
+        let first = cbc.encrypt(b"plaintext", &mut rng);
+        let second = cbc.encrypt(b"plaintext", &mut rng);
+        assert_ne!(first[..8], second[..8]);
+        assert_eq!(first, cbc.encrypt_with_iv(first[..8].into(), b"plaintext"));
+        assert_eq!(
+            cbc.decrypt(IvPlacement::Prepended, &first)
+                .map(|opened| opened.to_vec()),
+            Ok(b"plaintext".to_vec())
+        );
+    }
+
+    #[test]
+    fn padme() {
+        let cbc = cbc().padme();
+        let iv = [2; 8].into();
+
+        for (len, padded) in [(0, 8), (7, 16), (8, 16), (100, 112), (1000, 1032)] {
+            let plaintext = vec![5; len];
+            let message = cbc.encrypt_with_iv(&iv, &plaintext);
+            // Padmé to the length plus one, then at least one byte of PKCS#7
+            assert_eq!(message.len(), 8 + padded, "{len}");
+            assert_eq!(
+                cbc.decrypt(IvPlacement::Prepended, &message)
+                    .map(|opened| opened.to_vec()),
+                Ok(plaintext)
+            );
+        }
+    }
+
+    #[test]
+    fn chaining() {
+        let rc5 = RC5_32_12_16::new(&[1; 16].into());
+        let message = cbc().encrypt_with_iv(&[2; 8].into(), b"sixteen bytes...");
+
+        let mut expected = Block::<RC5_32_12_16>::from([2; 8]);
+        for (block, plaintext) in
+            message[8..]
+                .chunks(8)
+                .zip([&b"sixteen "[..], b"bytes...", &[8; 8]])
+        {
+            expected
+                .iter_mut()
+                .zip(plaintext)
+                .for_each(|(byte, plaintext)| *byte ^= plaintext);
+            rc5.encrypt_block(&mut expected);
+            assert_eq!(block, &expected[..]);
+        }
+    }
+
+    #[test]
+    fn derived_iv() {
+        use aes::Aes128;
+        use cmac::{Cmac, Mac};
+
+        let cbc = CbcPad::new(Aes128::new(&[1; 16].into()));
+        let mut mac = <Cmac<Aes128> as Mac>::new(&[1; 16].into());
+        mac.update(b"CBC-IV");
+        mac.update(b"record 7");
+        let iv = mac.finalize().into_bytes();
+
+        assert_eq!(cbc.derive_iv(b"record 7"), iv);
+        assert_ne!(cbc.derive_iv(b"record 8"), iv);
+
+        let message = cbc.encrypt_with_iv(&iv, b"plaintext");
+        let derived = IvPlacement::Derived(b"record 7");
+        assert_eq!(
+            cbc.decrypt(derived, &message[16..])
+                .map(|opened| opened.to_vec()),
+            Ok(b"plaintext".to_vec())
+        );
+        assert_ne!(
+            cbc.decrypt(IvPlacement::Derived(b"record 8"), &message[16..])
+                .map(|opened| opened.to_vec()),
+            Ok(b"plaintext".to_vec())
+        );
+    }
+
+    #[test]
+    fn malformed() {
+        let cbc = cbc();
+        let message = cbc.encrypt_with_iv(&[2; 8].into(), b"plaintext");
+
+        for message in [&message[..0], &message[..7], &message[..8], &message[..15]] {
+            assert_eq!(
+                cbc.decrypt(IvPlacement::Prepended, message),
+                Err(InvalidCiphertext)
+            );
+        }
+        assert_eq!(
+            cbc.decrypt(IvPlacement::Separate(&[2; 8].into()), &message[9..]),
+            Err(InvalidCiphertext)
+        );
+    }
+}
@@ -0,0 +1,121 @@
+#[cfg(test)]
+mod tests {
+    use std::io::{Cursor, IoSliceMut, Read, Seek, SeekFrom};
+
+    use cipher::KeyInit;
+    use rc5::{
+        encrypt_chunks, CtrReader, EncryptReader, Keystream, KeystreamMode, StreamEncryptor,
+        VectoredKeystream, RC5_32_12_16,
+    };
+
+    #[test]
+    fn chunks_of_any_size() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let iv = [2; 8].into();
+        let plaintext: Vec<u8> = (0..100).collect();
+
+        for mode in [KeystreamMode::Ctr, KeystreamMode::Ofb] {
+            let mut expected = plaintext.clone();
+            Keystream::new(&rc5, mode, &iv).apply(&mut expected);
+
+            let chunks = [
+                &plaintext[..3],
+                &plaintext[3..3],
+                &plaintext[3..20],
+                &plaintext[20..],
+            ];
+            let encrypted: Vec<u8> = encrypt_chunks(Keystream::new(&rc5, mode, &iv), chunks)
+                .flatten()
+                .collect();
+            assert_eq!(encrypted, expected);
+
+            let mut reader = EncryptReader::new(Keystream::new(&rc5, mode, &iv), &plaintext[..]);
+            let mut encrypted = vec![0; 5];
+            reader.read_exact(&mut encrypted).unwrap();
+            reader.read_to_end(&mut encrypted).unwrap();
+            assert_eq!(encrypted, expected);
+        }
+    }
+
+    #[test]
+    fn vectored() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let iv = [2; 8].into();
+        let plaintext: Vec<u8> = (0..100).collect();
+
+        let mut expected = plaintext.clone();
+        Keystream::new(&rc5, KeystreamMode::Ctr, &iv).apply(&mut expected);
+
+        let mut data = plaintext.clone();
+        let (first, rest) = data.split_at_mut(3);
+        let (second, rest) = rest.split_at_mut(17);
+        let (third, fourth) = rest.split_at_mut(40);
+        let mut keystream = VectoredKeystream::new(Keystream::new(&rc5, KeystreamMode::Ctr, &iv));
+        keystream.apply_vectored(&mut [IoSliceMut::new(first), IoSliceMut::new(&mut [])]);
+        keystream.apply_vectored(&mut [IoSliceMut::new(second), IoSliceMut::new(third)]);
+        keystream.apply_buffers([fourth]);
+        assert_eq!(data, expected);
+
+        let mut reader = EncryptReader::new(
+            Keystream::new(&rc5, KeystreamMode::Ctr, &iv),
+            Cursor::new(&plaintext),
+        );
+        let (mut first, mut second) = ([0; 5], [0; 95]);
+        let read = reader
+            .read_vectored(&mut [IoSliceMut::new(&mut first), IoSliceMut::new(&mut second)])
+            .unwrap();
+        assert_eq!(read, 100);
+        assert_eq!([&first[..], &second[..]].concat(), expected);
+    }
+
+    #[test]
+    fn stream_with_small_buffer() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let iv = [2; 8].into();
+        let plaintext: Vec<u8> = (0..100).collect();
+
+        let mut expected = plaintext.clone();
+        Keystream::new(&rc5, KeystreamMode::Ctr, &iv).apply(&mut expected);
+
+        for buffer_size in [0, 1, 8, 13, 1000] {
+            let mut stream = StreamEncryptor::new(Keystream::new(&rc5, KeystreamMode::Ctr, &iv))
+                .buffer_size(buffer_size);
+
+            let mut encrypted = Vec::new();
+            assert_eq!(stream.run(&plaintext[..30], &mut encrypted).unwrap(), 30);
+            assert_eq!(stream.run(&plaintext[30..], &mut encrypted).unwrap(), 70);
+            assert_eq!(encrypted, expected);
+        }
+    }
+
+    #[test]
+    fn random_access_reads() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let iv = [2; 8].into();
+        let plaintext: Vec<u8> = (0..100).collect();
+
+        // ciphertext stored after a header
+        let mut file = b"header".to_vec();
+        let mut ciphertext = plaintext.clone();
+        Keystream::new(&rc5, KeystreamMode::Ctr, &iv).apply(&mut ciphertext);
+        file.extend(ciphertext);
+
+        let mut inner = Cursor::new(file);
+        inner.seek(SeekFrom::Start(6)).unwrap();
+        let mut reader = CtrReader::new(&rc5, &iv, inner).unwrap();
+
+        for (offset, len) in [(0, 3), (13, 20), (8, 8), (95, 5), (42, 1)] {
+            let mut buf = vec![0; len];
+            assert_eq!(reader.read_at(&mut buf, offset).unwrap(), len);
+            assert_eq!(buf, plaintext[offset as usize..][..len]);
+        }
+
+        reader.seek(SeekFrom::End(-10)).unwrap();
+        let mut tail = Vec::new();
+        reader.read_to_end(&mut tail).unwrap();
+        assert_eq!(tail, plaintext[90..]);
+
+        assert!(reader.seek(SeekFrom::Current(-103)).is_err());
+        assert_eq!(reader.stream_position().unwrap(), 100);
+    }
+}
@@ -0,0 +1,78 @@
+#![cfg(all(feature = "codegen", target_arch = "x86_64"))]
+
+// Builds the `tests/codegen` crate with LTO and checks that the optimizer didn't turn the data
+// dependent parts of the round function into branches.
+
+#[cfg(test)]
+mod tests {
+    use std::{path::Path, process::Command};
+
+    fn assembly() -> String {
+        let fixture = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/codegen");
+        // a separate target directory, the outer build holds the lock on the default one
+        let target = Path::new(env!("CARGO_TARGET_TMPDIR")).join("codegen");
+
+        let status = Command::new(env!("CARGO"))
+            .args(["rustc", "--release", "--lib", "--target-dir"])
+            .arg(&target)
+            .args(["--", "--emit", "asm"])
+            .current_dir(&fixture)
+            .status()
+            .unwrap();
+        assert!(status.success());
+
+        let deps = target.join("release/deps");
+        let asm = std::fs::read_dir(deps)
+            .unwrap()
+            .map(|entry| entry.unwrap().path())
+            .find(|path| path.extension().is_some_and(|ext| ext == "s"))
+            .expect("no assembly emitted");
+        std::fs::read_to_string(asm).unwrap()
+    }
+
+    /// Instructions of the function `name`, without directives and labels.
+    fn function<'a>(asm: &'a str, name: &str) -> Vec<&'a str> {
+        asm.lines()
+            .skip_while(|line| *line != format!("{name}:"))
+            .take_while(|line| !line.contains(".cfi_endproc"))
+            .map(str::trim)
+            .filter(|line| !line.is_empty() && !line.starts_with('.') && !line.ends_with(':'))
+            .collect()
+    }
+
+    /// Every conditional jump must depend on a comparison with a constant, the round counter,
+    /// and never on the data or the key.
+    fn assert_branches_on_constants(instructions: &[&str]) {
+        assert!(!instructions.is_empty());
+
+        let mut flags = None;
+        for instruction in instructions {
+            let mnemonic = instruction.split_whitespace().next().unwrap();
+            if mnemonic.starts_with('j') && mnemonic != "jmp" {
+                let compare = flags.expect("conditional jump without comparison");
+                assert!(
+                    compare_with_constant(compare),
+                    "`{instruction}` depends on `{compare}`"
+                );
+            } else if !["mov", "lea", "push", "pop", "nop", "call", "jmp"]
+                .iter()
+                .any(|prefix| mnemonic.starts_with(prefix))
+            {
+                flags = Some(*instruction);
+            }
+        }
+    }
+
+    fn compare_with_constant(instruction: &str) -> bool {
+        let mut parts = instruction.split_whitespace();
+        parts.next().unwrap().starts_with("cmp") && parts.next().unwrap().starts_with('$')
+    }
+
+    #[test]
+    fn round_function_is_branch_free() {
+        let asm = assembly();
+
+        assert_branches_on_constants(&function(&asm, "rc5_encrypt"));
+        assert_branches_on_constants(&function(&asm, "rc5_decrypt"));
+    }
+}
@@ -0,0 +1,14 @@
+//! Exported entry points whose assembly is inspected by `tests/codegen.rs`.
+
+use cipher::{BlockDecrypt, BlockEncrypt};
+use rc5::RC5_32_12_16;
+
+#[no_mangle]
+pub fn rc5_encrypt(cipher: &RC5_32_12_16, block: &mut [u8; 8]) {
+    cipher.encrypt_block(block.into());
+}
+
+#[no_mangle]
+pub fn rc5_decrypt(cipher: &RC5_32_12_16, block: &mut [u8; 8]) {
+    cipher.decrypt_block(block.into());
+}
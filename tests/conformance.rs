@@ -0,0 +1,18 @@
+// RustCrypto's `block_cipher_test!` over published vectors, the same trait level checks every
+// RustCrypto block cipher runs. The files in `tests/data` hold key, plaintext and ciphertext
+// triples in blobby format, blobby's `convert` example turns them into hex and back:
+// - rc5_32_12_16: the five vectors of the RC5 paper and the two of the crate's own tests
+// - rc5_32_20_16, rc5_8_12_4, rc5_16_16_8, rc5_64_24_24: from the RC5 test vectors draft for
+//   other parameterizations
+
+#[cfg(test)]
+mod tests {
+    use cipher::consts::{U12, U16, U20, U4};
+    use rc5::{Rc5, RC5_16_16_8, RC5_32_12_16, RC5_64_24_24};
+
+    cipher::block_cipher_test!(rc5_32_12_16, "rc5_32_12_16", RC5_32_12_16);
+    cipher::block_cipher_test!(rc5_32_20_16, "rc5_32_20_16", Rc5<u32, U20, U16>);
+    cipher::block_cipher_test!(rc5_8_12_4, "rc5_8_12_4", Rc5<u8, U12, U4>);
+    cipher::block_cipher_test!(rc5_16_16_8, "rc5_16_16_8", RC5_16_16_8);
+    cipher::block_cipher_test!(rc5_64_24_24, "rc5_64_24_24", RC5_64_24_24);
+}
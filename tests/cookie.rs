@@ -0,0 +1,63 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use rc5::{KeyRing, SessionCookies, TokenCipher, TokenError, RC5_32_12_16};
+
+    #[test]
+    fn rotation() {
+        let start = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_000);
+        let day = Duration::from_secs(24 * 3600);
+
+        let mut keys = KeyRing::<TokenCipher<RC5_32_12_16>>::new();
+        keys.insert(0x1a, &[1; 32].into(), start, Some(start + day));
+        let mut cookies = SessionCookies::new(keys).ttl(2 * day);
+
+        let old = cookies.encode_at(b"user=1", start, &[0; 8].into()).unwrap();
+        assert!(old.starts_with("1a."));
+        assert!(cookies
+            .encode_at(b"user=1", start + day, &[0; 8].into())
+            .is_none());
+
+        cookies
+            .keys_mut()
+            .insert(0x1b, &[2; 32].into(), start + day, None);
+        let new = cookies
+            .encode_at(b"user=2", start + day, &[0; 8].into())
+            .unwrap();
+        assert!(new.starts_with("1b."));
+
+        assert_eq!(
+            cookies.decode_at(&old, start + day).unwrap().as_slice(),
+            b"user=1"
+        );
+        assert_eq!(
+            cookies.decode_at(&new, start + day).unwrap().as_slice(),
+            b"user=2"
+        );
+        assert_eq!(
+            cookies.decode_at(&old, start + 3 * day),
+            Err(TokenError::Expired)
+        );
+
+        // the key ID is part of the lookup, not a way around the tag
+        let swapped = new.replacen("1b.", "1a.", 1);
+        assert_eq!(
+            cookies.decode_at(&swapped, start + day),
+            Err(TokenError::Invalid)
+        );
+        let signed = new.replacen("1b.", "+1b.", 1);
+        for invalid in ["", "1b", "zz.abc", "ff.abc", &signed] {
+            assert_eq!(
+                cookies.decode_at(invalid, start + day),
+                Err(TokenError::Invalid)
+            );
+        }
+
+        cookies.keys_mut().remove(0x1a);
+        assert_eq!(
+            cookies.decode_at(&old, start + day),
+            Err(TokenError::Invalid)
+        );
+    }
+}
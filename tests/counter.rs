@@ -0,0 +1,60 @@
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use rc5::{CounterStore, FileCounterStore, PersistentCounter};
+
+    struct Memory(Option<u64>, usize);
+
+    impl CounterStore for Memory {
+        fn load(&mut self) -> io::Result<Option<u64>> {
+            Ok(self.0)
+        }
+
+        fn store(&mut self, value: u64) -> io::Result<()> {
+            self.0 = Some(value);
+            self.1 += 1;
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn never_repeats_after_restart() {
+        let mut counter = PersistentCounter::open(Memory(None, 0), 10).unwrap();
+        let first: Vec<u64> = (0..15).map(|_| counter.next_value().unwrap()).collect();
+        assert_eq!(first, (0..15).collect::<Vec<_>>());
+
+        // restart without a clean shutdown
+        let store = counter.into_inner();
+        assert_eq!(store.1, 2);
+        let mut counter = PersistentCounter::open(store, 10).unwrap();
+        assert_eq!(counter.next_value().unwrap(), 20);
+    }
+
+    #[test]
+    fn exhausted() {
+        let mut counter = PersistentCounter::open(Memory(Some(u64::MAX - 1), 0), 1).unwrap();
+
+        assert_eq!(counter.next_value().unwrap(), u64::MAX - 1);
+        assert!(counter.next_value().is_err());
+    }
+
+    #[test]
+    fn file_store() {
+        let path = std::env::temp_dir().join(format!("rc5-counter-{}", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let mut counter = PersistentCounter::open(FileCounterStore::new(&path), 100).unwrap();
+        assert_eq!(counter.next_value().unwrap(), 0);
+        assert_eq!(counter.next_value().unwrap(), 1);
+        drop(counter);
+
+        let mut counter = PersistentCounter::open(FileCounterStore::new(&path), 100).unwrap();
+        assert_eq!(counter.next_value().unwrap(), 100);
+
+        std::fs::write(&path, b"torn").unwrap();
+        assert!(PersistentCounter::open(FileCounterStore::new(&path), 100).is_err());
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
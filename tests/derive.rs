@@ -0,0 +1,96 @@
+#![cfg(feature = "derive")]
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use rc5::{DeterministicCipher, EncryptedFields, FieldError, KeyRing, SealField, RC5_32_12_16};
+
+    #[derive(Clone, Debug, PartialEq, EncryptedFields)]
+    struct Customer {
+        id: u64,
+        #[encrypted]
+        email: String,
+        #[encrypted]
+        notes: Vec<u8>,
+    }
+
+    #[derive(EncryptedFields)]
+    struct Generic<T> {
+        #[allow(dead_code)]
+        value: T,
+        #[encrypted]
+        secret: Vec<u8>,
+    }
+
+    fn keys() -> KeyRing<DeterministicCipher<RC5_32_12_16>> {
+        let mut keys = KeyRing::new();
+        keys.insert(1, &[1; 32].into(), SystemTime::UNIX_EPOCH, None);
+        keys
+    }
+
+    fn customer() -> Customer {
+        Customer {
+            id: 7,
+            email: "user@example.com".to_owned(),
+            notes: b"prefers email".to_vec(),
+        }
+    }
+
+    #[test]
+    fn seal_and_open() {
+        let keys = keys();
+
+        let mut sealed = customer();
+        sealed.seal_fields(&keys).unwrap();
+        assert_eq!(sealed.id, 7);
+        assert_ne!(sealed.email, customer().email);
+        assert_eq!(&sealed.notes[..4], &1u32.to_be_bytes());
+
+        let mut opened = sealed.clone();
+        opened.open_fields(&keys).unwrap();
+        assert_eq!(opened, customer());
+
+        // the field name is bound to the ciphertext
+        let mut swapped = sealed.clone();
+        swapped.notes = b"prefers email".to_vec();
+        swapped.notes.seal_field("email", &keys).unwrap();
+        assert_eq!(
+            swapped.open_fields(&keys),
+            Err(FieldError::InvalidCiphertext)
+        );
+
+        let mut generic = Generic {
+            value: 1u8,
+            secret: vec![1, 2, 3],
+        };
+        generic.seal_fields(&keys).unwrap();
+        generic.open_fields(&keys).unwrap();
+        assert_eq!(generic.secret, [1, 2, 3]);
+    }
+
+    #[test]
+    fn key_rotation() {
+        let mut keys = keys();
+        let mut sealed = customer();
+        sealed.seal_fields(&keys).unwrap();
+
+        keys.insert(
+            2,
+            &[2; 32].into(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(1),
+            None,
+        );
+        let mut resealed = customer();
+        resealed.seal_fields(&keys).unwrap();
+        assert_eq!(&resealed.notes[..4], &2u32.to_be_bytes());
+
+        sealed.clone().open_fields(&keys).unwrap();
+        keys.remove(1);
+        assert_eq!(sealed.open_fields(&keys), Err(FieldError::UnknownKey));
+        assert_eq!(
+            customer().seal_fields(&KeyRing::<DeterministicCipher<RC5_32_12_16>>::new()),
+            Err(FieldError::NoCurrentKey)
+        );
+    }
+}
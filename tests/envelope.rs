@@ -0,0 +1,195 @@
+#![cfg(feature = "etm")]
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use aes::Aes128;
+    use cipher::KeyInit;
+    use cmac::{Cmac, Mac};
+    use rc5::{
+        EnvelopeError, EnvelopeHeader, EtM, EtmMode, KeyRing, KeystreamMode, Rc5Const,
+        ENVELOPE_VERSION, RC5_32_12_16, RC5_64_24_24,
+    };
+
+    fn etm(mode: EtmMode) -> EtM<RC5_32_12_16, Cmac<Aes128>> {
+        EtM::new(
+            RC5_32_12_16::new(&[1; 16].into()),
+            <Cmac<Aes128> as Mac>::new(&[2; 16].into()),
+            mode,
+        )
+    }
+
+    #[test]
+    fn header() {
+        let header = EnvelopeHeader::new::<RC5_32_12_16>(EtmMode::CbcPad, 0x0102_0304);
+        let bytes = header.to_bytes();
+        assert_eq!(bytes, [ENVELOPE_VERSION, 32, 12, 16, 1, 0, 1, 2, 3, 4]);
+
+        let mut envelope = bytes.to_vec();
+        envelope.extend_from_slice(b"rest");
+        assert_eq!(EnvelopeHeader::parse(&envelope), Ok((header, &b"rest"[..])));
+
+        let header = EnvelopeHeader::new::<RC5_64_24_24>(EtmMode::Keystream(KeystreamMode::Ofb), 7);
+        assert_eq!(
+            header.to_bytes(),
+            [ENVELOPE_VERSION, 64, 24, 24, 3, 0, 0, 0, 0, 7]
+        );
+        assert!(header.is_variant::<RC5_64_24_24>());
+        assert!(!header.is_variant::<RC5_32_12_16>());
+
+        for (i, byte, err) in [
+            (0, 2, EnvelopeError::UnsupportedVersion),
+            (4, 0, EnvelopeError::Malformed),
+            (4, 4, EnvelopeError::Malformed),
+            (5, 4, EnvelopeError::Malformed),
+        ] {
+            let mut malformed = bytes;
+            malformed[i] = byte;
+            assert_eq!(EnvelopeHeader::parse(&malformed), Err(err));
+        }
+        assert_eq!(
+            EnvelopeHeader::parse(&bytes[..9]),
+            Err(EnvelopeError::Malformed)
+        );
+    }
+
+    #[test]
+    fn seal_and_open() {
+        let modes = [
+            EtmMode::CbcPad,
+            EtmMode::Keystream(KeystreamMode::Ctr),
+            EtmMode::Keystream(KeystreamMode::Ofb),
+        ];
+
+        for (i, mode) in modes.into_iter().enumerate() {
+            let etm = etm(mode);
+            let iv = [i as u8; 8].into();
+            let envelope = etm.seal_with_iv(7, &iv, b"ad", b"plaintext");
+
+            let (header, message) = EnvelopeHeader::parse(&envelope).unwrap();
+            assert_eq!(header, EnvelopeHeader::new::<RC5_32_12_16>(mode, 7));
+            // an EtM message with the tag over the header too
+            let plain = etm.encrypt_with_iv(&iv, b"ad", b"plaintext");
+            assert_eq!(message.len(), plain.len());
+            assert_eq!(message[..plain.len() - 16], plain[..plain.len() - 16]);
+            assert_ne!(message[plain.len() - 16..], plain[plain.len() - 16..]);
+            assert_eq!(
+                etm.open(b"ad", &envelope).map(|opened| opened.to_vec()),
+                Ok(b"plaintext".to_vec())
+            );
+            assert_eq!(
+                etm.open(b"other", &envelope),
+                Err(EnvelopeError::InvalidCiphertext)
+            );
+
+            // the header is authenticated
+            let mut restamped = envelope.clone();
+            restamped[9] ^= 1;
+            assert_eq!(
+                etm.open(b"ad", &restamped),
+                Err(EnvelopeError::InvalidCiphertext)
+            );
+        }
+    }
+
+    #[test]
+    fn honors_header() {
+        let ctr = etm(EtmMode::Keystream(KeystreamMode::Ctr));
+        let envelope = ctr.seal_with_iv(1, &[1; 8].into(), b"", b"plaintext");
+
+        // the mode comes from the header, not the cipher
+        assert_eq!(
+            etm(EtmMode::CbcPad)
+                .open(b"", &envelope)
+                .map(|opened| opened.to_vec()),
+            Ok(b"plaintext".to_vec())
+        );
+
+        let rc5_20 = EtM::new(
+            Rc5Const::<u32, 20, 16>::new(&[1; 16].into()),
+            <Cmac<Aes128> as Mac>::new(&[2; 16].into()),
+            EtmMode::CbcPad,
+        );
+        assert_eq!(
+            rc5_20.open(b"", &envelope),
+            Err(EnvelopeError::VariantMismatch)
+        );
+    }
+
+    #[test]
+    fn padme() {
+        let padded = etm(EtmMode::Keystream(KeystreamMode::Ctr)).padme();
+        let envelope = padded.seal_with_iv(1, &[1; 8].into(), b"", &[7; 100]);
+
+        let (header, message) = EnvelopeHeader::parse(&envelope).unwrap();
+        assert!(header.padme);
+        assert_eq!(envelope[5], 1);
+        // IV, 104 padded bytes and the tag
+        assert_eq!(message.len(), 8 + 104 + 16);
+
+        // the flag is honored by ciphers without the option
+        let plain = etm(EtmMode::CbcPad);
+        assert_eq!(
+            plain.open(b"", &envelope).map(|opened| opened.to_vec()),
+            Ok(vec![7; 100])
+        );
+    }
+
+    #[test]
+    fn committing() {
+        let committing = etm(EtmMode::CbcPad).committing();
+        let envelope = committing.seal_with_iv(1, &[1; 8].into(), b"", b"plaintext");
+        assert!(EnvelopeHeader::parse(&envelope).unwrap().0.committing);
+        assert_eq!(envelope[5], 2);
+
+        // the flag is honored by ciphers without the option
+        assert_eq!(
+            etm(EtmMode::CbcPad)
+                .open(b"", &envelope)
+                .map(|opened| opened.to_vec()),
+            Ok(b"plaintext".to_vec())
+        );
+
+        // but can't be dropped
+        let plain = etm(EtmMode::CbcPad).seal_with_iv(1, &[2; 8].into(), b"", b"plaintext");
+        assert_eq!(
+            committing.open(b"", &plain),
+            Err(EnvelopeError::InvalidCiphertext)
+        );
+    }
+
+    #[test]
+    fn key_ring() {
+        let epoch = SystemTime::UNIX_EPOCH;
+        let mut ring = KeyRing::new();
+        ring.insert_cipher(1, etm(EtmMode::CbcPad), epoch, None);
+
+        let old = ring.seal_with_iv(&[1; 8].into(), b"", b"old").unwrap();
+        assert_eq!(EnvelopeHeader::parse(&old).unwrap().0.key_id, 1);
+
+        let new_key = EtM::new(
+            RC5_32_12_16::new(&[3; 16].into()),
+            <Cmac<Aes128> as Mac>::new(&[4; 16].into()),
+            EtmMode::Keystream(KeystreamMode::Ctr),
+        );
+        ring.insert_cipher(2, new_key, epoch + Duration::from_secs(1), None);
+        let new = ring.seal_with_iv(&[2; 8].into(), b"", b"new").unwrap();
+        assert_eq!(EnvelopeHeader::parse(&new).unwrap().0.key_id, 2);
+
+        for (envelope, plaintext) in [(&old, b"old"), (&new, b"new")] {
+            assert_eq!(
+                ring.open(b"", envelope).map(|opened| opened.to_vec()),
+                Ok(plaintext.to_vec())
+            );
+        }
+
+        ring.remove(1);
+        assert_eq!(ring.open(b"", &old), Err(EnvelopeError::UnknownKey));
+        ring.remove(2);
+        assert_eq!(
+            ring.seal_with_iv(&[3; 8].into(), b"", b""),
+            Err(EnvelopeError::NoCurrentKey)
+        );
+    }
+}
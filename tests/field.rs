@@ -0,0 +1,112 @@
+#[cfg(test)]
+mod tests {
+    use cipher::KeyInit;
+    use rc5::{BlindIndex, DeterministicCipher, InvalidCiphertext, RC5_32_12_16};
+
+    fn hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn rfc5297_vector() {
+        let siv = DeterministicCipher::<aes::Aes128>::new(
+            &[
+                0xff, 0xfe, 0xfd, 0xfc, 0xfb, 0xfa, 0xf9, 0xf8, 0xf7, 0xf6, 0xf5, 0xf4, 0xf3, 0xf2,
+                0xf1, 0xf0,
+            ]
+            .into(),
+            &[
+                0xf0, 0xf1, 0xf2, 0xf3, 0xf4, 0xf5, 0xf6, 0xf7, 0xf8, 0xf9, 0xfa, 0xfb, 0xfc, 0xfd,
+                0xfe, 0xff,
+            ]
+            .into(),
+        );
+        let ad = hex("101112131415161718191a1b1c1d1e1f2021222324252627");
+        let pt = hex("112233445566778899aabbccddee");
+        let ct = hex("85632d07c6e8f37f950acd320a2ecc9340c02b9690c4dc04daef7f6afe5c");
+
+        assert_eq!(siv.encrypt(&[&ad], &pt), ct);
+        assert_eq!(
+            siv.decrypt(&[&ad], &ct).map(|opened| opened.to_vec()),
+            Ok(pt)
+        );
+    }
+
+    #[test]
+    fn cmac_vectors() {
+        // NIST SP 800-38B, AES-128 examples
+        let index = BlindIndex::<aes::Aes128>::new(
+            &[
+                0x2b, 0x7e, 0x15, 0x16, 0x28, 0xae, 0xd2, 0xa6, 0xab, 0xf7, 0x15, 0x88, 0x09, 0xcf,
+                0x4f, 0x3c,
+            ]
+            .into(),
+        );
+        let msg = hex(concat!(
+            "6bc1bee22e409f96e93d7e117393172aae2d8a571e03ac9c9eb76fac45af8e51",
+            "30c81c46a35ce411e5fbc1191a0a52eff69f2445df4f9b17ad2b417be66c3710"
+        ));
+
+        for (len, tag) in [
+            (0, "bb1d6929e95937287fa37d129b756746"),
+            (16, "070a16b46b4d4144f79bdd9dd04a287c"),
+            (40, "dfa66747de9ae63030ca32611497c827"),
+            (64, "51f0bebf7e3b9d92fc49741779363cfe"),
+        ] {
+            assert_eq!(index.tag(&msg[..len])[..], hex(tag)[..]);
+        }
+    }
+
+    #[test]
+    fn deterministic_rc5() {
+        let siv = DeterministicCipher::<RC5_32_12_16>::new(&[1; 16].into(), &[2; 16].into());
+
+        for len in [0, 1, 7, 8, 9, 16, 100] {
+            let pt: Vec<u8> = (0..len).map(|i| i as u8).collect();
+            let ct = siv.encrypt(&[b"users.email"], &pt);
+
+            assert_eq!(ct.len(), 8 + len);
+            assert_eq!(siv.encrypt(&[b"users.email"], &pt), ct);
+            assert_eq!(
+                siv.decrypt(&[b"users.email"], &ct)
+                    .map(|opened| opened.to_vec()),
+                Ok(pt.clone())
+            );
+            assert_eq!(siv.decrypt(&[b"users.name"], &ct), Err(InvalidCiphertext));
+
+            let mut tampered = ct.clone();
+            *tampered.last_mut().unwrap() ^= 1;
+            assert_eq!(
+                siv.decrypt(&[b"users.email"], &tampered),
+                Err(InvalidCiphertext)
+            );
+        }
+
+        assert_eq!(siv.decrypt(&[], &[0; 7]), Err(InvalidCiphertext));
+
+        let mut key = [1; 32];
+        key[16..].fill(2);
+        let siv_from_key = <DeterministicCipher<RC5_32_12_16> as KeyInit>::new(&key.into());
+        assert_eq!(
+            siv_from_key.encrypt(&[], b"value"),
+            siv.encrypt(&[], b"value")
+        );
+    }
+
+    #[test]
+    fn blind_index() {
+        let index = BlindIndex::<RC5_32_12_16>::new(&[3; 16].into());
+
+        assert_eq!(
+            index.tag(b"alice@example.com"),
+            index.tag(b"alice@example.com")
+        );
+        assert_ne!(
+            index.tag(b"alice@example.com"),
+            index.tag(b"bob@example.com")
+        );
+    }
+}
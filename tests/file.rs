@@ -0,0 +1,40 @@
+#![cfg(feature = "mmap")]
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use cipher::KeyInit;
+    use rc5::{encrypt_file_in_place, Keystream, KeystreamMode, RC5_32_12_16};
+
+    #[test]
+    fn encrypt_in_place() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let iv = [2; 8].into();
+        let path = std::env::temp_dir().join(format!("rc5-file-{}", std::process::id()));
+
+        let plaintext: Vec<u8> = (0..1000).map(|i| i as u8).collect();
+        fs::write(&path, &plaintext).unwrap();
+
+        for mode in [KeystreamMode::Ctr, KeystreamMode::Ofb] {
+            encrypt_file_in_place(&path, &rc5, mode, &iv).unwrap();
+
+            let mut expected = vec![0; plaintext.len()];
+            Keystream::new(&rc5, mode, &iv).fill(&mut expected);
+            expected
+                .iter_mut()
+                .zip(&plaintext)
+                .for_each(|(e, p)| *e ^= p);
+            assert_eq!(fs::read(&path).unwrap(), expected);
+
+            encrypt_file_in_place(&path, &rc5, mode, &iv).unwrap();
+            assert_eq!(fs::read(&path).unwrap(), plaintext);
+        }
+
+        fs::write(&path, []).unwrap();
+        encrypt_file_in_place(&path, &rc5, KeystreamMode::Ctr, &iv).unwrap();
+        assert!(fs::read(&path).unwrap().is_empty());
+
+        fs::remove_file(&path).unwrap();
+    }
+}
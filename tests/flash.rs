@@ -0,0 +1,70 @@
+#![cfg(feature = "embedded-storage")]
+
+#[cfg(test)]
+mod tests {
+    use cipher::KeyInit;
+    use embedded_storage::nor_flash::{ErrorType, NorFlash, NorFlashErrorKind, ReadNorFlash};
+    use rc5::{EncryptedFlash, RC5_32_12_16};
+
+    struct Ram(Vec<u8>);
+
+    impl ErrorType for Ram {
+        type Error = NorFlashErrorKind;
+    }
+
+    impl ReadNorFlash for Ram {
+        const READ_SIZE: usize = 1;
+
+        fn read(&mut self, offset: u32, bytes: &mut [u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            bytes.copy_from_slice(&self.0[offset..offset + bytes.len()]);
+            Ok(())
+        }
+
+        fn capacity(&self) -> usize {
+            self.0.len()
+        }
+    }
+
+    impl NorFlash for Ram {
+        const WRITE_SIZE: usize = 4;
+        const ERASE_SIZE: usize = 256;
+
+        fn erase(&mut self, from: u32, to: u32) -> Result<(), Self::Error> {
+            self.0[from as usize..to as usize].fill(0xFF);
+            Ok(())
+        }
+
+        fn write(&mut self, offset: u32, bytes: &[u8]) -> Result<(), Self::Error> {
+            let offset = offset as usize;
+            self.0[offset..offset + bytes.len()].copy_from_slice(bytes);
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn pages_roundtrip() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[4; 16].into());
+        let mut flash = EncryptedFlash::new(Ram(vec![0xFF; 1024]), rc5, &[0; 8].into());
+
+        let page = [0x5A; 256];
+        flash.write(512, &page).unwrap();
+        flash.write(0, &page).unwrap();
+        flash.write(260, b"abcd").unwrap();
+
+        let mut read = [0; 256];
+        flash.read(512, &mut read).unwrap();
+        assert_eq!(read, page);
+        flash.read(0, &mut read).unwrap();
+        assert_eq!(read, page);
+
+        let mut partial = [0; 3];
+        flash.read(261, &mut partial).unwrap();
+        assert_eq!(&partial, b"bcd");
+
+        // equal pages at different addresses are stored differently
+        let raw = flash.into_inner().0;
+        assert_ne!(raw[..256], page);
+        assert_ne!(raw[..256], raw[512..768]);
+    }
+}
@@ -0,0 +1,71 @@
+#[cfg(test)]
+mod tests {
+    use cipher::KeyInit;
+    use rc5::{decrypt_in_range, encrypt_in_range, RC5_32_12_16};
+
+    fn rc5() -> RC5_32_12_16 {
+        <RC5_32_12_16 as KeyInit>::new(&[5; 16].into())
+    }
+
+    #[test]
+    fn permutes_small_range() {
+        let rc5 = rc5();
+        let mut seen = vec![false; 1000];
+
+        for x in 0..1000 {
+            let y = encrypt_in_range(&rc5, x, 0..1000);
+            assert!(y < 1000);
+            assert!(!seen[y as usize]);
+            seen[y as usize] = true;
+
+            assert_eq!(decrypt_in_range(&rc5, y, 0..1000), x);
+        }
+    }
+
+    #[test]
+    fn respects_range_start() {
+        let rc5 = rc5();
+        let range = 19_000_101..21_001_231;
+
+        for x in [range.start, 20_240_229, range.end - 1] {
+            let y = encrypt_in_range(&rc5, x, range.clone());
+            assert!(range.contains(&y));
+            assert_eq!(decrypt_in_range(&rc5, y, range.clone()), x);
+        }
+    }
+
+    #[test]
+    fn ranges_with_the_same_length_differ() {
+        let rc5 = rc5();
+        let (n, k) = (1000, 5000);
+
+        let shifted = (0..n)
+            .filter(|&x| {
+                encrypt_in_range(&rc5, x, 0..n) != encrypt_in_range(&rc5, x + k, k..k + n) - k
+            })
+            .count();
+        assert!(shifted > 900);
+    }
+
+    #[test]
+    fn edge_ranges() {
+        let rc5 = rc5();
+
+        assert_eq!(encrypt_in_range(&rc5, 7, 7..8), 7);
+        for x in 0..2 {
+            assert_eq!(
+                decrypt_in_range(&rc5, encrypt_in_range(&rc5, x, 0..2), 0..2),
+                x
+            );
+        }
+
+        let y = encrypt_in_range(&rc5, u64::MAX - 1, 0..u64::MAX);
+        assert_eq!(decrypt_in_range(&rc5, y, 0..u64::MAX), u64::MAX - 1);
+    }
+
+    #[test]
+    #[should_panic]
+    fn value_outside_range() {
+        encrypt_in_range(&rc5(), 10, 0..10);
+    }
+}
@@ -0,0 +1,40 @@
+#[cfg(test)]
+mod tests {
+    use cipher::{BlockEncrypt, KeyInit};
+    use rc5::{HeaderProtection, RC5_32_12_16};
+
+    #[test]
+    fn protect_and_unprotect() {
+        let key = [3; 16].into();
+        let hp = HeaderProtection::<RC5_32_12_16>::new(&key);
+        let sample = (*b"ciphertx").into();
+
+        let mut expected = sample;
+        <RC5_32_12_16 as KeyInit>::new(&key).encrypt_block(&mut expected);
+        assert_eq!(hp.mask(&sample), expected);
+
+        let (mut flags, mut packet_number) = (0xC3u8, [0x00, 0x00, 0x01, 0x2A]);
+        hp.apply(&sample, &mut flags, 0x0F, &mut packet_number);
+        assert_eq!(flags & 0xF0, 0xC0);
+        assert_eq!(flags & 0x0F, 0x03 ^ (expected[0] & 0x0F));
+        assert_eq!(
+            packet_number[..],
+            [
+                expected[1],
+                expected[2],
+                0x01 ^ expected[3],
+                0x2A ^ expected[4]
+            ]
+        );
+
+        hp.apply(&sample, &mut flags, 0x0F, &mut packet_number);
+        assert_eq!((flags, packet_number), (0xC3, [0x00, 0x00, 0x01, 0x2A]));
+    }
+
+    #[test]
+    #[should_panic]
+    fn packet_number_too_long() {
+        let hp = HeaderProtection::<RC5_32_12_16>::new(&[3; 16].into());
+        hp.apply(&[0; 8].into(), &mut 0, 0x1F, &mut [0; 8]);
+    }
+}
@@ -0,0 +1,79 @@
+#[cfg(test)]
+mod tests {
+    use cipher::KeyInit;
+    use rc5::{decrypt_u64, decrypt_uuid, encrypt_u64, encrypt_uuid, InvalidToken, RC5_32_12_16};
+
+    #[test]
+    fn roundtrip() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[9; 16].into());
+
+        for id in [0, 1, 2, 41, u64::MAX - 1, u64::MAX] {
+            let token = encrypt_u64(&rc5, id);
+
+            assert_eq!(token.len(), 11);
+            assert!(token
+                .bytes()
+                .all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_'));
+            assert_eq!(decrypt_u64(&rc5, &token), Ok(id));
+        }
+    }
+
+    #[test]
+    fn known_token() {
+        let key = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D,
+            0x0E, 0x0F,
+        ];
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&key.into());
+
+        // block 0011223344556677 encrypts to 2DDC149BCF088B9E
+        assert_eq!(encrypt_u64(&rc5, 0x0011223344556677), "LdwUm88Ii54");
+    }
+
+    #[test]
+    fn sequential_ids_are_unlinkable() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[9; 16].into());
+
+        assert_ne!(encrypt_u64(&rc5, 1)[..4], encrypt_u64(&rc5, 2)[..4]);
+    }
+
+    #[test]
+    fn rejects_malformed_tokens() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[9; 16].into());
+
+        for token in [
+            "",
+            "LdwUm88Ii5",
+            "LdwUm88Ii54A",
+            "LdwUm88Ii5+",
+            "LdwUm88Ii55",
+        ] {
+            assert_eq!(decrypt_u64(&rc5, token), Err(InvalidToken), "{token}");
+        }
+    }
+
+    #[test]
+    fn uuid_roundtrip() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[9; 16].into());
+        // 67e55044-10b1-426f-9247-bb680e5fe0c8
+        let uuid = 0x67e5504410b1426f9247bb680e5fe0c8u128.to_be_bytes();
+
+        let encrypted = encrypt_uuid(&rc5, uuid);
+
+        assert_ne!(encrypted, uuid);
+        assert_eq!(decrypt_uuid(&rc5, encrypted), uuid);
+    }
+
+    #[test]
+    fn uuid_diffuses_into_both_halves() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[9; 16].into());
+        let mut uuid = [0; 16];
+
+        let a = encrypt_uuid(&rc5, uuid);
+        uuid[15] = 1;
+        let b = encrypt_uuid(&rc5, uuid);
+
+        assert_ne!(a[..8], b[..8]);
+        assert_ne!(a[8..], b[8..]);
+    }
+}
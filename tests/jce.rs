@@ -0,0 +1,75 @@
+#[cfg(test)]
+mod tests {
+    use cipher::{BlockEncrypt, KeyInit};
+    use rc5::{ParameterSpecError, Rc5ParameterSpec, RC5_32_12_16};
+
+    fn spec() -> Rc5ParameterSpec {
+        Rc5ParameterSpec {
+            version: 0x10,
+            rounds: 12,
+            word_size: 32,
+            iv: Some(vec![1, 2, 3, 4, 5, 6, 7, 8]),
+        }
+    }
+
+    #[test]
+    fn from_parameter_spec() {
+        let (rc5, iv) = RC5_32_12_16::from_parameter_spec(&[4; 16], &spec()).unwrap();
+        assert_eq!(iv, Some([1, 2, 3, 4, 5, 6, 7, 8].into()));
+
+        let mut block = [0; 8].into();
+        let mut expected = block;
+        rc5.encrypt_block(&mut block);
+        <RC5_32_12_16 as KeyInit>::new(&[4; 16].into()).encrypt_block(&mut expected);
+        assert_eq!(block, expected);
+
+        let without_iv = Rc5ParameterSpec { iv: None, ..spec() };
+        let (_, iv) = RC5_32_12_16::from_parameter_spec(&[4; 16], &without_iv).unwrap();
+        assert_eq!(iv, None);
+    }
+
+    #[test]
+    fn mismatched_parameters() {
+        let cases = [
+            (
+                Rc5ParameterSpec {
+                    version: 0x11,
+                    ..spec()
+                },
+                ParameterSpecError::UnsupportedVersion,
+            ),
+            (
+                Rc5ParameterSpec {
+                    word_size: 64,
+                    ..spec()
+                },
+                ParameterSpecError::WordSizeMismatch,
+            ),
+            (
+                Rc5ParameterSpec {
+                    rounds: 16,
+                    ..spec()
+                },
+                ParameterSpecError::RoundsMismatch,
+            ),
+            (
+                Rc5ParameterSpec {
+                    iv: Some(vec![0; 16]),
+                    ..spec()
+                },
+                ParameterSpecError::InvalidIvLength,
+            ),
+        ];
+
+        for (spec, error) in cases {
+            assert_eq!(
+                RC5_32_12_16::from_parameter_spec(&[4; 16], &spec).err(),
+                Some(error)
+            );
+        }
+        assert_eq!(
+            RC5_32_12_16::from_parameter_spec(&[4; 10], &spec()).err(),
+            Some(ParameterSpecError::InvalidKeyLength)
+        );
+    }
+}
@@ -0,0 +1,154 @@
+// Every variant runs the same structural suite, add a line to `variants!` with each new one.
+
+#[cfg(test)]
+mod tests {
+    use cipher::{
+        consts::{U0, U10, U12, U16, U20, U255, U4},
+        typenum::Unsigned,
+        AlgorithmName, BlockDecrypt, BlockEncrypt, BlockSizeUser, KeyInit, KeySizeUser,
+    };
+    use rc5::{Rc5, Rc5Const, RC5_16_16_8, RC5_32_12_16, RC5_32_12_9, RC5_64_24_24};
+
+    /// Stack available to key setup and block operations, in debug builds. Release builds need
+    /// much less, see the README.
+    const STACK_LIMIT: usize = 64 << 10;
+
+    /// `variant => word bits, rounds, key bytes[, key, plaintext, ciphertext]`
+    macro_rules! variants {
+        ($($name:ident: $variant:ty => $w:literal, $r:literal, $b:literal $(, $key:expr, $pt:expr, $ct:expr)?;)*) => {
+            $(
+                mod $name {
+                    use super::*;
+
+                    #[test]
+                    fn sizes() {
+                        assert_eq!(<$variant as BlockSizeUser>::BlockSize::USIZE, 2 * $w / 8);
+                        assert_eq!(<$variant as KeySizeUser>::KeySize::USIZE, $b);
+                    }
+
+                    #[test]
+                    fn roundtrip() {
+                        let key = (0..$b).map(|i| (i * 37) as u8).collect::<Vec<_>>();
+                        let cipher = <$variant as KeyInit>::new_from_slice(&key).unwrap();
+
+                        let pt = (0..2 * $w / 8).map(|i| i as u8).collect::<Vec<_>>();
+                        let mut block = cipher::Block::<$variant>::clone_from_slice(&pt);
+                        cipher.encrypt_block(&mut block);
+                        assert_ne!(block[..], pt[..]);
+
+                        cipher.decrypt_block(&mut block);
+                        assert_eq!(block[..], pt[..]);
+                    }
+
+                    #[test]
+                    fn bounded_stack() {
+                        // an overflow aborts the whole test binary
+                        std::thread::Builder::new()
+                            .stack_size(STACK_LIMIT)
+                            .spawn(|| {
+                                let key = [0x5A; $b];
+                                let cipher = <$variant as KeyInit>::new_from_slice(&key).unwrap();
+
+                                let mut block = cipher::Block::<$variant>::default();
+                                cipher.encrypt_block(&mut block);
+                                cipher.decrypt_block(&mut block);
+                            })
+                            .unwrap()
+                            .join()
+                            .unwrap();
+                    }
+
+                    #[test]
+                    fn name() {
+                        struct Name;
+                        impl std::fmt::Display for Name {
+                            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                                <$variant as AlgorithmName>::write_alg_name(f)
+                            }
+                        }
+
+                        assert_eq!(Name.to_string(), format!("RC5-{}/{}/{}", $w, $r, $b));
+                    }
+
+                    $(
+                        #[test]
+                        fn vector() {
+                            let cipher = <$variant as KeyInit>::new_from_slice(&$key).unwrap();
+
+                            let mut block = cipher::Block::<$variant>::clone_from_slice(&$pt);
+                            cipher.encrypt_block(&mut block);
+                            assert_eq!(block[..], $ct[..]);
+
+                            cipher.decrypt_block(&mut block);
+                            assert_eq!(block[..], $pt[..]);
+                        }
+                    )?
+                }
+            )*
+        };
+    }
+
+    variants! {
+        rc5_32_12_16: RC5_32_12_16 => 32, 12, 16,
+            [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F],
+            [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77],
+            [0x2D, 0xDC, 0x14, 0x9B, 0xCF, 0x08, 0x8B, 0x9E];
+        rc5_32_20_16: Rc5<u32, U20, U16> => 32, 20, 16,
+            [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F],
+            [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07],
+            [0x2A, 0x0E, 0xDC, 0x0E, 0x94, 0x31, 0xFF, 0x73];
+        rc5_32_20_16_const: Rc5Const<u32, 20, 16> => 32, 20, 16,
+            [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F],
+            [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07],
+            [0x2A, 0x0E, 0xDC, 0x0E, 0x94, 0x31, 0xFF, 0x73];
+        rc5_8_12_4: Rc5<u8, U12, U4> => 8, 12, 4,
+            [0x00, 0x01, 0x02, 0x03],
+            [0x00, 0x01],
+            [0x21, 0x2A];
+        rc5_16_16_8: RC5_16_16_8 => 16, 16, 8,
+            [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07],
+            [0x00, 0x01, 0x02, 0x03],
+            [0x23, 0xA8, 0xD7, 0x2E];
+        rc5_64_24_24: RC5_64_24_24 => 64, 24, 24,
+            [
+                0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F,
+                0x10, 0x11, 0x12, 0x13, 0x14, 0x15, 0x16, 0x17,
+            ],
+            [0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E, 0x0F],
+            [0xA4, 0x67, 0x72, 0x82, 0x0E, 0xDB, 0xCE, 0x02, 0x35, 0xAB, 0xEA, 0x32, 0xAE, 0x71, 0x78, 0xDA];
+        rc5_32_12_9: RC5_32_12_9 => 32, 12, 9;
+        rc5_32_16_10: Rc5<u32, U16, U10> => 32, 16, 10;
+        rc5_32_12_0: Rc5<u32, U12, U0> => 32, 12, 0;
+        rc5_8_16_4: Rc5<u8, U16, U4> => 8, 16, 4;
+        // the largest parameters the paper allows, worst case for stack usage
+        rc5_32_255_255: Rc5<u32, U255, U255> => 32, 255, 255;
+    }
+
+    /// The paper gives an empty key one zero word of `L`, the same as a key of zero bytes up to
+    /// the word size.
+    #[test]
+    fn empty_key() {
+        let expected = {
+            let mut block = [0x11; 8].into();
+            Rc5::<u32, U12, U4>::new(&[0; 4].into()).encrypt_block(&mut block);
+            block
+        };
+
+        let empty = Default::default();
+        let [_, batch] = Rc5::<u32, U12, U0>::new_batch(&[empty, empty]);
+        let ciphers = [
+            Rc5::<u32, U12, U0>::new(&empty),
+            Rc5::<u32, U12, U0>::new_in_place(&mut Default::default()),
+            batch,
+            Rc5Const::<u32, 12, 0>::new(&empty),
+        ];
+        for cipher in ciphers {
+            let mut block = [0x11; 8].into();
+            cipher.encrypt_block(&mut block);
+            assert_eq!(block, expected);
+        }
+
+        let parameters: rc5::Rc5Parameters = "RC5-32/12/0".parse().unwrap();
+        assert!(Rc5::<u32, U12, U0>::from_parameters(&[], &parameters).is_ok());
+    }
+}
@@ -0,0 +1,145 @@
+#[cfg(test)]
+mod tests {
+    use cipher::{consts::U16, BlockEncrypt, InvalidLength};
+    use rc5::{KeyWarning, ParseKeyError, Rc5Key, RC5_32_12_16};
+
+    const KEY: [u8; 16] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E,
+        0x0F,
+    ];
+
+    #[test]
+    fn conversions_agree() {
+        let from_array = Rc5Key::<U16>::from(KEY);
+        let from_slice = Rc5Key::<U16>::try_from(&KEY[..]).unwrap();
+        let from_hex: Rc5Key<U16> = "000102030405060708090a0B0c0D0e0F".parse().unwrap();
+
+        assert_eq!(from_array.as_bytes(), from_slice.as_bytes());
+        assert_eq!(from_array.as_bytes(), from_hex.as_bytes());
+        assert_eq!(from_array.as_ref(), &KEY[..]);
+    }
+
+    #[test]
+    fn invalid_keys() {
+        assert_eq!(
+            Rc5Key::<U16>::try_from(&KEY[..15]).unwrap_err(),
+            InvalidLength
+        );
+        assert_eq!(
+            "0001".parse::<Rc5Key<U16>>().unwrap_err(),
+            ParseKeyError::InvalidLength
+        );
+        assert_eq!(
+            "000102030405060708090a0b0c0d0e0g"
+                .parse::<Rc5Key<U16>>()
+                .unwrap_err(),
+            ParseKeyError::InvalidDigit
+        );
+    }
+
+    #[test]
+    fn debug_hides_key() {
+        assert_eq!(format!("{:?}", Rc5Key::<U16>::from(KEY)), "Rc5Key<16>(..)");
+    }
+
+    #[test]
+    fn cipher_constructors() {
+        let mut from_key = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77].into();
+        let mut from_hex = from_key;
+        let ct = [0x2D, 0xDC, 0x14, 0x9B, 0xCF, 0x08, 0x8B, 0x9E];
+
+        RC5_32_12_16::from_key(&KEY.into()).encrypt_block(&mut from_key);
+        RC5_32_12_16::from_hex("000102030405060708090A0B0C0D0E0F")
+            .unwrap()
+            .encrypt_block(&mut from_hex);
+
+        assert_eq!(from_key[..], ct[..]);
+        assert_eq!(from_hex[..], ct[..]);
+        assert!(RC5_32_12_16::from_hex("00").is_err());
+    }
+
+    #[test]
+    fn audit() {
+        assert_eq!(Rc5Key::<U16>::from([0; 16]).audit(), [KeyWarning::AllZero]);
+        assert_eq!(
+            Rc5Key::<U16>::from([0xAA; 16]).audit(),
+            [KeyWarning::LowEntropy]
+        );
+        assert_eq!(
+            Rc5Key::<U16>::from(*b"hunter2hunter2!!").audit(),
+            [KeyWarning::AsciiPassword]
+        );
+        assert_eq!(
+            Rc5Key::<U16>::from(*b"aaaaaaaabbbbbbbb").audit(),
+            [KeyWarning::LowEntropy, KeyWarning::AsciiPassword]
+        );
+        assert!(Rc5Key::<U16>::from([
+            0x2B, 0xD6, 0x45, 0x9F, 0x82, 0xC5, 0xB3, 0x00, 0x95, 0x2C, 0x49, 0x10, 0x48, 0x81,
+            0xFF, 0x48,
+        ])
+        .audit()
+        .is_empty());
+    }
+
+    #[test]
+    fn constant_time_eq() {
+        use subtle::ConstantTimeEq;
+
+        let key = Rc5Key::<U16>::from(KEY);
+        let mut other = KEY;
+        other[15] ^= 1;
+
+        assert_eq!(key, Rc5Key::from(KEY));
+        assert_ne!(key, Rc5Key::from(other));
+        assert!(bool::from(key.ct_eq(&Rc5Key::from(KEY))));
+
+        assert!(RC5_32_12_16::from_key(&key) == RC5_32_12_16::from_key(&Rc5Key::from(KEY)));
+        assert!(RC5_32_12_16::from_key(&key) != RC5_32_12_16::from_key(&Rc5Key::from(other)));
+    }
+
+    #[test]
+    fn new_in_place() {
+        use cipher::{
+            consts::{U10, U12, U20, U4},
+            KeyInit,
+        };
+        use rc5::Rc5;
+
+        let mut key = KEY.into();
+        assert!(RC5_32_12_16::new_in_place(&mut key) == RC5_32_12_16::new(&KEY.into()));
+        assert_eq!(key[..], [0; 16]);
+
+        // not a multiple of the word size, expanded from a padded copy
+        let mut key = (*b"0123456789").into();
+        assert!(Rc5::<u32, U20, U10>::new_in_place(&mut key) == Rc5::new(b"0123456789".into()));
+        assert_eq!(key[..], [0; 10]);
+
+        let mut key = [1, 2, 3, 4].into();
+        assert!(Rc5::<u8, U12, U4>::new_in_place(&mut key) == Rc5::new(&[1, 2, 3, 4].into()));
+        assert_eq!(key[..], [0; 4]);
+    }
+
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn generate_and_split() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let key = Rc5Key::<U16>::from(KEY);
+        let mut rng = StdRng::seed_from_u64(3);
+
+        let generated = Rc5Key::<U16>::generate(&mut StdRng::seed_from_u64(3));
+        assert_eq!(generated, Rc5Key::generate(&mut StdRng::seed_from_u64(3)));
+        assert!(generated.audit().is_empty());
+
+        for n in 1..5 {
+            let shares = key.split(n, &mut rng);
+            assert_eq!(shares.len(), n);
+            assert_eq!(Rc5Key::combine(&shares), key);
+
+            if n > 1 {
+                assert!(shares.iter().all(|share| *share != key));
+                assert_ne!(Rc5Key::combine(&shares[1..]), key);
+            }
+        }
+    }
+}
@@ -0,0 +1,74 @@
+#![cfg(feature = "core")]
+
+// Golden expanded key tables, any change to the key schedule shows up here first.
+
+#[cfg(test)]
+mod tests {
+    use cipher::consts::{U12, U16, U20, U4};
+    use rc5::{core::RC5, Rc5};
+
+    const KEY_0_15: [u8; 16] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E,
+        0x0F,
+    ];
+
+    #[test]
+    fn rc5_32_12_16_zero_key() {
+        let table = Rc5::<u32, U12, U16>::substitute_key(&[0; 16].into());
+
+        assert_eq!(
+            table[..],
+            [
+                0x9BBBD8C8, 0x1A37F7FB, 0x46F8E8C5, 0x460C6085, 0x70F83B8A, 0x284B8303, 0x513E1454,
+                0xF621ED22, 0x3125065D, 0x11A83A5D, 0xD427686B, 0x713AD82D, 0x4B792F99, 0x2799A4DD,
+                0xA7901C49, 0xDEDE871A, 0x36C03196, 0xA7EFC249, 0x61A78BB8, 0x3B0A1D2B, 0x4DBFCA76,
+                0xAE162167, 0x30D76B0A, 0x43192304, 0xF6CC1431, 0x65046380,
+            ]
+        );
+    }
+
+    #[test]
+    fn rc5_32_12_16() {
+        let table = Rc5::<u32, U12, U16>::substitute_key(&KEY_0_15.into());
+
+        assert_eq!(
+            table[..],
+            [
+                0xD447E233, 0xD82EEC20, 0x84FCE219, 0xB93353DE, 0x5AC2588F, 0x48E922F1, 0x879F5460,
+                0x1A693A4B, 0x5171B55F, 0x9A206E4D, 0x9966C4A0, 0xB166B0D7, 0x89CC6827, 0xCBE1B9B7,
+                0x1BB7F44F, 0x638829B4, 0x4DA0AB3A, 0x74E54561, 0x5EB4DEE9, 0xBEF10188, 0x728A511F,
+                0x37A8DEBC, 0x5735676A, 0xF96B764A, 0x7AEC5407, 0x15E8E206,
+            ]
+        );
+    }
+
+    #[test]
+    fn rc5_32_20_16() {
+        let table = Rc5::<u32, U20, U16>::substitute_key(&KEY_0_15.into());
+
+        assert_eq!(
+            table[..],
+            [
+                0x98C9276E, 0x30979FA4, 0x966BE368, 0xD7057A17, 0xDBE7412A, 0xF7B6D1BB, 0x85559148,
+                0xE81E0C32, 0x995619D3, 0xFC7C2368, 0x541C7DCC, 0x77487E5A, 0x8CD224F4, 0x2A327569,
+                0x4B00CB44, 0x5E1E4E21, 0xE498092D, 0x10693A90, 0xF394A693, 0xFCF1C822, 0x3E1BE3B3,
+                0x99688946, 0x3963729A, 0x34F2E81A, 0xA6BB6B92, 0x5D077337, 0x8781BDD2, 0xFDB392CB,
+                0xCE2FF0CF, 0x2E6D4614, 0x6F741DF9, 0xD0D1EA24, 0x4C148425, 0x5C878F29, 0x7FEE9768,
+                0xAECECF08, 0x0B04ED43, 0x4BBE9406, 0x8CB79705, 0x02CE935B, 0x2272F8D9, 0x4765B2F6,
+            ]
+        );
+    }
+
+    #[test]
+    fn rc5_8_12_4() {
+        let table = Rc5::<u8, U12, U4>::substitute_key(&[0x00, 0x01, 0x02, 0x03].into());
+
+        assert_eq!(
+            table[..],
+            [
+                0xF6, 0x15, 0xB4, 0x42, 0xFE, 0xA3, 0xBD, 0x6D, 0x0D, 0x09, 0x17, 0x5C, 0x13, 0x22,
+                0x2C, 0xD6, 0xC4, 0x61, 0x90, 0xDC, 0x09, 0xF9, 0x21, 0x5C, 0x75, 0xB5,
+            ]
+        );
+    }
+}
@@ -0,0 +1,68 @@
+#[cfg(test)]
+mod tests {
+    use std::io;
+
+    use cipher::consts::U16;
+    use rc5::{
+        keyfile::{self, KeyFormat},
+        Rc5Key,
+    };
+
+    const KEY: [u8; 16] = [
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D, 0x0E,
+        0x0F,
+    ];
+
+    #[test]
+    fn formats() {
+        let files: [(&[u8], KeyFormat); 4] = [
+            (&KEY, KeyFormat::Raw),
+            (b"000102030405060708090a0b0c0d0e0f\n", KeyFormat::Hex),
+            (b"AAECAwQFBgcICQoLDA0ODw==\n", KeyFormat::Base64),
+            (
+                b"-----BEGIN RC5 KEY-----\nAAECAwQFBgcI\nCQoLDA0ODw==\n-----END RC5 KEY-----\n",
+                KeyFormat::Armored,
+            ),
+        ];
+
+        for (contents, format) in files {
+            let key: Rc5Key<U16> = keyfile::parse(contents, format).unwrap();
+            assert_eq!(key.as_bytes()[..], KEY, "{format:?}");
+        }
+    }
+
+    #[test]
+    fn invalid_files() {
+        let files: [(&[u8], KeyFormat); 5] = [
+            (&KEY[1..], KeyFormat::Raw),
+            (b"000102030405060708090a0b0c0d0e0g", KeyFormat::Hex),
+            (b"AAECAwQFBgcICQoLDA0ODw=", KeyFormat::Base64),
+            (b"AAECAwQFBgcICQoLDA0O", KeyFormat::Base64),
+            (b"AAECAwQFBgcICQoLDA0ODw==", KeyFormat::Armored),
+        ];
+
+        for (contents, format) in files {
+            let err = keyfile::parse::<U16>(contents, format).unwrap_err();
+            assert_eq!(err.kind(), io::ErrorKind::InvalidData, "{format:?}");
+        }
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn permissions() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let path = std::env::temp_dir().join(format!("rc5-keyfile-{}", std::process::id()));
+        std::fs::write(&path, KEY).unwrap();
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        let key = keyfile::load::<U16>(&path, KeyFormat::Raw).unwrap();
+        assert_eq!(key.as_bytes()[..], KEY);
+
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        let err = keyfile::load::<U16>(&path, KeyFormat::Raw).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+
+        std::fs::remove_file(&path).unwrap();
+    }
+}
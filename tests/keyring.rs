@@ -0,0 +1,42 @@
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use cipher::{BlockEncrypt, KeyInit};
+    use rc5::{KeyRing, RC5_32_12_16};
+
+    fn encrypt(cipher: &RC5_32_12_16) -> [u8; 8] {
+        let mut block = [1, 2, 3, 4, 5, 6, 7, 8].into();
+        cipher.encrypt_block(&mut block);
+        block.into()
+    }
+
+    #[test]
+    fn rotation() {
+        let epoch = SystemTime::UNIX_EPOCH;
+        let day = Duration::from_secs(24 * 60 * 60);
+
+        let mut ring = KeyRing::<RC5_32_12_16>::new();
+        ring.insert(1, &[1; 16].into(), epoch, Some(epoch + 10 * day));
+        ring.insert(2, &[2; 16].into(), epoch + 5 * day, None);
+        ring.insert(3, &[3; 16].into(), epoch + 20 * day, None);
+
+        assert!(ring.current_at(epoch - day).is_none());
+        assert_eq!(ring.current_at(epoch).unwrap().0, 1);
+        assert_eq!(ring.current_at(epoch + 5 * day).unwrap().0, 2);
+        assert_eq!(ring.current_at(epoch + 15 * day).unwrap().0, 2);
+        assert_eq!(ring.current_at(epoch + 20 * day).unwrap().0, 3);
+
+        let (id, cipher) = ring.current_at(epoch + 20 * day).unwrap();
+        assert_eq!(
+            encrypt(cipher),
+            encrypt(&RC5_32_12_16::new(&[id as u8; 16].into()))
+        );
+
+        // expired keys still decrypt
+        assert!(ring.get(1).is_some());
+        assert!(ring.remove(1));
+        assert!(ring.get(1).is_none());
+        assert!(!ring.remove(1));
+    }
+}
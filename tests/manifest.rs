@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use std::{fs, io, path::PathBuf};
+
+    use cipher::KeyInit;
+    use rc5::{ManifestSigner, Mismatch, RC5_32_12_16};
+
+    #[test]
+    fn detects_changes() {
+        let root = std::env::temp_dir().join(format!("rc5-manifest-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&root);
+        fs::create_dir_all(root.join("data/nested")).unwrap();
+        fs::write(root.join("a.txt"), b"first").unwrap();
+        fs::write(root.join("data/b.bin"), vec![7; 100_000]).unwrap();
+        fs::write(root.join("data/nested/c"), b"").unwrap();
+
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[9; 16].into());
+        let signer = ManifestSigner::new(&rc5).threads(2);
+
+        let manifest = signer.create(&root).unwrap();
+        assert_eq!(manifest.lines().count(), 4);
+        assert!(manifest.contains(" data/nested/c\n"));
+        assert_eq!(signer.verify(&root, &manifest).unwrap(), []);
+
+        fs::write(root.join("a.txt"), b"First").unwrap();
+        fs::remove_file(root.join("data/nested/c")).unwrap();
+        fs::write(root.join("d"), b"new").unwrap();
+        assert_eq!(
+            signer.verify(&root, &manifest).unwrap(),
+            [
+                Mismatch::Modified(PathBuf::from("a.txt")),
+                Mismatch::Missing(PathBuf::from("data/nested/c")),
+                Mismatch::Added(PathBuf::from("d")),
+            ]
+        );
+
+        // an edited manifest no longer verifies
+        let forged = manifest.replacen(" data/b.bin", " data/c.bin", 1);
+        let err = signer.verify(&root, &forged).unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        let other = <RC5_32_12_16 as KeyInit>::new(&[8; 16].into());
+        let err = ManifestSigner::new(&other)
+            .verify(&root, &manifest)
+            .unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
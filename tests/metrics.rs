@@ -0,0 +1,92 @@
+#![cfg(feature = "metrics")]
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashMap,
+        sync::{atomic::AtomicU64, atomic::Ordering, Arc, Mutex},
+    };
+
+    use cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+    use metrics::{
+        Counter, Gauge, Histogram, Key, KeyName, Metadata, Recorder, SharedString, Unit,
+    };
+    use rc5::{DeterministicCipher, RC5_32_12_16};
+
+    /// Counters keyed by name and labels, like `rc5_blocks_total{op=encrypt}`.
+    #[derive(Default)]
+    struct Counters(Mutex<HashMap<String, Arc<AtomicU64>>>);
+
+    impl Counters {
+        fn get(&self, key: &str) -> u64 {
+            self.0
+                .lock()
+                .unwrap()
+                .get(key)
+                .map_or(0, |counter| counter.load(Ordering::Relaxed))
+        }
+    }
+
+    impl Recorder for Counters {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            let labels: Vec<String> = key
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            let name = match labels.is_empty() {
+                true => key.name().to_owned(),
+                false => format!("{}{{{}}}", key.name(), labels.join(",")),
+            };
+            Counter::from_arc(self.0.lock().unwrap().entry(name).or_default().clone())
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, _: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::noop()
+        }
+    }
+
+    #[test]
+    fn blocks_and_key_setups() {
+        let counters = Counters::default();
+
+        metrics::with_local_recorder(&counters, || {
+            let rc5 = RC5_32_12_16::new(&[0; 16].into());
+            let _ = RC5_32_12_16::new_batch(&[[1; 16].into(), [2; 16].into()]);
+
+            let mut blocks = [[0; 8].into(); 5];
+            rc5.encrypt_blocks(&mut blocks);
+            rc5.decrypt_blocks(&mut blocks[..2]);
+            rc5.encrypt_block(&mut blocks[0]);
+        });
+
+        assert_eq!(counters.get("rc5_key_setups_total"), 3);
+        assert_eq!(counters.get("rc5_blocks_total{op=encrypt}"), 6);
+        assert_eq!(counters.get("rc5_bytes_total{op=encrypt}"), 48);
+        assert_eq!(counters.get("rc5_blocks_total{op=decrypt}"), 2);
+        assert_eq!(counters.get("rc5_bytes_total{op=decrypt}"), 16);
+    }
+
+    #[test]
+    fn tag_failures() {
+        let counters = Counters::default();
+
+        metrics::with_local_recorder(&counters, || {
+            let siv = DeterministicCipher::<RC5_32_12_16>::new(&[1; 16].into(), &[2; 16].into());
+            let mut ciphertext = siv.encrypt(&[], b"plaintext");
+            assert!(siv.decrypt(&[], &ciphertext).is_ok());
+
+            ciphertext[0] ^= 1;
+            assert!(siv.decrypt(&[], &ciphertext).is_err());
+        });
+
+        assert_eq!(counters.get("rc5_tag_failures_total{scheme=siv}"), 1);
+    }
+}
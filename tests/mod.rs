@@ -62,4 +62,76 @@ mod tests {
 
         assert!(ct[..] == pt[..]);
     }
+
+    #[test]
+    fn batch_key_setup() {
+        let keys = [
+            [
+                0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D,
+                0x0E, 0x0F,
+            ]
+            .into(),
+            [
+                0x2B, 0xD6, 0x45, 0x9F, 0x82, 0xC5, 0xB3, 0x00, 0x95, 0x2C, 0x49, 0x10, 0x48, 0x81,
+                0xFF, 0x48,
+            ]
+            .into(),
+            [0; 16].into(),
+        ];
+
+        for (rc5, key) in RC5_32_12_16::new_batch(&keys).iter().zip(&keys) {
+            let single = <RC5_32_12_16 as KeyInit>::new(key);
+            let mut block = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77].into();
+            let mut expected = block;
+
+            rc5.encrypt_block(&mut block);
+            single.encrypt_block(&mut expected);
+            assert_eq!(block, expected);
+        }
+    }
+
+    #[test]
+    fn plain_array_blocks() {
+        let key = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D,
+            0x0E, 0x0F,
+        ];
+        let mut block = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77];
+
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&key.into());
+        rc5.encrypt_block_array(&mut block);
+        assert_eq!(block, [0x2D, 0xDC, 0x14, 0x9B, 0xCF, 0x08, 0x8B, 0x9E]);
+
+        rc5.decrypt_block_array(&mut block);
+        assert_eq!(block, [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77]);
+    }
+
+    #[test]
+    fn self_test() {
+        assert_eq!(rc5::self_test(), Ok(()));
+    }
+
+    #[test]
+    fn public_backends() {
+        use cipher::{Block, BlockBackend};
+
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[7; 16].into());
+        let plaintext: Vec<Block<RC5_32_12_16>> = (0..11u8).map(|i| [i; 8].into()).collect();
+
+        let mut blocks = plaintext.clone();
+        let mut backend = rc5.encrypt_backend();
+        for block in blocks.iter_mut() {
+            backend.proc_block(block.into());
+        }
+
+        let mut expected = plaintext.clone();
+        rc5.encrypt_blocks(&mut expected);
+        assert_eq!(blocks, expected);
+
+        let mut backend = rc5.decrypt_backend();
+        for block in blocks.iter_mut() {
+            backend.proc_block(block.into());
+        }
+        assert_eq!(blocks, plaintext);
+    }
 }
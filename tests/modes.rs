@@ -0,0 +1,60 @@
+// Differential tests of the keystream modes against the generic RustCrypto mode crates, see also
+// the fuzz targets in `fuzz/`.
+
+#[cfg(test)]
+mod tests {
+    use cipher::{KeyInit, KeyIvInit, StreamCipher};
+    use rand::{Rng, SeedableRng};
+    use rc5::{CtrPipeline, Keystream, KeystreamMode, RC5_32_12_16};
+
+    fn apply(keystream: &mut Keystream<'_, RC5_32_12_16>, data: &mut [u8]) {
+        let mut buf = vec![0; data.len()];
+        keystream.fill(&mut buf);
+        data.iter_mut().zip(&buf).for_each(|(d, k)| *d ^= k);
+    }
+
+    #[test]
+    fn matches_mode_crates() {
+        let mut rng = rand::rngs::StdRng::seed_from_u64(0x5eed);
+
+        for _ in 0..200 {
+            let key: [u8; 16] = rng.gen();
+            let mut iv: [u8; 8] = rng.gen();
+            if rng.gen_bool(0.2) {
+                // exercise the counter carry and wrap around
+                iv[4..].fill(0xFF);
+            }
+            let data: Vec<u8> = (0..rng.gen_range(0..300)).map(|_| rng.gen()).collect();
+            let rc5 = <RC5_32_12_16 as KeyInit>::new(&key.into());
+
+            let mut expected = data.clone();
+            ctr::Ctr64BE::<RC5_32_12_16>::new(&key.into(), &iv.into())
+                .apply_keystream(&mut expected);
+
+            let mut actual = data.clone();
+            apply(
+                &mut Keystream::new(&rc5, KeystreamMode::Ctr, &iv.into()),
+                &mut actual,
+            );
+            assert_eq!(actual, expected);
+
+            let mut piped = Vec::new();
+            CtrPipeline::new(&rc5, &iv.into())
+                .threads(3)
+                .chunk_size(rng.gen_range(1..64))
+                .run(&data[..], &mut piped)
+                .unwrap();
+            assert_eq!(piped, expected);
+
+            let mut expected = data.clone();
+            ofb::Ofb::<RC5_32_12_16>::new(&key.into(), &iv.into()).apply_keystream(&mut expected);
+
+            let mut actual = data;
+            apply(
+                &mut Keystream::new(&rc5, KeystreamMode::Ofb, &iv.into()),
+                &mut actual,
+            );
+            assert_eq!(actual, expected);
+        }
+    }
+}
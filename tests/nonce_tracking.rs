@@ -0,0 +1,148 @@
+#![cfg(feature = "nonce-tracking")]
+
+#[cfg(test)]
+mod tests {
+    use std::io::Read;
+
+    use cipher::KeyInit;
+    use rc5::{
+        CtrPipeline, EncryptReader, Keystream, KeystreamMode, ParallelCipher, StreamEncryptor,
+        XCtr, RC5_32_12_16,
+    };
+
+    fn rc5() -> RC5_32_12_16 {
+        RC5_32_12_16::new(&[1; 16].into())
+    }
+
+    #[test]
+    fn repeats_of_a_message() {
+        let rc5 = rc5();
+        let iv = [2; 8].into();
+        let plaintext = b"the same message".to_vec();
+
+        let mut ciphertext = plaintext.clone();
+        Keystream::new(&rc5, KeystreamMode::Ctr, &iv).apply(&mut ciphertext);
+
+        let mut again = plaintext.clone();
+        Keystream::new(&rc5, KeystreamMode::Ctr, &iv).apply(&mut again);
+        assert_eq!(again, ciphertext);
+
+        Keystream::new(&rc5, KeystreamMode::Ctr, &iv).apply(&mut ciphertext);
+        assert_eq!(ciphertext, plaintext);
+
+        // other IVs, modes and keys are independent
+        Keystream::new(&rc5, KeystreamMode::Ctr, &[3; 8].into()).apply(&mut b"other".to_vec());
+        Keystream::new(&rc5, KeystreamMode::Ofb, &iv).apply(&mut b"other".to_vec());
+        let other_key = RC5_32_12_16::new(&[4; 16].into());
+        Keystream::new(&other_key, KeystreamMode::Ctr, &iv).apply(&mut b"other".to_vec());
+
+        // seeking shares the IV between ranges of a message and isn't tracked
+        Keystream::ctr_at(&rc5, &iv, 1).apply(&mut b"other".to_vec());
+    }
+
+    #[test]
+    #[should_panic(expected = "CTR: nonce reused under the same key")]
+    fn keystream_reuse() {
+        let rc5 = rc5();
+        let iv = [2; 8].into();
+
+        Keystream::new(&rc5, KeystreamMode::Ctr, &iv).apply(&mut b"first message".to_vec());
+        Keystream::new(&rc5, KeystreamMode::Ctr, &iv).apply(&mut b"second message".to_vec());
+    }
+
+    #[test]
+    fn repeats_through_adapters() {
+        let rc5 = rc5();
+        let iv = [2; 8].into();
+        let plaintext: Vec<u8> = (0..200).collect();
+
+        let mut ciphertext = Vec::new();
+        let keystream = Keystream::new(&rc5, KeystreamMode::Ctr, &iv);
+        StreamEncryptor::new(keystream)
+            .run(&plaintext[..], &mut ciphertext)
+            .unwrap();
+
+        let mut decrypted = Vec::new();
+        let keystream = Keystream::new(&rc5, KeystreamMode::Ctr, &iv);
+        EncryptReader::new(keystream, &ciphertext[..])
+            .read_to_end(&mut decrypted)
+            .unwrap();
+        assert_eq!(decrypted, plaintext);
+
+        let mut again = plaintext.clone();
+        ParallelCipher::new(&rc5)
+            .threads(3)
+            .apply_ctr(&iv, &mut again);
+        assert_eq!(again, ciphertext);
+
+        let mut decrypted = Vec::new();
+        CtrPipeline::new(&rc5, &iv)
+            .threads(2)
+            .chunk_size(16)
+            .run(&ciphertext[..], &mut decrypted)
+            .unwrap();
+        assert_eq!(decrypted, plaintext);
+    }
+
+    #[test]
+    #[should_panic(expected = "CTR: nonce reused under the same key")]
+    fn stream_reuse() {
+        let rc5 = rc5();
+        let iv = [2; 8].into();
+
+        let keystream = Keystream::new(&rc5, KeystreamMode::Ctr, &iv);
+        StreamEncryptor::new(keystream)
+            .run(&b"first message"[..], Vec::new())
+            .unwrap();
+        let mut reader = EncryptReader::new(
+            Keystream::new(&rc5, KeystreamMode::Ctr, &iv),
+            &b"second message"[..],
+        );
+        reader.read_to_end(&mut Vec::new()).unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "CTR: nonce reused under the same key")]
+    fn parallel_reuse() {
+        let rc5 = rc5();
+        let iv = [2; 8].into();
+
+        ParallelCipher::new(&rc5)
+            .threads(2)
+            .apply_ctr(&iv, &mut b"first message".to_vec());
+        CtrPipeline::new(&rc5, &iv)
+            .threads(2)
+            .run(&b"second message"[..], Vec::new())
+            .unwrap();
+    }
+
+    #[test]
+    #[should_panic(expected = "nonce reused under the same key")]
+    fn xctr_reuse() {
+        let xctr = XCtr::<RC5_32_12_16>::new(&[1; 16].into());
+        let nonce = [5; 24].into();
+
+        xctr.apply_keystream(&nonce, &mut b"first message".to_vec());
+        xctr.apply_keystream(&nonce, &mut b"second message".to_vec());
+    }
+
+    #[cfg(feature = "etm")]
+    #[test]
+    #[should_panic(expected = "etm: nonce reused under the same key")]
+    fn etm_cbc_reuse() {
+        use aes::Aes128;
+        use cmac::{Cmac, Mac};
+        use rc5::{EtM, EtmMode};
+
+        let etm = EtM::new(
+            rc5(),
+            <Cmac<Aes128> as Mac>::new(&[2; 16].into()),
+            EtmMode::CbcPad,
+        );
+        let iv = [3; 8].into();
+
+        let message = etm.encrypt_with_iv(&iv, b"", b"first message");
+        assert_eq!(etm.encrypt_with_iv(&iv, b"", b"first message"), message);
+        etm.encrypt_with_iv(&iv, b"", b"second message");
+    }
+}
@@ -0,0 +1,119 @@
+#![cfg(feature = "openssl")]
+
+#[cfg(test)]
+mod tests {
+    use aes::Aes128;
+    use rc5::{openssl, RC5_32_12_16};
+
+    const SALT: [u8; 8] = [1, 2, 3, 4, 5, 6, 7, 8];
+
+    fn hex(hex: &str) -> Vec<u8> {
+        (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    #[test]
+    fn bytes_to_key() {
+        // `openssl enc -aes-128-cbc -md md5 -pass pass:secret -P` with and without `-nosalt`
+        let cases = [
+            (
+                Some(&SALT),
+                "c9e5a1bd216dbe1317e230cef48f38ee",
+                "7f0e17ad64022144bccec4a1aa2879ab",
+            ),
+            (
+                None,
+                "5ebe2294ecd0e0f08eab7690d2a6ee69",
+                "26ae5cc854e36b6bdfca366848dea6bb",
+            ),
+        ];
+
+        for (salt, expected_key, expected_iv) in cases {
+            let (mut key, mut iv) = ([0; 16], [0; 16]);
+            openssl::bytes_to_key(b"secret", salt, &mut key, &mut iv);
+            assert_eq!(key[..], hex(expected_key)[..]);
+            assert_eq!(iv[..], hex(expected_iv)[..]);
+
+            // `-rc5` takes the same key and the first 8 bytes as its IV
+            let mut rc5_iv = [0; 8];
+            openssl::bytes_to_key(b"secret", salt, &mut key, &mut rc5_iv);
+            assert_eq!(rc5_iv[..], iv[..8]);
+        }
+    }
+
+    #[test]
+    fn decrypt_openssl_output() {
+        // `openssl enc -aes-128-cbc -md md5 -pass pass:secret`, the format doesn't depend on the
+        // cipher and the system OpenSSL may be built without RC5
+        let encrypted = hex(
+            "53616c7465645f5fec013365087b9de88f5fc4625cd0fb0ae8acbfbbf116d9d585f656500a1e809749d011ba49b35b4e",
+        );
+
+        assert_eq!(
+            openssl::decrypt::<Aes128>(b"secret", &encrypted)
+                .unwrap()
+                .as_slice(),
+            b"openssl enc compatibility"
+        );
+        assert_eq!(
+            openssl::encrypt_with_salt::<Aes128>(
+                b"secret",
+                b"openssl enc compatibility",
+                Some(encrypted[8..16].try_into().unwrap())
+            ),
+            encrypted
+        );
+    }
+
+    #[test]
+    fn roundtrip() {
+        for salt in [Some(&SALT), None] {
+            for len in [0, 7, 8, 100] {
+                let plaintext = vec![0x42; len];
+                let encrypted =
+                    openssl::encrypt_with_salt::<RC5_32_12_16>(b"secret", &plaintext, salt);
+
+                assert_eq!(encrypted.starts_with(b"Salted__"), salt.is_some());
+                assert_eq!(
+                    openssl::decrypt::<RC5_32_12_16>(b"secret", &encrypted)
+                        .unwrap()
+                        .as_slice(),
+                    plaintext
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn invalid_data() {
+        let encrypted = openssl::encrypt_with_salt::<RC5_32_12_16>(b"secret", b"data", Some(&SALT));
+
+        assert_eq!(
+            openssl::decrypt::<RC5_32_12_16>(b"wrong", &encrypted),
+            Err(openssl::DecryptError)
+        );
+        assert!(openssl::decrypt::<RC5_32_12_16>(b"secret", &encrypted[..20]).is_err());
+        assert!(openssl::decrypt::<RC5_32_12_16>(b"secret", b"Salted__1234").is_err());
+        assert!(openssl::decrypt::<RC5_32_12_16>(b"secret", b"").is_err());
+    }
+
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn random_salt() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let mut rng = StdRng::seed_from_u64(5);
+        let first = openssl::encrypt::<RC5_32_12_16>(b"secret", b"data", &mut rng);
+        let second = openssl::encrypt::<RC5_32_12_16>(b"secret", b"data", &mut rng);
+
+        assert_ne!(first[8..16], second[8..16]);
+        assert_eq!(
+            openssl::decrypt::<RC5_32_12_16>(b"secret", &first)
+                .unwrap()
+                .as_slice(),
+            b"data"
+        );
+    }
+}
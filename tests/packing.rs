@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod tests {
+    use rc5::{
+        packing::{block_to_words, words_to_block},
+        Word,
+    };
+
+    #[test]
+    fn little_endian_halves() {
+        let block = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77].into();
+        assert_eq!(block_to_words::<u32>(&block), (0x33221100, 0x77665544));
+
+        let mut packed = Default::default();
+        words_to_block::<u32>(0x33221100, 0x77665544, &mut packed);
+        assert_eq!(packed, block);
+
+        assert_eq!(block_to_words::<u8>(&[0x01, 0x02].into()), (0x01, 0x02));
+    }
+
+    #[test]
+    fn aligned_words() {
+        #[repr(align(4))]
+        struct Aligned([u8; 12]);
+
+        let mut buf = Aligned([
+            0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0, 0, 0,
+        ]);
+        assert_eq!(
+            u32::pair_from_aligned(&buf.0[..8]),
+            Some((0x33221100, 0x77665544))
+        );
+        assert_eq!(u32::pair_from_aligned(&buf.0[1..9]), None);
+        assert_eq!(u32::pair_from_aligned(&buf.0[..4]), None);
+
+        assert!(u32::pair_into_aligned(
+            0x33221100,
+            0x77665544,
+            &mut buf.0[4..]
+        ));
+        assert_eq!(buf.0[4..], [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77]);
+        assert!(!u32::pair_into_aligned(0, 0, &mut buf.0[2..10]));
+        assert_eq!(buf.0[..4], [0x00, 0x11, 0x22, 0x33]);
+    }
+}
@@ -0,0 +1,44 @@
+#[cfg(test)]
+mod tests {
+    use rc5::{pad_padme, padme_len, unpad_padme, InvalidPadding};
+
+    #[test]
+    fn padme_lengths() {
+        // worked out by hand from the algorithm in the PURBs paper
+        let lengths = [
+            (0, 0),
+            (1, 1),
+            (2, 2),
+            (9, 10),
+            (100, 104),
+            (1000, 1024),
+            (1025, 1088),
+            (1_000_000, 1_015_808),
+        ];
+        for (len, padded) in lengths {
+            assert_eq!(padme_len(len), padded, "{len}");
+        }
+
+        for len in 2..100_000 {
+            let padded = padme_len(len);
+            assert!(padded >= len && padded - len <= len * 12 / 100 + 1, "{len}");
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        for len in 0..300 {
+            let data: Vec<u8> = (0..len).map(|i| (i % 3) as u8).collect();
+
+            let mut padded = data.clone();
+            pad_padme(&mut padded);
+            assert_eq!(padded.len(), padme_len(data.len() + 1));
+
+            unpad_padme(&mut padded).unwrap();
+            assert_eq!(padded, data);
+        }
+
+        assert_eq!(unpad_padme(&mut vec![]), Err(InvalidPadding));
+        assert_eq!(unpad_padme(&mut vec![1, 0, 0]), Err(InvalidPadding));
+    }
+}
@@ -0,0 +1,38 @@
+#[cfg(test)]
+mod tests {
+    use cipher::{Block, BlockDecrypt, BlockEncrypt, KeyInit};
+    use rc5::{Keystream, KeystreamMode, ParallelCipher, RC5_32_12_16};
+
+    #[test]
+    fn matches_single_threaded() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let iv = [0xFF; 8].into();
+        let data: Vec<u8> = (0..=255).cycle().take(203).collect();
+
+        for threads in [1, 2, 3, 7, 64] {
+            let parallel = ParallelCipher::new(&rc5).threads(threads);
+
+            let mut expected = data.clone();
+            Keystream::new(&rc5, KeystreamMode::Ctr, &iv).apply(&mut expected);
+            let mut actual = data.clone();
+            parallel.apply_ctr(&iv, &mut actual);
+            assert_eq!(actual, expected);
+
+            let blocks: Vec<Block<RC5_32_12_16>> = data
+                .chunks_exact(8)
+                .map(Block::<RC5_32_12_16>::clone_from_slice)
+                .collect();
+            let mut expected = blocks.clone();
+            rc5.encrypt_blocks(&mut expected);
+            let mut actual = blocks.clone();
+            parallel.encrypt_blocks(&mut actual);
+            assert_eq!(actual, expected);
+
+            rc5.decrypt_blocks(&mut expected);
+            parallel.decrypt_blocks(&mut actual);
+            assert_eq!(actual, blocks);
+        }
+
+        ParallelCipher::new(&rc5).apply_ctr(&iv, &mut []);
+    }
+}
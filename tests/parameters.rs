@@ -0,0 +1,159 @@
+#[cfg(test)]
+mod tests {
+    use cipher::{
+        consts::{U16, U20},
+        BlockEncrypt, KeyInit,
+    };
+    use rc5::{
+        KeystreamMode, ParameterSpecError, ParseParametersError, Rc5, Rc5Mode, Rc5Parameters,
+        RC5_32_12_16,
+    };
+
+    #[test]
+    fn parse() {
+        let cases = [
+            (
+                "RC5-32/20/16-CTR",
+                32,
+                20,
+                Some(16),
+                Some(Rc5Mode::Ctr),
+                None,
+            ),
+            ("RC5-32/12/16", 32, 12, Some(16), None, None),
+            ("RC5/32/12/16", 32, 12, Some(16), None, None),
+            ("rc5/32/12", 32, 12, None, None, None),
+            (
+                "RC5-CBC-Pad/32/16",
+                32,
+                16,
+                None,
+                Some(Rc5Mode::CbcPad),
+                None,
+            ),
+            (
+                "rc5-cbc-pad/32/12/16",
+                32,
+                12,
+                Some(16),
+                Some(Rc5Mode::CbcPad),
+                None,
+            ),
+            (
+                "RC5-CBC-Pad/32/16/b64:AAECAwQFBgcICQoLDA0ODw==",
+                32,
+                16,
+                Some(16),
+                Some(Rc5Mode::CbcPad),
+                Some((0..16).collect::<Vec<u8>>()),
+            ),
+            (
+                "RC5-8/12/4-CBC-Pad/hex:00010203",
+                8,
+                12,
+                Some(4),
+                Some(Rc5Mode::CbcPad),
+                Some(vec![0, 1, 2, 3]),
+            ),
+            // `/` is part of the base64 alphabet
+            (
+                "RC5-32/12/16-CTR/b64:AAAAAAAAAAAAAAAAAAAA/w==",
+                32,
+                12,
+                Some(16),
+                Some(Rc5Mode::Ctr),
+                Some([[0; 15].as_slice(), &[0xFF]].concat()),
+            ),
+            (
+                "RC5/32/12/b64://///////////////////w==",
+                32,
+                12,
+                Some(16),
+                None,
+                Some(vec![0xFF; 16]),
+            ),
+        ];
+
+        for (string, word_size, rounds, key_size, mode, key) in cases {
+            assert_eq!(
+                string.parse::<Rc5Parameters>().unwrap(),
+                Rc5Parameters {
+                    word_size,
+                    rounds,
+                    key_size,
+                    mode,
+                    key,
+                },
+                "{string}"
+            );
+        }
+
+        assert_eq!(Rc5Mode::Ofb.keystream_mode(), Some(KeystreamMode::Ofb));
+        assert_eq!(Rc5Mode::Cts.keystream_mode(), None);
+    }
+
+    #[test]
+    fn invalid_strings() {
+        let cases = [
+            ("RC6-32/20/16", ParseParametersError::Syntax),
+            ("RC5-32/20", ParseParametersError::Syntax),
+            ("RC5-32/20-CTR", ParseParametersError::Syntax),
+            ("RC5/32", ParseParametersError::Syntax),
+            ("RC532/12/16", ParseParametersError::Syntax),
+            ("RC5-32/20/16/8", ParseParametersError::Syntax),
+            ("RC5-hex:00", ParseParametersError::Syntax),
+            ("RC5/+32/12/16", ParseParametersError::Syntax),
+            ("RC5-32/+12/16", ParseParametersError::Syntax),
+            ("RC5-32/12/+16", ParseParametersError::Syntax),
+            ("RC5-32/20/16-XTS", ParseParametersError::UnknownMode),
+            ("RC5-CBC/32/20/hex:0", ParseParametersError::InvalidKey),
+            ("RC5-CBC/32/20/hex:+f", ParseParametersError::InvalidKey),
+            ("RC5-CBC/32/20/hex:0g", ParseParametersError::InvalidKey),
+            ("RC5-CBC/32/20/b64:AA", ParseParametersError::InvalidKey),
+            (
+                "RC5-CBC/32/20/8/hex:00",
+                ParseParametersError::KeyLengthMismatch,
+            ),
+            ("RC5-24/20/16", ParseParametersError::OutOfRange),
+            ("RC5-32/256/16", ParseParametersError::OutOfRange),
+        ];
+
+        for (string, error) in cases {
+            assert_eq!(
+                string.parse::<Rc5Parameters>().unwrap_err(),
+                error,
+                "{string}"
+            );
+        }
+    }
+
+    #[test]
+    fn debug_hides_key() {
+        let parameters: Rc5Parameters = "RC5-32/12/hex:00010203".parse().unwrap();
+        assert!(!format!("{parameters:?}").contains("[0, 1, 2, 3]"));
+    }
+
+    #[test]
+    fn from_parameters() {
+        let parameters: Rc5Parameters = "RC5-CBC/32/12/b64:BAQEBAQEBAQEBAQEBAQEBA=="
+            .parse()
+            .unwrap();
+        let key = parameters.key.clone().unwrap();
+        let rc5 = RC5_32_12_16::from_parameters(&key, &parameters).unwrap();
+
+        let mut block = [0; 8].into();
+        let mut expected = block;
+        rc5.encrypt_block(&mut block);
+        <RC5_32_12_16 as KeyInit>::new(&[4; 16].into()).encrypt_block(&mut expected);
+        assert_eq!(block, expected);
+
+        assert_eq!(
+            Rc5::<u32, U20, U16>::from_parameters(&key, &parameters).err(),
+            Some(ParameterSpecError::RoundsMismatch)
+        );
+        assert_eq!(
+            RC5_32_12_16::from_parameters(&key[..8], &parameters).err(),
+            Some(ParameterSpecError::InvalidKeyLength)
+        );
+    }
+}
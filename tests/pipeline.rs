@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use cipher::KeyInit;
+    use rc5::{CtrPipeline, Keystream, KeystreamMode, RC5_32_12_16};
+
+    #[test]
+    fn matches_sequential_ctr() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let iv = [0, 0, 0, 0, 0, 0, 0xFF, 0xFE].into();
+        let plaintext: Vec<u8> = (0..10_000).map(|i| (i * 7) as u8).collect();
+
+        let mut expected = vec![0; plaintext.len()];
+        Keystream::new(&rc5, KeystreamMode::Ctr, &iv).fill(&mut expected);
+        expected
+            .iter_mut()
+            .zip(&plaintext)
+            .for_each(|(e, p)| *e ^= p);
+
+        for (threads, chunk_size) in [(1, 1), (3, 100), (8, 4096), (4, 1 << 20)] {
+            let pipeline = CtrPipeline::new(&rc5, &iv)
+                .threads(threads)
+                .chunk_size(chunk_size);
+
+            let mut ciphertext = Vec::new();
+            let written = pipeline.run(&plaintext[..], &mut ciphertext).unwrap();
+            assert_eq!(written, plaintext.len() as u64);
+            assert_eq!(ciphertext, expected);
+
+            let mut decrypted = Vec::new();
+            pipeline.run(&ciphertext[..], &mut decrypted).unwrap();
+            assert_eq!(decrypted, plaintext);
+        }
+    }
+
+    #[test]
+    fn ctr_at_offset() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let iv = [0, 0, 0, 0, 0xFF, 0xFF, 0xFF, 0xF0].into();
+
+        let mut keystream = Keystream::new(&rc5, KeystreamMode::Ctr, &iv);
+        let blocks: Vec<_> = (0..300).map(|_| keystream.next_block()).collect();
+
+        for offset in [0, 1, 15, 16, 17, 255, 299] {
+            assert_eq!(
+                Keystream::ctr_at(&rc5, &iv, offset).next_block(),
+                blocks[offset as usize]
+            );
+        }
+    }
+}
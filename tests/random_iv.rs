@@ -0,0 +1,38 @@
+#![cfg(feature = "rand_core")]
+
+#[cfg(test)]
+mod tests {
+    use cipher::KeyInit;
+    use rand::{rngs::StdRng, SeedableRng};
+    use rc5::{CtrPipeline, Keystream, KeystreamMode, RC5_32_12_16};
+
+    #[test]
+    fn keystream_returns_its_iv() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let mut rng = StdRng::seed_from_u64(7);
+
+        for mode in [KeystreamMode::Ctr, KeystreamMode::Ofb] {
+            let (mut keystream, iv) = Keystream::new_with_random_iv(&rc5, mode, &mut rng);
+            let (_, other_iv) = Keystream::new_with_random_iv(&rc5, mode, &mut rng);
+            assert_ne!(iv, other_iv);
+
+            let mut data = *b"attack at dawn";
+            keystream.apply(&mut data);
+            Keystream::new(&rc5, mode, &iv).apply(&mut data);
+            assert_eq!(&data, b"attack at dawn");
+        }
+    }
+
+    #[test]
+    fn pipeline_returns_its_iv() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let (pipeline, iv) = CtrPipeline::new_with_random_iv(&rc5, &mut StdRng::seed_from_u64(7));
+
+        let mut ct = Vec::new();
+        pipeline.run(&b"attack at dawn"[..], &mut ct).unwrap();
+
+        let mut pt = ct.clone();
+        Keystream::new(&rc5, KeystreamMode::Ctr, &iv).apply(&mut pt);
+        assert_eq!(pt, b"attack at dawn");
+    }
+}
@@ -0,0 +1,195 @@
+#![cfg(feature = "research")]
+
+#[cfg(test)]
+mod tests {
+    use cipher::{BlockEncrypt, KeyInit};
+    use rand::{rngs::StdRng, SeedableRng};
+    use rc5::{
+        research::{
+            chosen_pairs, encrypt_rounds, key_schedule, key_schedule_collisions, random_subkeys,
+            write_sts_keystream, Avalanche, Codebook, DifferenceTable, Keystream, KeystreamMode,
+            StsFormat,
+        },
+        RC5_32_12_16,
+    };
+
+    #[test]
+    fn chosen_pairs_have_difference() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let difference = (0x8000_0001u32, 0x10);
+
+        for (p, p_star) in chosen_pairs(difference, 64, &mut rng) {
+            assert_eq!((p.0 ^ p_star.0, p.1 ^ p_star.1), difference);
+        }
+    }
+
+    #[test]
+    fn encrypt_rounds_matches_round_count() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let subkeys = random_subkeys::<u32>(3, &mut rng);
+
+        assert_eq!(subkeys.len(), 8);
+        assert_eq!(
+            encrypt_rounds((0, 0), &subkeys[..2]),
+            (subkeys[0], subkeys[1])
+        );
+    }
+
+    #[test]
+    fn msb_difference_survives_key_whitening() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let subkeys = random_subkeys::<u32>(0, &mut rng);
+        let table = DifferenceTable::collect((1 << 31, 0), &subkeys, 256, &mut rng);
+
+        assert_eq!(table.samples(), 256);
+        assert_eq!(table.probability((1 << 31, 0)), 1.0);
+        assert_eq!(table.most_likely(1), vec![((1 << 31, 0), 1.0)]);
+    }
+
+    #[test]
+    fn key_schedule_matches_cipher() {
+        let key: Vec<u8> = (0..16).collect();
+        let subkeys = key_schedule::<u32>(&key, 12);
+
+        assert_eq!(
+            encrypt_rounds((0x33221100, 0x77665544), &subkeys),
+            (0x9B14DC2D, 0x9E8B08CF)
+        );
+    }
+
+    #[test]
+    fn key_schedule_of_empty_key() {
+        let rc5 =
+            rc5::Rc5::<u32, cipher::consts::U12, cipher::consts::U0>::new(&Default::default());
+        let mut block = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77].into();
+        rc5.encrypt_block(&mut block);
+
+        let subkeys = key_schedule::<u32>(&[], 12);
+        assert_eq!(subkeys, key_schedule::<u32>(&[0; 4], 12));
+        let (a, b) = encrypt_rounds((0x33221100, 0x77665544), &subkeys);
+        assert_eq!(block[..4], a.to_le_bytes());
+        assert_eq!(block[4..], b.to_le_bytes());
+    }
+
+    #[test]
+    fn avalanche_without_rounds_keeps_low_bits() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let avalanche = Avalanche::plaintext::<u32>(16, 0, 32, &mut rng);
+
+        assert_eq!((avalanche.input_bits(), avalanche.output_bits()), (64, 64));
+        for bit in 0..64 {
+            assert_eq!(avalanche.probability(bit, bit), 1.0);
+            assert_eq!(
+                avalanche.probability(bit, bit / 32 * 32),
+                f64::from(bit % 32 == 0)
+            );
+        }
+    }
+
+    #[test]
+    fn full_rounds_diffuse() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let plaintext = Avalanche::plaintext::<u32>(16, 12, 64, &mut rng);
+        let key = Avalanche::key::<u32>(16, 12, 16, &mut rng);
+
+        assert_eq!(key.matrix().len(), 128);
+        assert!((plaintext.mean() - 0.5).abs() < 0.01);
+        assert!((key.mean() - 0.5).abs() < 0.01);
+    }
+
+    #[test]
+    fn keystream_modes() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[7; 16].into());
+        let iv = [0, 0, 0, 0, 0, 0, 0, 0xFF].into();
+
+        let mut first = iv;
+        rc5.encrypt_block(&mut first);
+        let mut ctr_second = [0, 0, 0, 0, 0, 0, 1, 0].into();
+        rc5.encrypt_block(&mut ctr_second);
+        let mut ofb_second = first;
+        rc5.encrypt_block(&mut ofb_second);
+
+        let mut ctr = Keystream::new(&rc5, KeystreamMode::Ctr, &iv);
+        assert_eq!((ctr.next_block(), ctr.next_block()), (first, ctr_second));
+
+        let mut ofb = Keystream::new(&rc5, KeystreamMode::Ofb, &iv);
+        assert_eq!((ofb.next_block(), ofb.next_block()), (first, ofb_second));
+    }
+
+    #[test]
+    fn sts_formats() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[7; 16].into());
+        let iv = Default::default();
+
+        let mut binary = Vec::new();
+        let mut keystream = Keystream::new(&rc5, KeystreamMode::Ctr, &iv);
+        write_sts_keystream(&mut keystream, 104, StsFormat::Binary, &mut binary).unwrap();
+
+        let mut ascii = Vec::new();
+        let mut keystream = Keystream::new(&rc5, KeystreamMode::Ctr, &iv);
+        write_sts_keystream(&mut keystream, 100, StsFormat::Ascii, &mut ascii).unwrap();
+
+        assert_eq!(binary.len(), 13);
+        assert_eq!(ascii.len(), 100);
+        for (bit, char) in ascii.iter().enumerate() {
+            assert_eq!(*char - b'0', binary[bit / 8] >> (7 - bit % 8) & 1);
+        }
+
+        // a partial byte would write bits nobody asked for
+        let mut partial = Vec::new();
+        let mut keystream = Keystream::new(&rc5, KeystreamMode::Ctr, &iv);
+        let error =
+            write_sts_keystream(&mut keystream, 100, StsFormat::Binary, &mut partial).unwrap_err();
+        assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+        assert!(partial.is_empty());
+    }
+
+    #[test]
+    fn rc5_8_vector() {
+        let subkeys = key_schedule::<u8>(&[0x00, 0x01, 0x02, 0x03], 12);
+
+        assert_eq!(encrypt_rounds((0x00, 0x01), &subkeys), (0x21, 0x2A));
+    }
+
+    #[test]
+    fn key_schedule_prefix_collisions() {
+        let collisions = key_schedule_collisions::<u8>(2, 12, 2);
+
+        assert!(!collisions.is_empty());
+        for collision in &collisions {
+            assert!(collision.keys.len() > 1);
+            for key in &collision.keys {
+                assert_eq!(key_schedule::<u8>(key, 12)[..2], collision.prefix[..]);
+            }
+        }
+
+        let keys: usize = collisions.iter().map(|c| c.keys.len()).sum();
+        assert!(keys <= 1 << 16);
+    }
+
+    #[test]
+    fn codebook_is_permutation() {
+        let codebook = Codebook::new(&[0x00, 0x01, 0x02, 0x03], 12);
+
+        assert_eq!(codebook.encrypt(0x0100), 0x2A21);
+
+        let mut sorted = codebook.table().to_vec();
+        sorted.sort_unstable();
+        assert!(sorted.iter().copied().eq(0..=u16::MAX));
+
+        let inverse = codebook.inverse();
+        assert!((0..=u16::MAX).all(|block| inverse[usize::from(codebook.encrypt(block))] == block));
+
+        let cycles = codebook.cycles();
+        assert_eq!(cycles.iter().sum::<usize>(), 1 << 16);
+        assert_eq!(
+            cycles.iter().filter(|len| **len == 1).count(),
+            codebook.fixed_points().len()
+        );
+
+        let mut dump = Vec::new();
+        codebook.dump(&mut dump).unwrap();
+        assert!(dump.starts_with(b"0000 "));
+        assert_eq!(dump.len(), 10 << 16);
+    }
+}
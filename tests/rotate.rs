@@ -0,0 +1,23 @@
+#![cfg(feature = "ct_rotate")]
+
+#[cfg(test)]
+mod tests {
+    use rc5::Word;
+
+    #[test]
+    fn matches_native_rotation() {
+        for x in [0u32, 1, 0x8000_0001, 0xDEAD_BEEF] {
+            for n in [0, 1, 5, 16, 31, 32, 33, 0xFFFF_FFFF] {
+                assert_eq!(Word::rotate_left(x, n), x.rotate_left(n), "{x:x} {n}");
+                assert_eq!(Word::rotate_right(x, n), x.rotate_right(n), "{x:x} {n}");
+            }
+        }
+
+        for x in [0u8, 1, 0x81, 0xA5] {
+            for n in 0..=255 {
+                assert_eq!(Word::rotate_left(x, n), x.rotate_left(n.into()));
+                assert_eq!(Word::rotate_right(x, n), x.rotate_right(n.into()));
+            }
+        }
+    }
+}
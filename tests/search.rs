@@ -0,0 +1,179 @@
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicU64, Ordering};
+
+    use cipher::{Block, BlockEncrypt, KeyInit};
+    use rc5::{
+        search::{Challenge, KeySearch, KeySpace, StatsBlock, CHALLENGE_PLAINTEXT},
+        RC5_32_12_16, RC5_32_12_9,
+    };
+
+    const KEY: [u8; 16] = *b"secret-key-x9q2z";
+
+    fn pairs() -> Vec<(Block<RC5_32_12_16>, Block<RC5_32_12_16>)> {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&KEY.into());
+
+        [*b"known pt", *b"other pt"]
+            .into_iter()
+            .map(|pt| {
+                let mut ct = pt.into();
+                rc5.encrypt_block(&mut ct);
+                (pt.into(), ct)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_key_with_known_prefix() {
+        let pairs = pairs();
+        let space = KeySpace::new(b"0123456789abcdefghijklmnopqrstuvwxyz").with_prefix(&KEY[..13]);
+
+        let tried = AtomicU64::new(0);
+        let progress = |count, total| {
+            assert!(count <= total);
+            tried.fetch_max(count, Ordering::Relaxed);
+        };
+
+        let key = KeySearch::<RC5_32_12_16>::new(space, &pairs)
+            .threads(4)
+            .on_progress(&progress)
+            .run();
+
+        assert_eq!(key.map(|key| key.to_vec()), Some(KEY.to_vec()));
+        assert!(tried.load(Ordering::Relaxed) > 0);
+    }
+
+    #[test]
+    fn finds_key_with_known_bytes() {
+        let pairs = pairs();
+        let mut template = KEY.map(Some);
+        template[2] = None;
+        template[9] = None;
+        template[15] = None;
+        let space =
+            KeySpace::new(b"0123456789abcdefghijklmnopqrstuvwxyz-").with_template(&template);
+
+        assert_eq!(space.len(16), Some(37 * 37 * 37));
+
+        let key = KeySearch::<RC5_32_12_16>::new(space, &pairs)
+            .threads(4)
+            .run();
+
+        assert_eq!(key.map(|key| key.to_vec()), Some(KEY.to_vec()));
+    }
+
+    #[test]
+    fn exhausts_space_without_match() {
+        let pairs = pairs();
+        let space = KeySpace::new(b"abc").with_prefix(&KEY[..12]);
+
+        let key = KeySearch::<RC5_32_12_16>::new(space, &pairs)
+            .threads(3)
+            .run();
+
+        assert!(key.is_none());
+    }
+
+    #[test]
+    fn key_space_len() {
+        let space = KeySpace::full().with_prefix(&[0; 14]);
+
+        assert_eq!(space.len(16), Some(1 << 16));
+        assert_eq!(space.len(13), None);
+        assert_eq!(KeySpace::full().len(16), None);
+
+        let space = KeySpace::full().with_template(&[None, Some(0), None]);
+        assert_eq!(space.len(3), Some(1 << 16));
+        assert_eq!(space.len(2), None);
+    }
+
+    #[test]
+    fn stats_block_order() {
+        let block = StatsBlock::new(0x12_3456_789A).unwrap();
+
+        assert_eq!(block.key(0), [0x12, 0x34, 0x56, 0x78, 0x9A, 0, 0, 0, 0]);
+        assert_eq!(
+            block.key(0x0102),
+            [0x12, 0x34, 0x56, 0x78, 0x9A, 0, 0, 1, 2]
+        );
+        assert_eq!(block.keys().nth(0x0102), Some(block.key(0x0102)));
+        assert_eq!(StatsBlock::containing(&block.key(u32::MAX)), block);
+        assert_eq!(block.key_space().len(9), Some(StatsBlock::KEYS));
+
+        assert_eq!(
+            StatsBlock::new((1 << 40) - 1).unwrap().number(),
+            (1 << 40) - 1
+        );
+        assert_eq!(StatsBlock::new(1 << 40), None);
+    }
+
+    #[test]
+    fn stats_block_follows_client_increment() {
+        // the clients increment `hi` and carry into `mid` and `lo` in byte swapped order
+        let increment = |(hi, mid, lo): (u8, u32, u32)| match hi.checked_add(1) {
+            Some(hi) => (hi, mid, lo),
+            None => match mid.swap_bytes().checked_add(1) {
+                Some(mid) => (0, mid.swap_bytes(), lo),
+                None => (0, 0, lo.swap_bytes().wrapping_add(1).swap_bytes()),
+            },
+        };
+
+        let block = StatsBlock::new(0x12_3456_789A).unwrap();
+        let mut words = StatsBlock::key_words(&block.key(0xFFFE_FF00));
+        for index in 0xFFFE_FF00..=u32::MAX {
+            assert_eq!(
+                StatsBlock::key_from_words(words.0, words.1, words.2),
+                block.key(index)
+            );
+            words = increment(words);
+        }
+        // the last key of the block carries into the next one
+        let next = StatsBlock::new(0x12_3456_789B).unwrap();
+        assert_eq!(
+            StatsBlock::key_from_words(words.0, words.1, words.2),
+            next.key(0)
+        );
+
+        let last = StatsBlock::new((1 << 40) - 1).unwrap();
+        assert_eq!(
+            StatsBlock::key_words(&last.key(u32::MAX)),
+            (0xFF, u32::MAX, u32::MAX)
+        );
+    }
+
+    #[test]
+    fn challenge() {
+        let iv = [0x4A, 0x9F, 0x01, 0xE7, 0x33, 0x5C, 0xB2, 0x08];
+        let block = StatsBlock::new(0xAB_CDEF_0123).unwrap();
+        let key = block.key(300);
+
+        let cipher = <RC5_32_12_9 as KeyInit>::new(&key.into());
+        let mut ciphertext = *CHALLENGE_PLAINTEXT;
+        let mut previous = iv;
+        for chunk in ciphertext.chunks_mut(8) {
+            chunk
+                .iter_mut()
+                .zip(previous)
+                .for_each(|(byte, c)| *byte ^= c);
+            cipher.encrypt_block(chunk.into());
+            previous.copy_from_slice(chunk);
+        }
+
+        let challenge = Challenge::new(&iv, &ciphertext);
+        assert!(challenge.check(&key));
+        assert!(!challenge.check(&block.key(301)));
+
+        // matching the first two blocks isn't enough
+        let mut wrong_tail = ciphertext;
+        wrong_tail[23] ^= 1;
+        assert!(!Challenge::new(&iv, &wrong_tail).check(&key));
+
+        // the search follows the block order, one thread finds the key after 301 candidates
+        let found = challenge.search(block).threads(1).run();
+        assert_eq!(found.map(|key| key.to_vec()), Some(key.to_vec()));
+
+        let space = KeySpace::full().with_prefix(&key[..8]);
+        let found = challenge.search_in(space).threads(1).run();
+        assert_eq!(found.map(|key| key.to_vec()), Some(key.to_vec()));
+    }
+}
@@ -0,0 +1,24 @@
+#![cfg(all(feature = "zeroize", feature = "rand_core"))]
+
+#[cfg(test)]
+mod tests {
+    use rand::{rngs::StdRng, SeedableRng};
+    use rc5::SecureBuffer;
+
+    #[test]
+    fn encrypted_until_read() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let secret = b"correct horse battery staple".to_vec();
+        let buffer: SecureBuffer = SecureBuffer::new(&mut rng, secret.clone());
+
+        assert_eq!(buffer.len(), secret.len());
+        assert_eq!(format!("{buffer:?}"), "SecureBuffer(28 bytes)");
+
+        let guard = buffer.read();
+        assert_eq!(&guard[..], &secret[..]);
+        assert!(!format!("{guard:?}").contains("horse"));
+        drop(guard);
+
+        assert_eq!(&buffer.read()[..], &secret[..]);
+    }
+}
@@ -0,0 +1,49 @@
+#[cfg(test)]
+mod tests {
+    use cipher::KeyInit;
+    use rc5::{keyed_shuffle, keyed_unshuffle, RC5_32_12_16};
+
+    #[test]
+    fn shuffle_is_deterministic_permutation() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[1; 16].into());
+        let original: Vec<u32> = (0..1000).collect();
+
+        let mut first = original.clone();
+        keyed_shuffle(&rc5, &mut first);
+        let mut second = original.clone();
+        keyed_shuffle(&rc5, &mut second);
+
+        assert_eq!(first, second);
+        assert_ne!(first, original);
+
+        let mut sorted = first.clone();
+        sorted.sort_unstable();
+        assert_eq!(sorted, original);
+    }
+
+    #[test]
+    fn keys_give_different_permutations() {
+        let mut a: Vec<u32> = (0..100).collect();
+        let mut b = a.clone();
+
+        keyed_shuffle(&<RC5_32_12_16 as KeyInit>::new(&[1; 16].into()), &mut a);
+        keyed_shuffle(&<RC5_32_12_16 as KeyInit>::new(&[2; 16].into()), &mut b);
+
+        assert_ne!(a, b);
+    }
+
+    #[test]
+    fn unshuffle_restores_order() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[3; 16].into());
+        let original: Vec<char> = "keyed deterministic shuffle".chars().collect();
+
+        let mut shuffled = original.clone();
+        keyed_shuffle(&rc5, &mut shuffled);
+        keyed_unshuffle(&rc5, &mut shuffled);
+
+        assert_eq!(shuffled, original);
+
+        let mut empty: [u8; 0] = [];
+        keyed_shuffle(&rc5, &mut empty);
+    }
+}
@@ -0,0 +1,25 @@
+#![cfg(feature = "simd")]
+
+#[cfg(test)]
+mod tests {
+    use cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+    use rc5::RC5_32_12_16;
+
+    #[test]
+    fn lanes_match_single_blocks() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[5; 16].into());
+
+        let plaintext: Vec<_> = (0..19u8).map(|i| [i; 8].into()).collect();
+        let mut blocks = plaintext.clone();
+        rc5.encrypt_blocks(&mut blocks);
+
+        for (block, plaintext) in blocks.iter().zip(&plaintext) {
+            let mut expected = *plaintext;
+            rc5.encrypt_block(&mut expected);
+            assert_eq!(*block, expected);
+        }
+
+        rc5.decrypt_blocks(&mut blocks);
+        assert_eq!(blocks, plaintext);
+    }
+}
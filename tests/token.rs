@@ -0,0 +1,117 @@
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use cipher::KeyInit;
+    use rc5::{TokenCipher, TokenError, RC5_32_12_16};
+
+    const NOW: u64 = 1_700_000_000;
+
+    fn tokens() -> TokenCipher<RC5_32_12_16> {
+        TokenCipher::new(&[1; 16].into(), &[2; 16].into())
+    }
+
+    #[test]
+    fn roundtrip() {
+        let tokens = tokens();
+
+        for len in [0, 1, 7, 8, 9, 100] {
+            let plaintext: Vec<u8> = (0..len).collect();
+            let token = tokens.encrypt_at(&plaintext, NOW, &[3; 8].into());
+
+            assert!(token
+                .bytes()
+                .all(|c| c.is_ascii_alphanumeric() || c == b'-' || c == b'_'));
+            assert_eq!(
+                tokens
+                    .decrypt_at(&token, None, NOW + 10)
+                    .map(|opened| opened.to_vec()),
+                Ok(plaintext)
+            );
+        }
+    }
+
+    #[test]
+    fn ttl() {
+        let tokens = tokens();
+        let token = tokens.encrypt_at(b"session", NOW, &[3; 8].into());
+        let ttl = Some(Duration::from_secs(3600));
+
+        assert!(tokens.decrypt_at(&token, ttl, NOW + 3600).is_ok());
+        assert_eq!(
+            tokens.decrypt_at(&token, ttl, NOW + 3601),
+            Err(TokenError::Expired)
+        );
+        assert_eq!(
+            tokens.decrypt_at(&token, ttl, NOW - 61),
+            Err(TokenError::Invalid)
+        );
+    }
+
+    #[test]
+    fn rejects_forgeries() {
+        let tokens = tokens();
+        let token = tokens.encrypt_at(b"session", NOW, &[3; 8].into());
+
+        let other = TokenCipher::<RC5_32_12_16>::new(&[1; 16].into(), &[4; 16].into());
+        let other_mac = TokenCipher::<RC5_32_12_16>::new(&[4; 16].into(), &[2; 16].into());
+        assert!(other.decrypt_at(&token, None, NOW).is_err());
+        assert_eq!(
+            other_mac.decrypt_at(&token, None, NOW),
+            Err(TokenError::Invalid)
+        );
+
+        for i in 0..token.len() {
+            let mut modified = token.clone().into_bytes();
+            modified[i] = if modified[i] == b'A' { b'B' } else { b'A' };
+            let modified = String::from_utf8(modified).unwrap();
+            assert_eq!(
+                tokens.decrypt_at(&modified, None, NOW),
+                Err(TokenError::Invalid)
+            );
+        }
+
+        assert_eq!(
+            tokens.decrypt_at(&token[..token.len() - 11], None, NOW),
+            Err(TokenError::Invalid)
+        );
+        assert_eq!(tokens.decrypt_at("", None, NOW), Err(TokenError::Invalid));
+    }
+
+    #[test]
+    fn combined_key() {
+        let mut key = [1; 32];
+        key[16..].fill(2);
+        let token = tokens().encrypt_at(b"session", NOW, &[3; 8].into());
+
+        let tokens = <TokenCipher<RC5_32_12_16> as KeyInit>::new(&key.into());
+        assert_eq!(
+            tokens
+                .decrypt_at(&token, None, NOW)
+                .map(|opened| opened.to_vec()),
+            Ok(b"session".to_vec())
+        );
+    }
+
+    #[cfg(feature = "rand_core")]
+    #[test]
+    fn random_iv() {
+        use rand::{rngs::StdRng, SeedableRng};
+
+        let tokens = tokens();
+        let mut rng = StdRng::seed_from_u64(5);
+        let (a, b) = (
+            tokens.encrypt(b"session", &mut rng),
+            tokens.encrypt(b"session", &mut rng),
+        );
+
+        assert_ne!(a, b);
+        assert_eq!(
+            tokens
+                .decrypt(&a, Some(Duration::from_secs(60)))
+                .unwrap()
+                .as_slice(),
+            b"session"
+        );
+    }
+}
@@ -0,0 +1,142 @@
+#![cfg(feature = "tracing")]
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        fmt::Debug,
+        sync::{
+            atomic::{AtomicU64, Ordering},
+            Mutex, OnceLock,
+        },
+        thread::{self, ThreadId},
+    };
+
+    use cipher::KeyInit;
+    use rc5::{Keystream, KeystreamMode, ParallelCipher, TokenCipher, TokenError, RC5_32_12_16};
+    use tracing::{
+        field::{Field, Visit},
+        span, Event, Metadata, Subscriber,
+    };
+
+    /// Spans and events as `name{field=value ...}` lines per thread, in creation order.
+    ///
+    /// Scoped subscribers need the `std` feature of `tracing`, so this one is the global default
+    /// and tests only look at the lines of their own thread.
+    #[derive(Default)]
+    struct Captured {
+        lines: Mutex<Vec<(ThreadId, String)>>,
+        next_id: AtomicU64,
+    }
+
+    impl Captured {
+        fn get() -> &'static Self {
+            static CAPTURED: OnceLock<&'static Captured> = OnceLock::new();
+
+            CAPTURED.get_or_init(|| {
+                let captured: &'static Captured = Box::leak(Box::default());
+                tracing::subscriber::set_global_default(captured).unwrap();
+                captured
+            })
+        }
+
+        fn push(&self, line: String) {
+            self.lines
+                .lock()
+                .unwrap()
+                .push((thread::current().id(), line));
+        }
+
+        /// Lines of the current thread.
+        fn lines(&self) -> Vec<String> {
+            let current = thread::current().id();
+            self.lines
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|(thread, _)| *thread == current)
+                .map(|(_, line)| line.clone())
+                .collect()
+        }
+    }
+
+    struct Fields(Vec<String>);
+
+    impl Visit for Fields {
+        fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+            self.0.push(format!("{}={value:?}", field.name()));
+        }
+    }
+
+    impl Subscriber for &'static Captured {
+        fn enabled(&self, _: &Metadata<'_>) -> bool {
+            true
+        }
+
+        fn new_span(&self, span: &span::Attributes<'_>) -> span::Id {
+            let mut fields = Fields(Vec::new());
+            span.record(&mut fields);
+            let line = format!("{}{{{}}}", span.metadata().name(), fields.0.join(" "));
+            self.push(line);
+
+            span::Id::from_u64(self.next_id.fetch_add(1, Ordering::Relaxed) + 1)
+        }
+
+        fn record(&self, _: &span::Id, _: &span::Record<'_>) {}
+
+        fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+
+        fn event(&self, event: &Event<'_>) {
+            let mut fields = Fields(Vec::new());
+            event.record(&mut fields);
+            let line = format!("{}{{{}}}", event.metadata().level(), fields.0.join(" "));
+            self.push(line);
+        }
+
+        fn enter(&self, _: &span::Id) {}
+
+        fn exit(&self, _: &span::Id) {}
+    }
+
+    #[test]
+    fn bulk_spans() {
+        let captured = Captured::get();
+        let rc5 = RC5_32_12_16::new(&[1; 16].into());
+
+        Keystream::new(&rc5, KeystreamMode::Ctr, &[2; 8].into()).apply(&mut [0; 100]);
+        let parallel = ParallelCipher::new(&rc5).threads(2);
+        parallel.encrypt_blocks(&mut [[0; 8].into(); 5]);
+        parallel.apply_ctr(&[2; 8].into(), &mut [0; 30]);
+
+        assert_eq!(
+            captured.lines(),
+            [
+                "rc5::key_setup{}",
+                "rc5::keystream{mode=Ctr bytes=100}",
+                "rc5::parallel{op=\"encrypt\" bytes=40 threads=2}",
+                "rc5::parallel{op=\"ctr\" bytes=30 threads=2}",
+            ]
+        );
+    }
+
+    #[test]
+    fn authentication_failures() {
+        let captured = Captured::get();
+        let tokens = TokenCipher::<RC5_32_12_16>::new(&[1; 16].into(), &[2; 16].into());
+        let token = tokens.encrypt_at(b"session", 0, &[3; 8].into());
+        let skip = captured.lines().len();
+
+        let mut tampered = token.into_bytes();
+        tampered[20] = if tampered[20] == b'A' { b'B' } else { b'A' };
+        let tampered = String::from_utf8(tampered).unwrap();
+        assert_eq!(
+            tokens.decrypt_at(&tampered, None, 0),
+            Err(TokenError::Invalid)
+        );
+
+        // the scheme only, nothing about the key or the data
+        assert_eq!(
+            captured.lines()[skip..],
+            ["WARN{message=authentication failed scheme=\"token\"}"]
+        );
+    }
+}
@@ -0,0 +1,43 @@
+#[cfg(test)]
+mod tests {
+    use cipher::{BlockDecrypt, BlockEncrypt, KeyInit};
+    use rc5::{RC5_32_12_16, RC5_EDE3_32_12_16};
+
+    fn key(k1: u8, k2: u8, k3: u8) -> [u8; 48] {
+        let mut key = [0; 48];
+        key[..16].fill(k1);
+        key[16..32].fill(k2);
+        key[32..].fill(k3);
+        key
+    }
+
+    #[test]
+    fn single_key_compatible() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[3; 16].into());
+        let mut expected = [1, 2, 3, 4, 5, 6, 7, 8].into();
+        rc5.encrypt_block(&mut expected);
+
+        for key in [key(9, 9, 3), key(3, 9, 9), key(3, 3, 3)] {
+            let ede = <RC5_EDE3_32_12_16 as KeyInit>::new(&key.into());
+            let mut block = [1, 2, 3, 4, 5, 6, 7, 8].into();
+            ede.encrypt_block(&mut block);
+
+            assert_eq!(block, expected);
+        }
+    }
+
+    #[test]
+    fn roundtrip() {
+        let ede = <RC5_EDE3_32_12_16 as KeyInit>::new(&key(1, 2, 3).into());
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[3; 16].into());
+
+        let mut block = [1, 2, 3, 4, 5, 6, 7, 8].into();
+        let mut single = block;
+        ede.encrypt_block(&mut block);
+        rc5.encrypt_block(&mut single);
+        assert_ne!(block, single);
+
+        ede.decrypt_block(&mut block);
+        assert_eq!(block, [1, 2, 3, 4, 5, 6, 7, 8].into());
+    }
+}
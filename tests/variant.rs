@@ -0,0 +1,82 @@
+#[cfg(test)]
+mod tests {
+    use std::fmt;
+
+    use cipher::{
+        consts::{U10, U16, U20, U4},
+        AlgorithmName, Block, Key,
+    };
+    use rc5::{Rc5, Rc5Variant, RC5_32_12_16};
+
+    struct Name<C>(std::marker::PhantomData<C>);
+
+    impl<C: AlgorithmName> fmt::Display for Name<C> {
+        fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+            C::write_alg_name(f)
+        }
+    }
+
+    fn name<C: AlgorithmName>() -> String {
+        Name::<C>(std::marker::PhantomData).to_string()
+    }
+
+    fn roundtrip<V: Rc5Variant>(key: &Key<V>, pt: &Block<V>) -> Block<V> {
+        let cipher = V::new(key);
+        let mut block = pt.clone();
+
+        cipher.encrypt_block(&mut block);
+        let ct = block.clone();
+        cipher.decrypt_block(&mut block);
+        assert_eq!(&block, pt);
+
+        ct
+    }
+
+    #[test]
+    fn generic_over_variant() {
+        let key = [
+            0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0A, 0x0B, 0x0C, 0x0D,
+            0x0E, 0x0F,
+        ];
+        let pt = [0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77];
+        let ct = [0x2D, 0xDC, 0x14, 0x9B, 0xCF, 0x08, 0x8B, 0x9E];
+
+        assert_eq!(
+            roundtrip::<RC5_32_12_16>(&key.into(), &pt.into())[..],
+            ct[..]
+        );
+    }
+
+    #[cfg(feature = "core")]
+    #[test]
+    fn core_types_from_variant() {
+        use rc5::{core::RC5, ExpandedKeyTable, Rc5};
+
+        fn key_table<V: Rc5Variant>(
+            key: &rc5::Key<<V as Rc5Variant>::KeySize>,
+        ) -> ExpandedKeyTable<V::Word, V::Rounds> {
+            Rc5::<V::Word, V::Rounds, <V as Rc5Variant>::KeySize>::substitute_key(key)
+        }
+
+        let table = key_table::<RC5_32_12_16>(&[0; 16].into());
+        assert_eq!(table.len(), 26);
+
+        assert!(rc5::core::key_tables_ct_eq(
+            &table,
+            &key_table::<RC5_32_12_16>(&[0; 16].into())
+        ));
+        assert!(!rc5::core::key_tables_ct_eq(
+            &table,
+            &key_table::<RC5_32_12_16>(&[1; 16].into())
+        ));
+        assert!(!rc5::core::key_tables_ct_eq(&table, &table[1..]));
+    }
+
+    #[test]
+    fn algorithm_name_from_parameters() {
+        assert_eq!(name::<RC5_32_12_16>(), "RC5-32/12/16");
+        assert_eq!(name::<Rc5<u32, U20, U16>>(), "RC5-32/20/16");
+        assert_eq!(name::<Rc5<u8, U16, U4>>(), "RC5-8/16/4");
+        assert_eq!(name::<Rc5<u32, U16, U10>>(), "RC5-32/16/10");
+    }
+}
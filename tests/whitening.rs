@@ -0,0 +1,55 @@
+#[cfg(test)]
+mod tests {
+    use cipher::{AlgorithmName, BlockDecrypt, BlockEncrypt, KeyInit};
+    use rc5::{RC5X_32_12_16, RC5_32_12_16};
+
+    fn key(cipher_key: [u8; 16], pre: [u8; 8], post: [u8; 8]) -> [u8; 32] {
+        let mut key = [0; 32];
+        key[..16].copy_from_slice(&cipher_key);
+        key[16..24].copy_from_slice(&pre);
+        key[24..].copy_from_slice(&post);
+        key
+    }
+
+    #[test]
+    fn zero_whitening_is_rc5() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[7; 16].into());
+        let rc5x = <RC5X_32_12_16 as KeyInit>::new(&key([7; 16], [0; 8], [0; 8]).into());
+
+        let mut expected = [1, 2, 3, 4, 5, 6, 7, 8].into();
+        let mut block = expected;
+        rc5.encrypt_block(&mut expected);
+        rc5x.encrypt_block(&mut block);
+
+        assert_eq!(block, expected);
+    }
+
+    #[test]
+    fn whitening() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&[7; 16].into());
+        let rc5x = <RC5X_32_12_16 as KeyInit>::new(&key([7; 16], [0xA5; 8], [0x3C; 8]).into());
+
+        let mut expected = [0xA5 ^ 1, 0xA5 ^ 2, 0xA5 ^ 3, 0xA5, 0xA5, 0xA5, 0xA5, 0xA5].into();
+        rc5.encrypt_block(&mut expected);
+        expected.iter_mut().for_each(|b| *b ^= 0x3C);
+
+        let mut block = [1, 2, 3, 0, 0, 0, 0, 0].into();
+        rc5x.encrypt_block(&mut block);
+        assert_eq!(block, expected);
+
+        rc5x.decrypt_block(&mut block);
+        assert_eq!(block, [1, 2, 3, 0, 0, 0, 0, 0].into());
+    }
+
+    #[test]
+    fn algorithm_name() {
+        struct Name;
+        impl std::fmt::Display for Name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                RC5X_32_12_16::write_alg_name(f)
+            }
+        }
+
+        assert_eq!(Name.to_string(), "RC5X<RC5-32/12/16>");
+    }
+}
@@ -0,0 +1,50 @@
+#[cfg(test)]
+mod tests {
+    use cipher::{BlockEncrypt, KeyInit};
+    use rc5::{Keystream, KeystreamMode, XCtr, XNonce, RC5_32_12_16};
+
+    #[test]
+    fn roundtrip() {
+        let xctr = XCtr::<RC5_32_12_16>::new(&[1; 16].into());
+        let nonce: XNonce<RC5_32_12_16> = (0..24).collect();
+        let plaintext: Vec<u8> = (0..100).collect();
+
+        let mut data = plaintext.clone();
+        xctr.apply_keystream(&nonce, &mut data);
+        assert_ne!(data, plaintext);
+
+        let (cipher, iv) = xctr.subkey(&nonce);
+        assert_eq!(iv.as_slice(), &nonce[16..]);
+        let mut expected = plaintext.clone();
+        Keystream::new(&cipher, KeystreamMode::Ctr, &iv).apply(&mut expected);
+        assert_eq!(data, expected);
+
+        xctr.apply_keystream(&nonce, &mut data);
+        assert_eq!(data, plaintext);
+    }
+
+    #[test]
+    fn subkey_per_nonce() {
+        let xctr = XCtr::<RC5_32_12_16>::new(&[1; 16].into());
+        let block = |nonce: &XNonce<RC5_32_12_16>| {
+            let (cipher, _) = xctr.subkey(nonce);
+            let mut block = [0; 8].into();
+            cipher.encrypt_block(&mut block);
+            block
+        };
+
+        let nonce = XNonce::<RC5_32_12_16>::default();
+        let mut other_iv = nonce;
+        other_iv[23] = 1;
+        let mut other_prefix = nonce;
+        other_prefix[0] = 1;
+
+        assert_eq!(block(&nonce), block(&other_iv));
+        assert_ne!(block(&nonce), block(&other_prefix));
+
+        // the subkey isn't the master key
+        let mut master = [0; 8].into();
+        RC5_32_12_16::new(&[1; 16].into()).encrypt_block(&mut master);
+        assert_ne!(block(&nonce), master);
+    }
+}
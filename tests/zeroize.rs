@@ -0,0 +1,151 @@
+#![cfg(feature = "zeroize")]
+
+// Checks that secrets are really gone after drop instead of trusting the `Drop` impls: stack
+// values are dropped in place and their memory inspected, heap buffers are inspected by the
+// allocator when they are freed.
+
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    sync::atomic::{AtomicBool, AtomicU64, Ordering},
+};
+
+/// Key bytes unlikely to appear anywhere else in the test process.
+const SECRET: [u8; 16] = *b"\x9e\x11zeroize-me!\x42\x77\xa5";
+
+/// First 16 bytes of the expanded key table of [SECRET], once a test has computed it.
+static TABLE: [AtomicU64; 2] = [AtomicU64::new(0), AtomicU64::new(0)];
+
+static LEAKED: AtomicBool = AtomicBool::new(false);
+
+/// Reports freed heap blocks still holding [SECRET] or the start of its key table.
+struct Inspecting;
+
+unsafe impl GlobalAlloc for Inspecting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        let mut table = [0; 16];
+        table[..8].copy_from_slice(&TABLE[0].load(Ordering::SeqCst).to_ne_bytes());
+        table[8..].copy_from_slice(&TABLE[1].load(Ordering::SeqCst).to_ne_bytes());
+
+        let block = std::slice::from_raw_parts(ptr, layout.size());
+        if block
+            .windows(SECRET.len())
+            .any(|window| window == SECRET || (table != [0; 16] && window == table))
+        {
+            LEAKED.store(true, Ordering::SeqCst);
+        }
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: Inspecting = Inspecting;
+
+#[cfg(test)]
+mod tests {
+    use std::mem::{size_of, MaybeUninit};
+
+    use cipher::{consts::U16, KeyInit};
+    use rc5::{
+        keyfile, DeterministicCipher, InvalidCiphertext, Rc5Key, TokenCipher, RC5X_32_12_16,
+        RC5_32_12_16,
+    };
+
+    use super::*;
+
+    /// Drop a value in place and return the memory it occupied.
+    fn bytes_after_drop<T>(value: T) -> Vec<u8> {
+        let mut slot = MaybeUninit::new(value);
+        // SAFETY: the slot holds an initialized value which is dropped once, afterwards its
+        // memory is only read as bytes
+        unsafe {
+            slot.assume_init_drop();
+            let bytes = slot.as_ptr() as *const u8;
+            (0..size_of::<T>())
+                .map(|i| std::ptr::read_volatile(bytes.add(i)))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn stack_values() {
+        let rc5 = <RC5_32_12_16 as KeyInit>::new(&SECRET.into());
+        assert!(bytes_after_drop(rc5).iter().all(|&byte| byte == 0));
+
+        let mut key = [0; 32];
+        key[..16].copy_from_slice(&SECRET);
+        key[16..].copy_from_slice(&SECRET);
+        let rc5x = <RC5X_32_12_16 as KeyInit>::new(&key.into());
+        assert!(bytes_after_drop(rc5x).iter().all(|&byte| byte == 0));
+
+        let key = Rc5Key::<U16>::from(SECRET);
+        assert!(bytes_after_drop(key).iter().all(|&byte| byte == 0));
+    }
+
+    #[test]
+    fn heap_buffers() {
+        #[cfg(feature = "rand_core")]
+        {
+            use rand::{rngs::StdRng, SeedableRng};
+
+            let mut rng = StdRng::seed_from_u64(1);
+            let buffer: rc5::SecureBuffer = rc5::SecureBuffer::new(&mut rng, SECRET.to_vec());
+            let guard = buffer.read();
+            assert_eq!(guard[..], SECRET);
+            drop(guard);
+        }
+
+        let key: Rc5Key<U16> = keyfile::parse(&SECRET, keyfile::KeyFormat::Raw).unwrap();
+        drop(key);
+
+        // the batch key schedule works on interleaved copies of the tables on the heap, with one
+        // key they hold the table as is
+        #[cfg(feature = "core")]
+        {
+            use cipher::consts::U12;
+            use rc5::core::RC5;
+
+            let table = <RC5_32_12_16 as RC5<u32, U12, U16>>::substitute_key(&SECRET.into());
+            let mut start = [0; 16];
+            for (bytes, word) in start.chunks_mut(4).zip(&table) {
+                bytes.copy_from_slice(&word.to_ne_bytes());
+            }
+            TABLE[0].store(
+                u64::from_ne_bytes(start[..8].try_into().unwrap()),
+                Ordering::SeqCst,
+            );
+            TABLE[1].store(
+                u64::from_ne_bytes(start[8..].try_into().unwrap()),
+                Ordering::SeqCst,
+            );
+
+            let [rc5] = RC5_32_12_16::new_batch(&[SECRET.into()]);
+            drop(rc5);
+        }
+
+        let tokens = TokenCipher::<RC5_32_12_16>::new(&[2; 16].into(), &[3; 16].into());
+        let token = tokens.encrypt_at(&SECRET, 0, &[4; 8].into());
+        let plaintext = tokens.decrypt_at(&token, None, 0).unwrap();
+        assert_eq!(plaintext[..], SECRET);
+        drop(plaintext);
+
+        // S2V MACs everything but the last block of the plaintext, a failed decryption has
+        // decrypted the whole ciphertext
+        let siv = DeterministicCipher::<RC5_32_12_16>::new(&[2; 16].into(), &[3; 16].into());
+        let mut field = [0; 24];
+        field[..16].copy_from_slice(&SECRET);
+        let sealed = siv.encrypt(&[b"users.email"], &field);
+        assert_eq!(
+            siv.decrypt(&[b"users.name"], &sealed),
+            Err(InvalidCiphertext)
+        );
+        let opened = siv.decrypt(&[b"users.email"], &sealed).unwrap();
+        assert_eq!(opened[..], field);
+        drop(opened);
+
+        assert!(!LEAKED.load(Ordering::SeqCst));
+    }
+}